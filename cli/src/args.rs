@@ -0,0 +1,245 @@
+use crate::error::Error;
+use nwn_lib::files::gff::path::FieldPath;
+use nwn_model::ids::{class::Class, spell::Spell};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: nwn2-charedit-cli <COMMAND> <PATH> [OPTIONS]
+
+PATH is a save folder, a resgff.zip, or a .ifo/.bic/.ros/.rst file
+
+Commands:
+  dump                           Print every field of a GFF file
+  get <FIELD>                    Print a field, e.g. Mod_PlayerList[0].ClassList[0].Class
+  set <FIELD> <VALUE>            Change a field, the value is parsed as the field's type
+  list-players                   List the characters in the file
+  add-feat <FEAT>                Add a feat by id
+  remove-feat <FEAT>             Remove a feat by id
+  add-spell <CLASS> <LEVEL> <SPELL>
+                                 Add a known spell, CLASS and SPELL are names or ids
+  set-ability <ABILITY> <VALUE>  Set str, dex, con, int, wis or cha
+  export <BIC>                   Write a character to a .bic file
+  import <BIC>                   Replace a character in a player list with a .bic file
+
+Options:
+  --file <NAME>     File in a save folder or resgff.zip, defaults to playerlist.ifo
+  --player <INDEX>  Character to change, defaults to 0
+  --output <PATH>   Write changes to PATH instead of back to the input
+  --json            Print dump and get output as JSON
+  -h, --help        Print this message";
+
+common::open_enum! {
+    pub enum Ability: u8 {
+        Str = 0,
+        Dex = 1,
+        Con = 2,
+        Int = 3,
+        Wis = 4,
+        Cha = 5,
+    }
+}
+impl Ability {
+    /// Field label in a creature struct
+    pub fn label(&self) -> Option<&'static str> {
+        match *self {
+            Self::Str => Some("Str"),
+            Self::Dex => Some("Dex"),
+            Self::Con => Some("Con"),
+            Self::Int => Some("Int"),
+            Self::Wis => Some("Wis"),
+            Self::Cha => Some("Cha"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Dump,
+    Get(FieldPath),
+    Set(FieldPath, String),
+    ListPlayers,
+    AddFeat(u16),
+    RemoveFeat(u16),
+    AddSpell {
+        class: Class,
+        level: usize,
+        spell: Spell,
+    },
+    SetAbility(Ability, u8),
+    Export(PathBuf),
+    Import(PathBuf),
+}
+impl Command {
+    /// Whether the command changes the input
+    pub fn is_edit(&self) -> bool {
+        !matches!(
+            self,
+            Self::Dump | Self::Get(_) | Self::ListPlayers | Self::Export(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub path: PathBuf,
+    pub file: Option<String>,
+    pub player: usize,
+    pub output: Option<PathBuf>,
+    pub json: bool,
+}
+
+fn usage(msg: impl std::fmt::Display) -> Error {
+    Error::Usage(msg.to_string())
+}
+
+fn parse_arg<T>(name: &str, value: &str) -> Result<T, Error>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e| usage(format!("Invalid {name} {value:?}: {e}")))
+}
+
+impl Args {
+    /// Parses the arguments after the program name, `None` if help was requested
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, Error> {
+        let mut positional = vec![];
+        let mut file = None;
+        let mut player = 0;
+        let mut output = None;
+        let mut json = false;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| usage(format!("Missing value for {name}")))
+            };
+
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--file" => file = Some(value("--file")?),
+                "--player" => player = parse_arg("player index", &value("--player")?)?,
+                "--output" => output = Some(PathBuf::from(value("--output")?)),
+                "--json" => json = true,
+                x if x.starts_with("--") => return Err(usage(format!("Unknown option {x}"))),
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let mut next = |name: &str| {
+            positional
+                .next()
+                .ok_or_else(|| usage(format!("Missing {name}")))
+        };
+
+        let command_name = next("command")?;
+        let path = PathBuf::from(next("path")?);
+
+        let command = match command_name.as_str() {
+            "dump" => Command::Dump,
+            "get" => Command::Get(parse_arg("field", &next("field")?)?),
+            "set" => Command::Set(parse_arg("field", &next("field")?)?, next("value")?),
+            "list-players" => Command::ListPlayers,
+            "add-feat" => Command::AddFeat(parse_arg("feat", &next("feat")?)?),
+            "remove-feat" => Command::RemoveFeat(parse_arg("feat", &next("feat")?)?),
+            "add-spell" => Command::AddSpell {
+                class: parse_arg("class", &next("class")?)?,
+                level: parse_arg("spell level", &next("spell level")?)?,
+                spell: parse_arg("spell", &next("spell")?)?,
+            },
+            "set-ability" => {
+                let ability: Ability = parse_arg("ability", &next("ability")?)?;
+                if ability.label().is_none() {
+                    return Err(usage(format!("Unknown ability {ability}")));
+                }
+
+                Command::SetAbility(ability, parse_arg("ability score", &next("value")?)?)
+            }
+            "export" => Command::Export(next("bic path")?.into()),
+            "import" => Command::Import(next("bic path")?.into()),
+            x => return Err(usage(format!("Unknown command {x}"))),
+        };
+
+        if let Some(x) = positional.next() {
+            return Err(usage(format!("Unexpected argument {x}")));
+        }
+
+        Ok(Some(Self {
+            command,
+            path,
+            file,
+            player,
+            output,
+            json,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Option<Args>, Error> {
+        Args::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parse_commands() {
+        let args = parse("add-spell save --player 1 Sorcerer 3 fireball")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            args.command,
+            Command::AddSpell {
+                class: Class::Sorcerer,
+                level: 3,
+                spell: Spell::Fireball,
+            }
+        );
+        assert_eq!(args.path, PathBuf::from("save"));
+        assert_eq!(args.player, 1);
+
+        let args = parse("set-ability player.bic con 14 --output out.bic")
+            .unwrap()
+            .unwrap();
+        assert_eq!(args.command, Command::SetAbility(Ability::Con, 14));
+        assert_eq!(args.output, Some(PathBuf::from("out.bic")));
+
+        let args = parse("get resgff.zip --file bishop.ros Str --json")
+            .unwrap()
+            .unwrap();
+        assert_eq!(args.command, Command::Get("Str".parse().unwrap()));
+        assert_eq!(args.file.as_deref(), Some("bishop.ros"));
+        assert!(args.json);
+
+        assert_eq!(parse("dump save --help").unwrap(), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        for args in [
+            "",
+            "dump",
+            "frobnicate save",
+            "get save",
+            "dump save extra",
+            "add-feat save feat",
+            "set-ability save luck 10",
+            "set-ability save str 256",
+            "dump save --player",
+            "dump save --verbose",
+        ] {
+            assert!(
+                matches!(parse(args), Err(Error::Usage(_))),
+                "Expected an error for {args:?}"
+            );
+        }
+    }
+}
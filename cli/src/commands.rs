@@ -0,0 +1,220 @@
+use crate::{
+    args::{Ability, Args, Command},
+    dump,
+    error::Error,
+    target::{Target, creatures, is_player_list},
+};
+use nwn_lib::{
+    files::gff::{
+        Gff,
+        field::Field,
+        path::FieldPath,
+        r#struct::{Struct, StructField},
+    },
+    save::{import_player, player_to_bic, read_bic, write_bic},
+};
+use nwn_model::{
+    field_ref::FieldRef,
+    ids::{class::Class, spell::Spell},
+    player::{feat_list::FeatList, player_class::PlayerClass},
+};
+use std::{io::Write, path::Path};
+
+fn usage(msg: impl std::fmt::Display) -> Error {
+    Error::Usage(msg.to_string())
+}
+
+fn find_field(s: &Struct, label: &str) -> Result<StructField, Error> {
+    s.find_direct(label)
+        .ok_or_else(|| usage(format!("Character has no {label} field")))
+}
+
+fn creature(gff: &Gff, index: usize) -> Result<Struct, Error> {
+    let mut creatures = creatures(gff);
+    let count = creatures.len();
+
+    if index >= count {
+        return Err(usage(format!(
+            "Player {index} not found, the file has {count}"
+        )));
+    }
+
+    Ok(creatures.swap_remove(index))
+}
+
+fn loc_string(s: &Struct, label: &str) -> Option<String> {
+    s.find_direct(label)?.read_field(|f| match f {
+        Field::ExoLocString(x) => x.substrings.first().map(|x| x.data.clone()),
+        _ => None,
+    })
+}
+
+fn classes(s: &Struct) -> Result<Vec<PlayerClass>, Error> {
+    let list = find_field(s, "ClassList")?;
+    let entries = list.read_field(|f| f.expect_list().cloned())?;
+
+    entries
+        .iter()
+        .map(|x| PlayerClass::new(x).map_err(Error::from))
+        .collect()
+}
+
+/// `First Last (Class level, ...)`, falling back to the tag without a name
+fn describe(s: &Struct) -> Result<String, Error> {
+    let name = [loc_string(s, "FirstName"), loc_string(s, "LastName")]
+        .into_iter()
+        .flatten()
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let name = match name.is_empty() {
+        true => s
+            .find_direct("Tag")
+            .and_then(|x| x.read_field(|f| f.try_exostring().map(|x| x.0.clone())))
+            .unwrap_or_default(),
+        false => name,
+    };
+
+    let classes = classes(s)?
+        .iter()
+        .map(|x| format!("{} {}", x.class.get(), x.level.get()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(format!("{name} ({classes})"))
+}
+
+fn feat_list(s: &Struct) -> Result<FeatList, Error> {
+    Ok(FeatList::from_field(find_field(s, "FeatList")?)?)
+}
+
+fn add_feat(s: &Struct, feat: u16) -> Result<(), Error> {
+    let mut feats = feat_list(s)?;
+
+    if feats.list_ref.get().iter().any(|x| *x.get() == feat) {
+        return Err(usage(format!("Character already has feat {feat}")));
+    }
+
+    feats.add_feat(feat);
+    Ok(())
+}
+
+fn remove_feat(s: &Struct, feat: u16) -> Result<(), Error> {
+    let mut feats = feat_list(s)?;
+
+    let index = feats
+        .list_ref
+        .get()
+        .iter()
+        .position(|x| *x.get() == feat)
+        .ok_or_else(|| usage(format!("Character doesn't have feat {feat}")))?;
+
+    feats.remove_feat(index);
+    Ok(())
+}
+
+fn add_spell(s: &Struct, class: Class, level: usize, spell: Spell) -> Result<(), Error> {
+    let mut class = classes(s)?
+        .into_iter()
+        .find(|x| *x.class.get() == class)
+        .ok_or_else(|| usage(format!("Character has no {class} levels")))?;
+
+    let known = class
+        .spell_known_list
+        .get_mut(level)
+        .and_then(Option::as_mut)
+        .ok_or_else(|| {
+            usage(format!(
+                "{} has no known spell list for level {level}",
+                class.class.get()
+            ))
+        })?;
+
+    if known.spells.contains(&spell) {
+        return Err(usage(format!("{spell} is already known")));
+    }
+
+    known.add_spell(spell)?;
+    Ok(())
+}
+
+fn set_ability(s: &Struct, ability: Ability, value: u8) -> Result<(), Error> {
+    let label = ability
+        .label()
+        .ok_or_else(|| usage(format!("Unknown ability {ability}")))?;
+
+    let mut field = FieldRef::new(find_field(s, label)?, Field::expect_byte)?;
+    field.set(value, |x| Field::Byte(*x));
+    Ok(())
+}
+
+fn set_field(gff: &Gff, path: &FieldPath, value: &str) -> Result<(), Error> {
+    let field = gff.root.find_path(path)?;
+    let new_field = field.read_field(|f| f.parse_as(value))?;
+
+    field.write().expect("Field poisoned").field = new_field;
+    Ok(())
+}
+
+fn import(target: &Target, gff: &Gff, index: usize, bic: &Path) -> Result<(), Error> {
+    if !is_player_list(gff) {
+        let msg = match target {
+            Target::Gff { .. } => "import needs a player list, copy the .bic instead",
+            _ => "import needs a player list, pick one with --file",
+        };
+        return Err(usage(msg));
+    }
+
+    import_player(gff, index, &read_bic(bic)?)?;
+    Ok(())
+}
+
+/// Runs a command, printing output to `out` and saving any changes
+pub fn run(args: &Args, out: &mut impl Write) -> Result<(), Error> {
+    let mut target = Target::open(&args.path)?;
+    let gff = target.gff(args.file.as_deref())?;
+    let player = || creature(gff, args.player);
+
+    match &args.command {
+        Command::Dump => match args.json {
+            true => writeln!(out, "{:#}", dump::struct_json(&gff.root))?,
+            false => write!(out, "{}", dump::struct_text(&gff.root))?,
+        },
+        Command::Get(path) => {
+            let field = gff.root.find_path(path)?;
+            let lock = field.read().expect("Field poisoned");
+
+            match args.json {
+                true => writeln!(out, "{:#}", dump::field_json(&lock.field))?,
+                false => write!(
+                    out,
+                    "{}",
+                    dump::field_text(lock.label.as_str(), &lock.field)
+                )?,
+            }
+        }
+        Command::Set(path, value) => set_field(gff, path, value)?,
+        Command::ListPlayers => {
+            for (i, s) in creatures(gff).iter().enumerate() {
+                writeln!(out, "{i}: {}", describe(s)?)?;
+            }
+        }
+        Command::AddFeat(feat) => add_feat(&player()?, *feat)?,
+        Command::RemoveFeat(feat) => remove_feat(&player()?, *feat)?,
+        Command::AddSpell {
+            class,
+            level,
+            spell,
+        } => add_spell(&player()?, *class, *level, *spell)?,
+        Command::SetAbility(ability, value) => set_ability(&player()?, *ability, *value)?,
+        Command::Export(path) => write_bic(&player_to_bic(&player()?)?, path)?,
+        Command::Import(path) => import(&target, gff, args.player, path)?,
+    }
+
+    if args.command.is_edit() {
+        target.save(args.output.as_deref())?;
+    }
+
+    Ok(())
+}
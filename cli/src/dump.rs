@@ -0,0 +1,146 @@
+use nwn_lib::files::gff::{exo_string::ExoLocString, field::Field, r#struct::Struct};
+use serde_json::{Map, Value, json};
+use std::fmt::Write;
+
+fn loc_string_text(s: &ExoLocString) -> String {
+    let text = s
+        .substrings
+        .iter()
+        .map(|x| format!("{:?}", x.data))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match (text.is_empty(), s.str_ref) {
+        (true, u32::MAX) => "\"\"".to_string(),
+        (true, str_ref) => format!("str_ref {str_ref}"),
+        (false, u32::MAX) => text,
+        (false, str_ref) => format!("{text} (str_ref {str_ref})"),
+    }
+}
+
+/// Single line value of a field, `None` for structs and lists
+pub fn scalar_text(field: &Field) -> Option<String> {
+    let text = match field {
+        Field::Byte(x) => x.to_string(),
+        Field::Char(x) => x.0.to_string(),
+        Field::Word(x) => x.to_string(),
+        Field::Short(x) => x.to_string(),
+        Field::DWord(x) => x.to_string(),
+        Field::Int(x) => x.to_string(),
+        Field::DWord64(x) => x.to_string(),
+        Field::Int64(x) => x.to_string(),
+        Field::Float(x) => x.to_string(),
+        Field::Double(x) => x.to_string(),
+        Field::ExoString(x) => format!("{:?}", x.0),
+        Field::ResRef(x) => format!("{:?}", x.0),
+        Field::ExoLocString(x) => loc_string_text(x),
+        Field::Void(x) => format!("{} bytes", x.data.len()),
+        Field::Struct(_) | Field::List(_) => return None,
+    };
+
+    Some(text)
+}
+
+fn write_field(out: &mut String, label: &str, field: &Field, indent: usize) -> std::fmt::Result {
+    let pad = "  ".repeat(indent);
+    let field_type = field.get_field_type();
+
+    match field {
+        Field::Struct(s) => {
+            writeln!(out, "{pad}{label} ({field_type:?}):")?;
+            write_struct(out, s, indent + 1)?;
+        }
+        Field::List(list) => {
+            writeln!(
+                out,
+                "{pad}{label} ({field_type:?}, {} entries):",
+                list.len()
+            )?;
+            for (i, s) in list.iter().enumerate() {
+                writeln!(out, "{pad}  [{i}]:")?;
+                write_struct(out, s, indent + 2)?;
+            }
+        }
+        field => {
+            let value = scalar_text(field).unwrap_or_default();
+            writeln!(out, "{pad}{label} ({field_type:?}): {value}")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_struct(out: &mut String, s: &Struct, indent: usize) -> std::fmt::Result {
+    for field in &s.fields {
+        let lock = field.read().expect("Field poisoned");
+        write_field(out, lock.label.as_str(), &lock.field, indent)?;
+    }
+
+    Ok(())
+}
+
+/// Indented listing of every field with its type
+pub fn struct_text(s: &Struct) -> String {
+    let mut out = String::new();
+    write_struct(&mut out, s, 0).expect("Writing to a String can't fail");
+    out
+}
+
+/// Listing of a field, only the value for single values
+pub fn field_text(label: &str, field: &Field) -> String {
+    match scalar_text(field) {
+        Some(text) => format!("{text}\n"),
+        None => {
+            let mut out = String::new();
+            write_field(&mut out, label, field, 0).expect("Writing to a String can't fail");
+            out
+        }
+    }
+}
+
+pub fn field_json(field: &Field) -> Value {
+    match field {
+        Field::Byte(x) => json!(x),
+        Field::Char(x) => json!(x.0),
+        Field::Word(x) => json!(x),
+        Field::Short(x) => json!(x),
+        Field::DWord(x) => json!(x),
+        Field::Int(x) => json!(x),
+        Field::DWord64(x) => json!(x),
+        Field::Int64(x) => json!(x),
+        Field::Float(x) => json!(x),
+        Field::Double(x) => json!(x),
+        Field::ExoString(x) => json!(x.0),
+        Field::ResRef(x) => json!(x.0),
+        Field::ExoLocString(x) => json!({
+            "str_ref": x.str_ref,
+            "strings": x.substrings.iter().map(|s| json!({
+                "language": format!("{:?}", s.language),
+                "gender": format!("{:?}", s.gender),
+                "text": s.data,
+            })).collect::<Vec<_>>(),
+        }),
+        Field::Void(x) => json!(
+            x.data
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>()
+        ),
+        Field::Struct(s) => struct_json(s),
+        Field::List(list) => Value::Array(list.iter().map(struct_json).collect()),
+    }
+}
+
+/// Struct as a JSON object keyed by field label
+pub fn struct_json(s: &Struct) -> Value {
+    let fields = s
+        .fields
+        .iter()
+        .map(|field| {
+            let lock = field.read().expect("Field poisoned");
+            (lock.label.as_str().to_string(), field_json(&lock.field))
+        })
+        .collect::<Map<_, _>>();
+
+    Value::Object(fields)
+}
@@ -0,0 +1,30 @@
+#[derive(Debug)]
+pub enum Error {
+    /// Bad command line arguments
+    Usage(String),
+    ModelError(nwn_model::error::Error),
+}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Usage(msg) => f.write_str(msg),
+            Self::ModelError(e) => e.fmt(f),
+        }
+    }
+}
+impl std::error::Error for Error {}
+impl From<nwn_model::error::Error> for Error {
+    fn from(value: nwn_model::error::Error) -> Self {
+        Self::ModelError(value)
+    }
+}
+impl From<nwn_lib::error::Error> for Error {
+    fn from(value: nwn_lib::error::Error) -> Self {
+        Self::ModelError(value.into())
+    }
+}
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::ModelError(value.into())
+    }
+}
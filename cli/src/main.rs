@@ -0,0 +1,134 @@
+mod args;
+mod commands;
+mod dump;
+mod error;
+mod target;
+
+use crate::{
+    args::{Args, USAGE},
+    error::Error,
+};
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = commands::run(&args, &mut std::io::stdout().lock()) {
+        match e {
+            Error::Usage(_) => eprintln!("{e}\n\nRun with --help for usage"),
+            _ => eprintln!("Error: {e}"),
+        }
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    /// Copies the player list fixture into a fresh temporary folder
+    fn copy_player_list(test: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("nwn2-charedit-cli-{}-{test}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("playerlist.ifo");
+        let data = include_bytes!("../../lib/src/tests/files/playerlist.ifo");
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    /// Runs `command` on `path` followed by `args`
+    fn run(command: &str, path: &Path, args: &[&str]) -> Result<String, Error> {
+        let path = path.to_string_lossy();
+        let args = [command, &path]
+            .into_iter()
+            .chain(args.iter().copied())
+            .map(String::from);
+
+        let args = Args::parse(args)?.unwrap();
+        let mut out = vec![];
+        commands::run(&args, &mut out)?;
+
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn edit_player_list() {
+        let path = copy_player_list("edit");
+
+        let players = run("list-players", &path, &[]).unwrap();
+        assert_eq!(players.lines().count(), 1);
+        assert!(players.starts_with("0: Merrin Tallow (Sorcerer 4"));
+
+        run("set-ability", &path, &["con", "16"]).unwrap();
+        assert_eq!(
+            run("get", &path, &["Mod_PlayerList[0].Con"]).unwrap(),
+            "16\n"
+        );
+
+        run("add-feat", &path, &["2"]).unwrap();
+        assert!(matches!(
+            run("add-feat", &path, &["2"]),
+            Err(Error::Usage(_))
+        ));
+        run("remove-feat", &path, &["2"]).unwrap();
+        assert!(matches!(
+            run("remove-feat", &path, &["2"]),
+            Err(Error::Usage(_))
+        ));
+
+        run("add-spell", &path, &["sorcerer", "0", "1"]).unwrap();
+        let known = "Mod_PlayerList[0].ClassList[0].KnownList0";
+        let json = run("get", &path, &[known, "--json"]).unwrap();
+        let spells: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(spells.as_array().unwrap().iter().any(|x| x["Spell"] == 1));
+
+        run("set", &path, &["Mod_PlayerList[0].Tag", "merrin"]).unwrap();
+        assert_eq!(
+            run("get", &path, &["Mod_PlayerList[0].Tag"]).unwrap(),
+            "\"merrin\"\n"
+        );
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn export_import() {
+        let path = copy_player_list("export_import");
+        let bic = path.with_file_name("player.bic");
+        let bic_arg = bic.to_string_lossy();
+
+        run("export", &path, &[&bic_arg]).unwrap();
+        let players = run("list-players", &bic, &[]).unwrap();
+        assert!(players.starts_with("0: Merrin Tallow"));
+
+        run("set-ability", &bic, &["str", "18"]).unwrap();
+        run("import", &path, &[&bic_arg]).unwrap();
+        assert_eq!(
+            run("get", &path, &["Mod_PlayerList[0].Str"]).unwrap(),
+            "18\n"
+        );
+        assert_eq!(
+            run("get", &path, &["Mod_PlayerList[0].IsPC"]).unwrap(),
+            "1\n"
+        );
+
+        assert!(matches!(
+            run("import", &bic, &[&bic_arg]),
+            Err(Error::Usage(_))
+        ));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
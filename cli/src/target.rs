@@ -0,0 +1,100 @@
+use crate::error::Error;
+use nwn_lib::{
+    files::gff::{Gff, r#struct::Struct},
+    save::{PLAYER_LIST, PLAYER_LIST_FIELD, RES_GFF, ResGff, SaveGame, player_list_entries},
+};
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+/// File a command runs on
+#[derive(Debug)]
+pub enum Target {
+    Save(SaveGame),
+    ResGff { path: PathBuf, res_gff: ResGff },
+    Gff { path: PathBuf, gff: Gff },
+}
+impl Target {
+    /// Opens a save folder, a `resgff.zip` or a single GFF file
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        if path.is_dir() {
+            return Ok(Self::Save(SaveGame::open(path)?));
+        }
+
+        let data = std::fs::read(path)?;
+
+        let is_res_gff = path
+            .file_name()
+            .is_some_and(|x| x.eq_ignore_ascii_case(RES_GFF));
+
+        if is_res_gff {
+            Ok(Self::ResGff {
+                path: path.into(),
+                res_gff: ResGff::read_zip(data)?,
+            })
+        } else {
+            Ok(Self::Gff {
+                path: path.into(),
+                gff: Gff::read_without_tlk(Cursor::new(data))?,
+            })
+        }
+    }
+
+    /// GFF file to run on, `file` names a member of a save folder or `resgff.zip`
+    /// and defaults to `playerlist.ifo`
+    pub fn gff(&self, file: Option<&str>) -> Result<&Gff, Error> {
+        let res_gff = match self {
+            Self::Save(save) => &save.res_gff,
+            Self::ResGff { res_gff, .. } => res_gff,
+            Self::Gff { gff, .. } => {
+                return match file {
+                    Some(_) => Err(Error::Usage(
+                        "--file only applies to save folders and resgff.zip".into(),
+                    )),
+                    None => Ok(gff),
+                };
+            }
+        };
+
+        let file = file.unwrap_or(PLAYER_LIST);
+
+        res_gff
+            .gff(file)
+            .ok_or_else(|| Error::Usage(format!("No GFF file named {file}")))
+    }
+
+    /// Writes the changes back to where they were read from, or to `output`
+    pub fn save(&mut self, output: Option<&Path>) -> Result<(), Error> {
+        match self {
+            Self::Save(save) => match output {
+                Some(output) => save.save_to(output)?,
+                None => save.save()?,
+            },
+            Self::ResGff { path, res_gff } => {
+                res_gff.sync_companions()?;
+                std::fs::write(output.unwrap_or(path), res_gff.write_zip()?)?;
+            }
+            Self::Gff { path, gff } => {
+                let mut buf = vec![];
+                gff.write(&mut buf)?;
+                std::fs::write(output.unwrap_or(path), buf)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn is_player_list(gff: &Gff) -> bool {
+    gff.root.find_direct(PLAYER_LIST_FIELD).is_some()
+}
+
+/// Characters in `gff`, the `Mod_PlayerList` entries of a player list or the
+/// root of a `.bic`/`.ros`. The structs share their fields with `gff`
+pub fn creatures(gff: &Gff) -> Vec<Struct> {
+    match is_player_list(gff) {
+        true => player_list_entries(gff),
+        false => vec![gff.root.clone()],
+    }
+}
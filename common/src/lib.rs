@@ -3,7 +3,7 @@ pub mod error;
 #[macro_export]
 macro_rules! open_enum {
     ($viz: vis enum $name: ident : $repr: ty { $($k: ident = $v: expr),+ $(,)? }) => {
-        #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+        #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
         #[repr(transparent)]
         $viz struct $name(pub $repr);
 
@@ -36,6 +36,24 @@ macro_rules! open_enum {
                 }
             }
         }
+
+        /// Parses a case insensitive name or a number
+        impl std::str::FromStr for $name {
+            type Err = $crate::error::EnumError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $(
+                    if s.eq_ignore_ascii_case(stringify!($k)) {
+                        return Ok(Self::$k);
+                    }
+                )+
+
+                s.parse::<$repr>().map($name).map_err(|_| $crate::error::EnumError {
+                    enum_type: stringify!($name),
+                    msg: format!("Unknown value: {s}"),
+                })
+            }
+        }
     };
 }
 
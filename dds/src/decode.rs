@@ -0,0 +1,229 @@
+use crate::{
+    Rgba,
+    format::{Format, PixelMasks},
+};
+use std::io::{Error, ErrorKind};
+
+mod ffi {
+    use std::ffi::{c_int, c_void};
+
+    // `destination_pitch` is in bytes, or in floats for BC6H
+    unsafe extern "C" {
+        pub unsafe fn bcdec_bc1(
+            compressed_block: *const c_void,
+            decompressed_block: *mut c_void,
+            destination_pitch: c_int,
+        );
+        pub unsafe fn bcdec_bc2(
+            compressed_block: *const c_void,
+            decompressed_block: *mut c_void,
+            destination_pitch: c_int,
+        );
+        pub unsafe fn bcdec_bc3(
+            compressed_block: *const c_void,
+            decompressed_block: *mut c_void,
+            destination_pitch: c_int,
+        );
+        pub unsafe fn bcdec_bc4(
+            compressed_block: *const c_void,
+            decompressed_block: *mut c_void,
+            destination_pitch: c_int,
+            is_signed: c_int,
+        );
+        pub unsafe fn bcdec_bc5(
+            compressed_block: *const c_void,
+            decompressed_block: *mut c_void,
+            destination_pitch: c_int,
+            is_signed: c_int,
+        );
+        pub unsafe fn bcdec_bc6h_float(
+            compressed_block: *const c_void,
+            decompressed_block: *mut c_void,
+            destination_pitch: c_int,
+            is_signed: c_int,
+        );
+        pub unsafe fn bcdec_bc7(
+            compressed_block: *const c_void,
+            decompressed_block: *mut c_void,
+            destination_pitch: c_int,
+        );
+    }
+}
+
+/// Maps a BC4/BC5 channel to `0..=255`
+fn unorm(x: u8, signed: bool) -> u8 {
+    match signed {
+        true => (x as i8 as i16 + 128) as u8,
+        false => x,
+    }
+}
+
+/// Decodes a single 4x4 block, pixels are in row order
+fn decode_block(format: Format, block: &[u8], out: &mut [Rgba; 16]) {
+    assert!(
+        format.block_size().is_some_and(|size| block.len() >= size),
+        "Block too small for {format:?}"
+    );
+
+    let src = block.as_ptr().cast();
+    let rgba_pitch = 4 * 4;
+
+    // SAFETY: `block` holds a whole block and each output buffer is a whole 4x4 block at the given pitch
+    unsafe {
+        match format {
+            Format::Bc1 => ffi::bcdec_bc1(src, out.as_mut_ptr().cast(), rgba_pitch),
+            Format::Bc2 => ffi::bcdec_bc2(src, out.as_mut_ptr().cast(), rgba_pitch),
+            Format::Bc3 => ffi::bcdec_bc3(src, out.as_mut_ptr().cast(), rgba_pitch),
+            Format::Bc7 => ffi::bcdec_bc7(src, out.as_mut_ptr().cast(), rgba_pitch),
+            Format::Bc4 { signed } => {
+                let mut buf = [0u8; 16];
+                ffi::bcdec_bc4(src, buf.as_mut_ptr().cast(), 4, signed as _);
+
+                for (px, r) in out.iter_mut().zip(buf) {
+                    let r = unorm(r, signed);
+                    *px = Rgba {
+                        r,
+                        g: r,
+                        b: r,
+                        a: 255,
+                    };
+                }
+            }
+            Format::Bc5 { signed } => {
+                let mut buf = [0u8; 32];
+                ffi::bcdec_bc5(src, buf.as_mut_ptr().cast(), 8, signed as _);
+
+                for (px, rg) in out.iter_mut().zip(buf.chunks_exact(2)) {
+                    *px = Rgba {
+                        r: unorm(rg[0], signed),
+                        g: unorm(rg[1], signed),
+                        b: 0,
+                        a: 255,
+                    };
+                }
+            }
+            Format::Bc6h { signed } => {
+                let mut buf = [0f32; 48];
+                ffi::bcdec_bc6h_float(src, buf.as_mut_ptr().cast(), 12, signed as _);
+
+                let tone_map = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+                for (px, rgb) in out.iter_mut().zip(buf.chunks_exact(3)) {
+                    *px = Rgba {
+                        r: tone_map(rgb[0]),
+                        g: tone_map(rgb[1]),
+                        b: tone_map(rgb[2]),
+                        a: 255,
+                    };
+                }
+            }
+            Format::Uncompressed(_) => unreachable!("Uncompressed formats have no blocks"),
+        }
+    }
+}
+
+fn decode_blocks(
+    format: Format,
+    block_size: usize,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Vec<Rgba> {
+    let (width, height) = (width as usize, height as usize);
+    let mut pixels = vec![Rgba::zero(); width * height];
+    let mut block_pixels = [const { Rgba::zero() }; 16];
+
+    let blocks = data.chunks_exact(block_size);
+    let positions =
+        (0..height.div_ceil(4)).flat_map(|y| (0..width.div_ceil(4)).map(move |x| (x * 4, y * 4)));
+
+    for ((x, y), block) in positions.zip(blocks) {
+        decode_block(format, block, &mut block_pixels);
+
+        // Blocks on the right and bottom edges can extend past the image
+        let block_width = 4.min(width - x);
+        let block_height = 4.min(height - y);
+
+        for row in 0..block_height {
+            let dst = (y + row) * width + x;
+            let src = row * 4;
+
+            pixels[dst..dst + block_width].clone_from_slice(&block_pixels[src..src + block_width]);
+        }
+    }
+
+    pixels
+}
+
+/// Scales the bits in `mask` to `0..=255`
+fn read_channel(pixel: u32, mask: u32) -> Option<u8> {
+    if mask == 0 {
+        return None;
+    }
+
+    let shift = mask.trailing_zeros();
+    let max = (mask >> shift) as u64;
+    let value = ((pixel & mask) >> shift) as u64;
+
+    Some((value * 255 / max) as u8)
+}
+
+fn decode_uncompressed(masks: PixelMasks, width: u32, height: u32, data: &[u8]) -> Vec<Rgba> {
+    let pixel_count = width as usize * height as usize;
+
+    data.chunks_exact(masks.bytes_per_pixel())
+        .take(pixel_count)
+        .map(|bytes| {
+            let pixel = bytes
+                .iter()
+                .enumerate()
+                .fold(0u32, |acc, (i, x)| acc | (*x as u32) << (i * 8));
+
+            let a = read_channel(pixel, masks.a).unwrap_or(255);
+
+            if masks.luminance {
+                let l = read_channel(pixel, masks.r).unwrap_or(0);
+                return Rgba {
+                    r: l,
+                    g: l,
+                    b: l,
+                    a,
+                };
+            }
+
+            Rgba {
+                r: read_channel(pixel, masks.r).unwrap_or(0),
+                g: read_channel(pixel, masks.g).unwrap_or(0),
+                b: read_channel(pixel, masks.b).unwrap_or(0),
+                a,
+            }
+        })
+        .collect()
+}
+
+/// Decodes a `width` x `height` image to RGBA, `data` must hold the whole image
+pub fn decode(format: Format, width: u32, height: u32, data: &[u8]) -> Result<Vec<Rgba>, Error> {
+    let size = format.data_size(width, height).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Image size overflows: {width}x{height}"),
+        )
+    })?;
+
+    let data = data.get(..size).ok_or_else(|| {
+        Error::new(
+            ErrorKind::UnexpectedEof,
+            format!(
+                "Expected {size} bytes of pixel data but found {}",
+                data.len()
+            ),
+        )
+    })?;
+
+    let pixels = match (format, format.block_size()) {
+        (Format::Uncompressed(masks), _) => decode_uncompressed(masks, width, height, data),
+        (_, Some(block_size)) => decode_blocks(format, block_size, width, height, data),
+        (_, None) => unreachable!("Compressed formats have a block size"),
+    };
+
+    Ok(pixels)
+}
@@ -0,0 +1,248 @@
+use crate::{
+    Rgba,
+    format::{Format, PixelMasks},
+};
+use std::io::{Error, ErrorKind};
+
+/// Packs a colour into 5:6:5 bits
+fn to_565(c: [u8; 3]) -> u16 {
+    let scale = |x: u8, max: u32| (x as u32 * max + 127) / 255;
+
+    ((scale(c[0], 31) << 11) | (scale(c[1], 63) << 5) | scale(c[2], 31)) as u16
+}
+
+fn from_565(c: u16) -> [u8; 3] {
+    let (r, g, b) = ((c >> 11) & 31, (c >> 5) & 63, c & 31);
+
+    [
+        ((r << 3) | (r >> 2)) as u8,
+        ((g << 2) | (g >> 4)) as u8,
+        ((b << 3) | (b >> 2)) as u8,
+    ]
+}
+
+fn distance(lhs: [u8; 3], rhs: [u8; 3]) -> u32 {
+    lhs.iter()
+        .zip(rhs)
+        .map(|(l, r)| (*l as i32 - r as i32).pow(2) as u32)
+        .sum()
+}
+
+fn nearest(palette: &[[u8; 3]], color: [u8; 3]) -> u32 {
+    (0..palette.len())
+        .min_by_key(|i| distance(palette[*i], color))
+        .unwrap_or_default() as u32
+}
+
+fn rgb(px: &Rgba) -> [u8; 3] {
+    [px.r, px.g, px.b]
+}
+
+/// Encodes the colour part of a BC1/BC3 block. With `allow_transparent`, pixels
+/// with alpha below 128 use the transparent index of the 3 colour mode
+fn encode_color_block(pixels: &[Rgba; 16], allow_transparent: bool) -> [u8; 8] {
+    let is_transparent = |px: &Rgba| allow_transparent && px.a < 128;
+    let opaque = pixels.iter().filter(|px| !is_transparent(px));
+
+    let (min, max) = opaque.fold(([255u8; 3], [0u8; 3]), |(min, max), px| {
+        let c = rgb(px);
+        (
+            [min[0].min(c[0]), min[1].min(c[1]), min[2].min(c[2])],
+            [max[0].max(c[0]), max[1].max(c[1]), max[2].max(c[2])],
+        )
+    });
+    let (mut c0, mut c1) = (to_565(max), to_565(min));
+
+    let has_transparent = pixels.iter().any(is_transparent);
+
+    // 4 colour mode needs c0 > c1, 3 colour mode needs c0 <= c1
+    if (c0 < c1) != has_transparent && c0 != c1 {
+        std::mem::swap(&mut c0, &mut c1);
+    }
+
+    let (e0, e1) = (from_565(c0), from_565(c1));
+    let lerp = |a: u8, b: u8, wa: u32, wb: u32| ((a as u32 * wa + b as u32 * wb) / (wa + wb)) as u8;
+    let mix = |wa, wb| [0, 1, 2].map(|i| lerp(e0[i], e1[i], wa, wb));
+
+    let palette = match c0 > c1 {
+        true => vec![e0, e1, mix(2, 1), mix(1, 2)],
+        false => vec![e0, e1, mix(1, 1)],
+    };
+
+    let indices = pixels.iter().enumerate().fold(0u32, |acc, (i, px)| {
+        let index = match is_transparent(px) {
+            true => 3,
+            false => nearest(&palette, rgb(px)),
+        };
+        acc | (index << (i * 2))
+    });
+
+    let mut block = [0u8; 8];
+    block[0..2].copy_from_slice(&c0.to_le_bytes());
+    block[2..4].copy_from_slice(&c1.to_le_bytes());
+    block[4..8].copy_from_slice(&indices.to_le_bytes());
+    block
+}
+
+fn encode_alpha_block(pixels: &[Rgba; 16]) -> [u8; 8] {
+    let a0 = pixels.iter().map(|px| px.a).max().unwrap_or_default();
+    let a1 = pixels.iter().map(|px| px.a).min().unwrap_or_default();
+
+    // a0 > a1 selects the 8 value mode
+    let palette: Vec<u8> = match a0 > a1 {
+        true => [a0, a1]
+            .into_iter()
+            .chain((1..7).map(|k| ((a0 as u32 * (7 - k) + a1 as u32 * k) / 7) as u8))
+            .collect(),
+        false => vec![a0],
+    };
+
+    let indices = pixels.iter().enumerate().fold(0u64, |acc, (i, px)| {
+        let index = (0..palette.len())
+            .min_by_key(|j| palette[*j].abs_diff(px.a))
+            .unwrap_or_default() as u64;
+        acc | (index << (i * 3))
+    });
+
+    let mut block = [0u8; 8];
+    block[0] = a0;
+    block[1] = a1;
+    block[2..8].copy_from_slice(&indices.to_le_bytes()[..6]);
+    block
+}
+
+/// Splits an image into 4x4 blocks, pixels past the edge repeat the last row or column
+fn blocks(width: u32, height: u32, pixels: &[Rgba]) -> impl Iterator<Item = [Rgba; 16]> + '_ {
+    let (width, height) = (width as usize, height as usize);
+
+    (0..height.div_ceil(4)).flat_map(move |by| {
+        (0..width.div_ceil(4)).map(move |bx| {
+            std::array::from_fn(|i| {
+                let x = (bx * 4 + i % 4).min(width - 1);
+                let y = (by * 4 + i / 4).min(height - 1);
+                pixels[y * width + x].clone()
+            })
+        })
+    })
+}
+
+/// Scales `x` to the bits in `mask`
+fn write_channel(x: u8, mask: u32) -> u32 {
+    if mask == 0 {
+        return 0;
+    }
+
+    let shift = mask.trailing_zeros();
+    let max = (mask >> shift) as u64;
+
+    (((x as u64 * max + 127) / 255) as u32) << shift
+}
+
+fn encode_uncompressed(masks: PixelMasks, pixels: &[Rgba]) -> Vec<u8> {
+    pixels
+        .iter()
+        .flat_map(|px| {
+            let pixel = match masks.luminance {
+                true => {
+                    let l = (px.r as u32 * 299 + px.g as u32 * 587 + px.b as u32 * 114) / 1000;
+                    write_channel(l as u8, masks.r)
+                }
+                false => {
+                    write_channel(px.r, masks.r)
+                        | write_channel(px.g, masks.g)
+                        | write_channel(px.b, masks.b)
+                }
+            } | write_channel(px.a, masks.a);
+
+            pixel
+                .to_le_bytes()
+                .into_iter()
+                .take(masks.bytes_per_pixel())
+        })
+        .collect()
+}
+
+pub fn check_supported(format: Format) -> Result<(), Error> {
+    match format {
+        Format::Uncompressed(_) | Format::Bc1 | Format::Bc3 => Ok(()),
+        format => Err(Error::new(
+            ErrorKind::Unsupported,
+            format!("Encoding {format:?} is not supported"),
+        )),
+    }
+}
+
+/// Encodes a `width` x `height` RGBA image
+pub fn encode(format: Format, width: u32, height: u32, pixels: &[Rgba]) -> Result<Vec<u8>, Error> {
+    if pixels.len() != width as usize * height as usize {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Expected {} pixels for a {width}x{height} image but found {}",
+                width as usize * height as usize,
+                pixels.len()
+            ),
+        ));
+    }
+
+    check_supported(format)?;
+
+    let data = match format {
+        Format::Uncompressed(masks) => encode_uncompressed(masks, pixels),
+        Format::Bc1 => blocks(width, height, pixels)
+            .flat_map(|block| encode_color_block(&block, true))
+            .collect(),
+        Format::Bc3 => blocks(width, height, pixels)
+            .flat_map(|block| {
+                let mut out = [0u8; 16];
+                out[..8].copy_from_slice(&encode_alpha_block(&block));
+                out[8..].copy_from_slice(&encode_color_block(&block, false));
+                out
+            })
+            .collect(),
+        _ => unreachable!("Checked by `check_supported`"),
+    };
+
+    Ok(data)
+}
+
+/// Halves an image with a box filter, odd rows and columns are folded into the last pixel.
+///
+/// Empty images are returned as they are
+pub fn downsample(width: u32, height: u32, pixels: &[Rgba]) -> (u32, u32, Vec<Rgba>) {
+    if width == 0 || height == 0 {
+        return (width, height, vec![]);
+    }
+    let (new_width, new_height) = ((width / 2).max(1), (height / 2).max(1));
+    let (width, height) = (width as usize, height as usize);
+
+    let pixels = (0..new_height as usize)
+        .flat_map(|y| (0..new_width as usize).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let end = |i: usize, new_size: u32, size: usize| match i + 1 == new_size as usize {
+                true => size,
+                false => i * 2 + 2,
+            };
+            let xs = x * 2..end(x, new_width, width);
+            let ys = y * 2..end(y, new_height, height);
+
+            let count = (xs.len() * ys.len()) as u32;
+            let sum =
+                ys.flat_map(|y| xs.clone().map(move |x| (x, y)))
+                    .fold([0u32; 4], |sum, (x, y)| {
+                        let px = &pixels[y * width + x];
+                        [
+                            sum[0] + px.r as u32,
+                            sum[1] + px.g as u32,
+                            sum[2] + px.b as u32,
+                            sum[3] + px.a as u32,
+                        ]
+                    });
+            let [r, g, b, a] = sum.map(|x| ((x + count / 2) / count) as u8);
+
+            Rgba { r, g, b, a }
+        })
+        .collect();
+
+    (new_width, new_height, pixels)
+}
@@ -0,0 +1,208 @@
+use crate::{DXGIFormat, DdsPixelFormat, HeaderExtra, PixelFormatFlags};
+use std::io::{Error, ErrorKind};
+
+/// Bit masks of each channel in an uncompressed pixel
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PixelMasks {
+    pub bit_count: u32,
+    pub r: u32,
+    pub g: u32,
+    pub b: u32,
+    pub a: u32,
+    /// Single channel in `r` used for red, green and blue
+    pub luminance: bool,
+}
+impl PixelMasks {
+    const fn rgba(bit_count: u32, r: u32, g: u32, b: u32, a: u32) -> Self {
+        Self {
+            bit_count,
+            r,
+            g,
+            b,
+            a,
+            luminance: false,
+        }
+    }
+
+    pub const R8G8B8A8: Self = Self::rgba(32, 0xff, 0xff00, 0xff_0000, 0xff00_0000);
+    pub const B8G8R8A8: Self = Self::rgba(32, 0xff_0000, 0xff00, 0xff, 0xff00_0000);
+    pub const B8G8R8X8: Self = Self::rgba(32, 0xff_0000, 0xff00, 0xff, 0);
+    pub const B5G6R5: Self = Self::rgba(16, 0xf800, 0x07e0, 0x001f, 0);
+    pub const B5G5R5A1: Self = Self::rgba(16, 0x7c00, 0x03e0, 0x001f, 0x8000);
+    pub const B4G4R4A4: Self = Self::rgba(16, 0x0f00, 0x00f0, 0x000f, 0xf000);
+    pub const R8G8: Self = Self::rgba(16, 0xff, 0xff00, 0, 0);
+    pub const R8: Self = Self::rgba(8, 0xff, 0, 0, 0);
+    pub const A8: Self = Self::rgba(8, 0, 0, 0, 0xff);
+
+    pub fn bytes_per_pixel(&self) -> usize {
+        self.bit_count.div_ceil(8) as usize
+    }
+
+    fn from_pixel_format(pf: &DdsPixelFormat) -> Option<Self> {
+        let flags = pf.flags;
+        let a = if flags.has_flag(PixelFormatFlags::AlphaPixels)
+            || flags.has_flag(PixelFormatFlags::Alpha)
+        {
+            pf.a_bit_mask
+        } else {
+            0
+        };
+
+        let masks = if flags.has_flag(PixelFormatFlags::Rgb) {
+            Self::rgba(
+                pf.rgb_bit_count,
+                pf.r_bit_mask,
+                pf.g_bit_mask,
+                pf.b_bit_mask,
+                a,
+            )
+        } else if flags.has_flag(PixelFormatFlags::Luminance) {
+            Self {
+                luminance: true,
+                ..Self::rgba(pf.rgb_bit_count, pf.r_bit_mask, 0, 0, a)
+            }
+        } else if flags.has_flag(PixelFormatFlags::Alpha) {
+            Self::rgba(pf.rgb_bit_count, 0, 0, 0, a)
+        } else {
+            return None;
+        };
+
+        matches!(masks.bit_count, 8 | 16 | 24 | 32).then_some(masks)
+    }
+}
+
+/// Pixel data layout of a DDS file
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    /// DXT1
+    Bc1,
+    /// DXT2, DXT3
+    Bc2,
+    /// DXT4, DXT5
+    Bc3,
+    /// Single channel, ATI1
+    Bc4 {
+        signed: bool,
+    },
+    /// Two channels, ATI2
+    Bc5 {
+        signed: bool,
+    },
+    /// HDR, tone mapped to 8 bits when decoded
+    Bc6h {
+        signed: bool,
+    },
+    Bc7,
+    Uncompressed(PixelMasks),
+}
+impl Format {
+    fn unsupported(msg: String) -> Error {
+        Error::new(ErrorKind::Unsupported, msg)
+    }
+
+    pub fn from_dxgi(format: DXGIFormat) -> Result<Self, Error> {
+        use DXGIFormat as F;
+
+        let format = match format {
+            F::BC1_TYPELESS | F::BC1_UNORM | F::BC1_UNORM_SRGB => Self::Bc1,
+            F::BC2_TYPELESS | F::BC2_UNORM | F::BC2_UNORM_SRGB => Self::Bc2,
+            F::BC3_TYPELESS | F::BC3_UNORM | F::BC3_UNORM_SRGB => Self::Bc3,
+            F::BC4_TYPELESS | F::BC4_UNORM => Self::Bc4 { signed: false },
+            F::BC4_SNORM => Self::Bc4 { signed: true },
+            F::BC5_TYPELESS | F::BC5_UNORM => Self::Bc5 { signed: false },
+            F::BC5_SNORM => Self::Bc5 { signed: true },
+            F::BC6H_TYPELESS | F::BC6H_UF16 => Self::Bc6h { signed: false },
+            F::BC6H_SF16 => Self::Bc6h { signed: true },
+            F::BC7_TYPELESS | F::BC7_UNORM | F::BC7_UNORM_SRGB => Self::Bc7,
+            F::R8G8B8A8_TYPELESS | F::R8G8B8A8_UNORM | F::R8G8B8A8_UNORM_SRGB => {
+                Self::Uncompressed(PixelMasks::R8G8B8A8)
+            }
+            F::B8G8R8A8_TYPELESS | F::B8G8R8A8_UNORM | F::B8G8R8A8_UNORM_SRGB => {
+                Self::Uncompressed(PixelMasks::B8G8R8A8)
+            }
+            F::B8G8R8X8_TYPELESS | F::B8G8R8X8_UNORM | F::B8G8R8X8_UNORM_SRGB => {
+                Self::Uncompressed(PixelMasks::B8G8R8X8)
+            }
+            F::B5G6R5_UNORM => Self::Uncompressed(PixelMasks::B5G6R5),
+            F::B5G5R5A1_UNORM => Self::Uncompressed(PixelMasks::B5G5R5A1),
+            F::B4G4R4A4_UNORM => Self::Uncompressed(PixelMasks::B4G4R4A4),
+            F::R8G8_TYPELESS | F::R8G8_UNORM => Self::Uncompressed(PixelMasks::R8G8),
+            F::R8_TYPELESS | F::R8_UNORM => Self::Uncompressed(PixelMasks::R8),
+            F::A8_UNORM => Self::Uncompressed(PixelMasks::A8),
+            x => {
+                return Err(Self::unsupported(format!(
+                    "Unsupported DXGI format: {}",
+                    x.0
+                )));
+            }
+        };
+
+        Ok(format)
+    }
+
+    pub fn from_four_cc(four_cc: &[u8; 4]) -> Result<Self, Error> {
+        let format = match four_cc {
+            b"DXT1" => Self::Bc1,
+            b"DXT2" | b"DXT3" => Self::Bc2,
+            b"DXT4" | b"DXT5" => Self::Bc3,
+            b"ATI1" | b"BC4U" => Self::Bc4 { signed: false },
+            b"BC4S" => Self::Bc4 { signed: true },
+            b"ATI2" | b"BC5U" => Self::Bc5 { signed: false },
+            b"BC5S" => Self::Bc5 { signed: true },
+            x => {
+                return Err(Self::unsupported(format!(
+                    "Unsupported FourCC: {:?}",
+                    String::from_utf8_lossy(x)
+                )));
+            }
+        };
+
+        Ok(format)
+    }
+
+    pub fn from_header(
+        pixel_format: &DdsPixelFormat,
+        header_extra: Option<&HeaderExtra>,
+    ) -> Result<Self, Error> {
+        if let Some(extra) = header_extra {
+            return Self::from_dxgi(extra.dxgi_format);
+        }
+
+        if pixel_format.flags.has_flag(PixelFormatFlags::FourCC) {
+            return Self::from_four_cc(&pixel_format.four_cc);
+        }
+
+        PixelMasks::from_pixel_format(pixel_format)
+            .map(Self::Uncompressed)
+            .ok_or_else(|| {
+                Self::unsupported(format!(
+                    "Unsupported pixel format: flags {:#x}, {} bits",
+                    pixel_format.flags.0, pixel_format.rgb_bit_count
+                ))
+            })
+    }
+
+    /// Size of a 4x4 block, `None` for uncompressed formats
+    pub fn block_size(&self) -> Option<usize> {
+        match self {
+            Self::Bc1 | Self::Bc4 { .. } => Some(8),
+            Self::Bc2 | Self::Bc3 | Self::Bc5 { .. } | Self::Bc6h { .. } | Self::Bc7 => Some(16),
+            Self::Uncompressed(_) => None,
+        }
+    }
+
+    /// Bytes needed for a `width` x `height` image, `None` if the size overflows
+    pub fn data_size(&self, width: u32, height: u32) -> Option<usize> {
+        let (width, height) = (width as usize, height as usize);
+
+        match self {
+            Self::Uncompressed(masks) => width
+                .checked_mul(height)?
+                .checked_mul(masks.bytes_per_pixel()),
+            _ => width
+                .div_ceil(4)
+                .checked_mul(height.div_ceil(4))?
+                .checked_mul(self.block_size()?),
+        }
+    }
+}
@@ -1,4 +1,12 @@
-use std::io::{Error, Read};
+mod decode;
+mod encode;
+mod format;
+mod surface;
+
+pub use format::{Format, PixelMasks};
+pub use surface::{Caps2, CubeFace, Surface};
+
+use std::io::{Error, ErrorKind, Read, Write};
 
 // DDS Format: https://learn.microsoft.com/en-us/windows/win32/direct3ddds/dx-graphics-dds-pguide
 // BC7 Format: https://learn.microsoft.com/en-us/windows/win32/direct3d11/bc7-format
@@ -12,6 +20,36 @@ macro_rules! read {
     }};
 }
 
+fn write_u32<W: Write>(writer: &mut W, values: &[u32]) -> Result<(), Error> {
+    values
+        .iter()
+        .try_for_each(|x| writer.write_all(&x.to_le_bytes()))
+}
+
+const HEADER_SIZE: u32 = 124;
+const PIXEL_FORMAT_SIZE: u32 = 32;
+
+common::open_enum! {
+  pub enum HeaderFlags: u32 {
+    Caps = 0x1,
+    Height = 0x2,
+    Width = 0x4,
+    Pitch = 0x8,
+    PixelFormat = 0x1000,
+    MipMapCount = 0x20000,
+    LinearSize = 0x80000,
+    Depth = 0x800000,
+  }
+}
+
+common::open_enum! {
+  pub enum Caps: u32 {
+    Complex = 0x8,
+    Texture = 0x1000,
+    MipMap = 0x400000,
+  }
+}
+
 common::open_enum! {
   pub enum PixelFormatFlags: u32 {
     AlphaPixels = 0x1,
@@ -52,6 +90,74 @@ impl DdsPixelFormat {
             a_bit_mask: read!(reader)?,
         })
     }
+
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write_u32(writer, &[self.size, self.flags.0])?;
+        writer.write_all(&self.four_cc)?;
+        write_u32(
+            writer,
+            &[
+                self.rgb_bit_count,
+                self.r_bit_mask,
+                self.g_bit_mask,
+                self.b_bit_mask,
+                self.a_bit_mask,
+            ],
+        )
+    }
+
+    fn from_format(format: Format) -> Result<Self, Error> {
+        let four_cc = |four_cc: &[u8; 4]| Self {
+            size: PIXEL_FORMAT_SIZE,
+            flags: PixelFormatFlags::FourCC,
+            four_cc: *four_cc,
+            rgb_bit_count: 0,
+            r_bit_mask: 0,
+            g_bit_mask: 0,
+            b_bit_mask: 0,
+            a_bit_mask: 0,
+        };
+
+        let pixel_format = match format {
+            Format::Bc1 => four_cc(b"DXT1"),
+            Format::Bc2 => four_cc(b"DXT3"),
+            Format::Bc3 => four_cc(b"DXT5"),
+            Format::Bc4 { signed: false } => four_cc(b"ATI1"),
+            Format::Bc4 { signed: true } => four_cc(b"BC4S"),
+            Format::Bc5 { signed: false } => four_cc(b"ATI2"),
+            Format::Bc5 { signed: true } => four_cc(b"BC5S"),
+            Format::Uncompressed(masks) => {
+                let color = match masks.luminance {
+                    true => PixelFormatFlags::Luminance,
+                    false if masks.r | masks.g | masks.b == 0 => PixelFormatFlags::Alpha,
+                    false => PixelFormatFlags::Rgb,
+                };
+                let alpha = match masks.a {
+                    0 => 0,
+                    _ => PixelFormatFlags::AlphaPixels.0,
+                };
+
+                Self {
+                    size: PIXEL_FORMAT_SIZE,
+                    flags: PixelFormatFlags(color.0 | alpha),
+                    four_cc: [0; 4],
+                    rgb_bit_count: masks.bit_count,
+                    r_bit_mask: masks.r,
+                    g_bit_mask: masks.g,
+                    b_bit_mask: masks.b,
+                    a_bit_mask: masks.a,
+                }
+            }
+            Format::Bc6h { .. } | Format::Bc7 => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    format!("{format:?} needs a DX10 header"),
+                ));
+            }
+        };
+
+        Ok(pixel_format)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -105,6 +211,82 @@ impl Header {
             reserved2: read!(reader)?,
         })
     }
+
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write_u32(
+            writer,
+            &[
+                self.size,
+                self.flags,
+                self.height,
+                self.width,
+                self.pitch_or_linear_size,
+                self.depth,
+                self.mip_map_count,
+            ],
+        )?;
+        write_u32(writer, &self.reserved1)?;
+        self.pixel_format.write(writer)?;
+        write_u32(
+            writer,
+            &[
+                self.caps,
+                self.caps2,
+                self.caps3,
+                self.caps4,
+                self.reserved2,
+            ],
+        )
+    }
+
+    fn new(width: u32, height: u32, mip_map_count: u32, format: Format) -> Result<Self, Error> {
+        let mut flags = HeaderFlags::Caps.0
+            | HeaderFlags::Height.0
+            | HeaderFlags::Width.0
+            | HeaderFlags::PixelFormat.0;
+        let mut caps = Caps::Texture.0;
+
+        if mip_map_count > 1 {
+            flags |= HeaderFlags::MipMapCount.0;
+            caps |= Caps::Complex.0 | Caps::MipMap.0;
+        }
+
+        let pitch_or_linear_size = match format {
+            Format::Uncompressed(masks) => {
+                flags |= HeaderFlags::Pitch.0;
+                width.checked_mul(masks.bit_count).map(|x| x.div_ceil(8))
+            }
+            _ => {
+                flags |= HeaderFlags::LinearSize.0;
+                format
+                    .data_size(width, height)
+                    .and_then(|x| x.try_into().ok())
+            }
+        }
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Image too large: {width}x{height}"),
+            )
+        })?;
+
+        Ok(Self {
+            size: HEADER_SIZE,
+            flags,
+            height,
+            width,
+            pitch_or_linear_size,
+            depth: 0,
+            mip_map_count,
+            reserved1: [0; 11],
+            pixel_format: DdsPixelFormat::from_format(format)?,
+            caps,
+            caps2: 0,
+            caps3: 0,
+            caps4: 0,
+            reserved2: 0,
+        })
+    }
 }
 
 common::int_enum! {
@@ -120,6 +302,14 @@ common::int_enum! {
 common::open_enum! {
   pub enum DXGIFormat: u32 {
     Unknown = 0,
+    R8G8B8A8_TYPELESS = 27,
+    R8G8B8A8_UNORM = 28,
+    R8G8B8A8_UNORM_SRGB = 29,
+    R8G8_TYPELESS = 48,
+    R8G8_UNORM = 49,
+    R8_TYPELESS = 60,
+    R8_UNORM = 61,
+    A8_UNORM = 65,
     BC1_TYPELESS = 70,
     BC1_UNORM = 71,
     BC1_UNORM_SRGB = 72,
@@ -135,12 +325,21 @@ common::open_enum! {
     BC5_TYPELESS = 82,
     BC5_UNORM = 83,
     BC5_SNORM = 84,
+    B5G6R5_UNORM = 85,
+    B5G5R5A1_UNORM = 86,
+    B8G8R8A8_UNORM = 87,
+    B8G8R8X8_UNORM = 88,
+    B8G8R8A8_TYPELESS = 90,
+    B8G8R8A8_UNORM_SRGB = 91,
+    B8G8R8X8_TYPELESS = 92,
+    B8G8R8X8_UNORM_SRGB = 93,
     BC6H_TYPELESS = 94,
     BC6H_UF16 = 95,
     BC6H_SF16 = 96,
     BC7_TYPELESS = 97,
     BC7_UNORM = 98,
     BC7_UNORM_SRGB = 99,
+    B4G4R4A4_UNORM = 115,
   }
 }
 
@@ -158,13 +357,31 @@ impl HeaderExtra {
             dxgi_format: DXGIFormat(read!(reader)?),
             resource_dimension: {
                 let x: u32 = read!(reader)?;
-                x.try_into().expect("Unexpected resource dimension")
+                x.try_into().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Unexpected resource dimension: {x}"),
+                    )
+                })?
             },
             misc_flag: read!(reader)?,
             array_size: read!(reader)?,
             misc_flags2: read!(reader)?,
         })
     }
+
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write_u32(
+            writer,
+            &[
+                self.dxgi_format.0,
+                self.resource_dimension.as_num(),
+                self.misc_flag,
+                self.array_size,
+                self.misc_flags2,
+            ],
+        )
+    }
 }
 
 #[repr(C)]
@@ -186,26 +403,99 @@ impl Rgba {
     }
 }
 
-mod ffi {
-    use std::ffi::{c_int, c_void};
-
-    unsafe extern "C" {
-        pub unsafe fn bcdec_bc7(
-            compressed_block: *const c_void,
-            decompressed_block: *mut c_void,
-            destination_pitch: c_int,
-        );
-    }
-}
-
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Dds {
     pub four_cc: [u8; 4],
     pub header: Header,
     pub header_extra: Option<HeaderExtra>,
-    pub pixels: Vec<Rgba>,
+    pub format: Format,
+    /// Every mip level of each layer, see [`Dds::surface`]
+    pub surfaces: Vec<Surface>,
 }
 impl Dds {
+    /// A single image, with a full chain of generated mip levels if `mip_maps` is set.
+    ///
+    /// Only uncompressed, BC1 and BC3 formats can be encoded
+    pub fn new(
+        width: u32,
+        height: u32,
+        pixels: Vec<Rgba>,
+        format: Format,
+        mip_maps: bool,
+    ) -> Result<Self, Error> {
+        encode::check_supported(format)?;
+
+        if width == 0 || height == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("DDS is {width}x{height}"),
+            ));
+        }
+        if pixels.len() != width as usize * height as usize {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Expected {} pixels for a {width}x{height} image but found {}",
+                    width as usize * height as usize,
+                    pixels.len()
+                ),
+            ));
+        }
+
+        let mut surfaces = vec![Surface {
+            layer: 0,
+            face: None,
+            mip_level: 0,
+            width,
+            height,
+            pixels,
+        }];
+
+        while let Some(last) = surfaces.last()
+            && mip_maps
+            && (last.width > 1 || last.height > 1)
+        {
+            let (width, height, pixels) = encode::downsample(last.width, last.height, &last.pixels);
+
+            surfaces.push(Surface {
+                layer: 0,
+                face: None,
+                mip_level: last.mip_level + 1,
+                width,
+                height,
+                pixels,
+            });
+        }
+
+        Ok(Self {
+            four_cc: *b"DDS ",
+            header: Header::new(width, height, surfaces.len() as u32, format)?,
+            header_extra: None,
+            format,
+            surfaces,
+        })
+    }
+
+    /// Writes the header and every surface encoded as [`Dds::format`]
+    pub fn write<W>(&self, mut writer: W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&self.four_cc)?;
+        self.header.write(&mut writer)?;
+
+        if let Some(extra) = &self.header_extra {
+            extra.write(&mut writer)?;
+        }
+
+        for surface in &self.surfaces {
+            let data = encode::encode(self.format, surface.width, surface.height, &surface.pixels)?;
+            writer.write_all(&data)?;
+        }
+
+        Ok(())
+    }
+
     pub fn read<R>(mut reader: R) -> Result<Self, Error>
     where
         R: Read,
@@ -221,43 +511,61 @@ impl Dds {
             None
         };
 
-        assert_eq!(
-            header_extra.as_ref().map(|x| x.dxgi_format),
-            Some(DXGIFormat::BC7_UNORM)
-        );
+        let format = Format::from_header(&header.pixel_format, header_extra.as_ref())?;
 
         let data = {
             let mut buf = Vec::new();
             reader.read_to_end(&mut buf).map(|_| buf)
         }?;
-        let mut data_ptr = data.as_ptr();
-
-        let mut pixels =
-            vec![const { Rgba::zero() }; header.width as usize * header.height as usize];
-        let pixels_ptr = pixels.as_mut_ptr();
 
-        let w = header.width;
-        let h = header.height;
-
-        unsafe {
-            for i in (0..h).step_by(4) {
-                for j in (0..w).step_by(4) {
-                    let dst: *mut u8 = pixels_ptr.cast();
-                    let dst = dst.add((i as usize * w as usize + j as usize) * 4);
-
-                    ffi::bcdec_bc7(data_ptr.cast(), dst.cast(), w as i32 * 4);
-                    data_ptr = data_ptr.add(16);
-                }
-            }
-        };
+        let surfaces = surface::read_surfaces(format, &header, header_extra.as_ref(), &data)?;
 
         Ok(Self {
             four_cc,
             header,
             header_extra,
-            pixels,
+            format,
+            surfaces,
         })
     }
+
+    /// Pixels of the full size image
+    pub fn pixels(&self) -> &[Rgba] {
+        self.surfaces
+            .first()
+            .map(|x| x.pixels.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn into_pixels(self) -> Vec<Rgba> {
+        self.surfaces
+            .into_iter()
+            .next()
+            .map(|x| x.pixels)
+            .unwrap_or_default()
+    }
+
+    pub fn surface(&self, layer: u32, face: Option<CubeFace>, mip_level: u32) -> Option<&Surface> {
+        self.surfaces
+            .iter()
+            .find(|x| x.layer == layer && x.face == face && x.mip_level == mip_level)
+    }
+
+    pub fn mip_levels(&self, layer: u32, face: Option<CubeFace>) -> impl Iterator<Item = &Surface> {
+        self.surfaces
+            .iter()
+            .filter(move |x| x.layer == layer && x.face == face)
+    }
+
+    /// Smallest mip level of the first layer that is at least `width` x `height`
+    pub fn mip_for_size(&self, width: u32, height: u32) -> Option<&Surface> {
+        let first = self.surfaces.first()?;
+
+        self.mip_levels(first.layer, first.face)
+            .filter(|x| x.width >= width && x.height >= height)
+            .last()
+            .or(Some(first))
+    }
 }
 
 #[cfg(test)]
@@ -265,6 +573,104 @@ mod test {
     use super::*;
     use std::io::{BufReader, BufWriter, Cursor};
 
+    struct TestPixelFormat {
+        flags: PixelFormatFlags,
+        four_cc: [u8; 4],
+        bit_count: u32,
+        masks: [u32; 4],
+    }
+    impl TestPixelFormat {
+        fn four_cc(four_cc: &[u8; 4]) -> Self {
+            Self {
+                flags: PixelFormatFlags::FourCC,
+                four_cc: *four_cc,
+                bit_count: 0,
+                masks: [0; 4],
+            }
+        }
+
+        fn masks(flags: u32, bit_count: u32, masks: [u32; 4]) -> Self {
+            Self {
+                flags: PixelFormatFlags(flags),
+                four_cc: [0; 4],
+                bit_count,
+                masks,
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct TestLayout {
+        mip_map_count: u32,
+        caps2: u32,
+        misc_flag: u32,
+        array_size: u32,
+    }
+
+    /// Builds a DDS file, `dxgi_format` adds a DX10 header
+    fn make_dds(
+        width: u32,
+        height: u32,
+        pf: TestPixelFormat,
+        dxgi_format: Option<DXGIFormat>,
+        data: &[u8],
+    ) -> Vec<u8> {
+        make_dds_with_layout(width, height, pf, dxgi_format, &TestLayout::default(), data)
+    }
+
+    fn make_dds_with_layout(
+        width: u32,
+        height: u32,
+        pf: TestPixelFormat,
+        dxgi_format: Option<DXGIFormat>,
+        layout: &TestLayout,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut out = b"DDS ".to_vec();
+        let mut push = |x: u32| out.extend(x.to_le_bytes());
+
+        push(124);
+        push(0x1007);
+        push(height);
+        push(width);
+        push(0);
+        push(0);
+        push(layout.mip_map_count);
+        (0..11).for_each(|_| push(0));
+
+        push(32);
+        push(pf.flags.0);
+        push(u32::from_le_bytes(pf.four_cc));
+        push(pf.bit_count);
+        pf.masks.into_iter().for_each(&mut push);
+
+        push(0x1000);
+        push(layout.caps2);
+        (0..3).for_each(|_| push(0));
+
+        if let Some(format) = dxgi_format {
+            push(format.0);
+            push(ResourceDimension::Texture2D as u32);
+            push(layout.misc_flag);
+            push(layout.array_size.max(1));
+            push(0);
+        }
+
+        out.extend(data);
+        out
+    }
+
+    fn read_dds(data: Vec<u8>) -> Result<Dds, Error> {
+        Dds::read(Cursor::new(data))
+    }
+
+    const RED: Rgba = Rgba {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+
     #[test]
     fn file_read() {
         let file = include_bytes!("../../lib/src/tests/files/is_fireball.dds");
@@ -277,7 +683,7 @@ mod test {
             let mut encoder = png::Encoder::new(out_file, dds.header.width, dds.header.height);
 
             let pixel_ptr = unsafe {
-                std::slice::from_raw_parts(dds.pixels.as_ptr().cast(), dds.pixels.len() * 4)
+                std::slice::from_raw_parts(dds.pixels().as_ptr().cast(), dds.pixels().len() * 4)
             };
 
             encoder.set_color(png::ColorType::Rgba);
@@ -288,4 +694,444 @@ mod test {
             encoder.finish().unwrap();
         }
     }
+
+    #[test]
+    fn read_bitmask() {
+        let bgra = TestPixelFormat::masks(0x41, 32, [0xff_0000, 0xff00, 0xff, 0xff00_0000]);
+        let dds = read_dds(make_dds(
+            2,
+            1,
+            bgra,
+            None,
+            &[0, 0, 255, 255, 30, 20, 10, 128],
+        ))
+        .unwrap();
+
+        assert_eq!(dds.format, Format::Uncompressed(PixelMasks::B8G8R8A8));
+        assert_eq!(
+            dds.pixels(),
+            [
+                RED,
+                Rgba {
+                    r: 10,
+                    g: 20,
+                    b: 30,
+                    a: 128
+                }
+            ]
+        );
+
+        let r5g6b5 = TestPixelFormat::masks(0x40, 16, [0xf800, 0x07e0, 0x001f, 0]);
+        let dds = read_dds(make_dds(1, 1, r5g6b5, None, &0xf800u16.to_le_bytes())).unwrap();
+        assert_eq!(dds.pixels(), [RED]);
+
+        let luminance = TestPixelFormat::masks(0x20000, 8, [0xff, 0, 0, 0]);
+        let dds = read_dds(make_dds(1, 1, luminance, None, &[100])).unwrap();
+        assert_eq!(
+            dds.pixels(),
+            [Rgba {
+                r: 100,
+                g: 100,
+                b: 100,
+                a: 255
+            }]
+        );
+    }
+
+    #[test]
+    fn read_dx10_uncompressed() {
+        let pf = TestPixelFormat::four_cc(b"DX10");
+        let data = [255, 0, 0, 255];
+        let dds = read_dds(make_dds(1, 1, pf, Some(DXGIFormat::R8G8B8A8_UNORM), &data)).unwrap();
+
+        assert_eq!(dds.format, Format::Uncompressed(PixelMasks::R8G8B8A8));
+        assert_eq!(dds.pixels(), [RED]);
+    }
+
+    #[test]
+    fn read_dxt1() {
+        // Solid red block: color0 = red, color1 = black, every index 0
+        let block = [0x00, 0xf8, 0, 0, 0, 0, 0, 0];
+        let data = [block, block].concat();
+
+        // 5x3 needs two blocks, the second is clipped to 1x3
+        let dds = read_dds(make_dds(
+            5,
+            3,
+            TestPixelFormat::four_cc(b"DXT1"),
+            None,
+            &data,
+        ))
+        .unwrap();
+
+        assert_eq!(dds.format, Format::Bc1);
+        assert_eq!(dds.pixels(), vec![RED; 15]);
+    }
+
+    #[test]
+    fn read_legacy_four_cc() {
+        let cases = [
+            (b"DXT1", Format::Bc1),
+            (b"DXT3", Format::Bc2),
+            (b"DXT5", Format::Bc3),
+            (b"ATI1", Format::Bc4 { signed: false }),
+            (b"ATI2", Format::Bc5 { signed: false }),
+        ];
+
+        for (four_cc, format) in cases {
+            let size = format.data_size(4, 4).unwrap();
+            let dds = make_dds(
+                4,
+                4,
+                TestPixelFormat::four_cc(four_cc),
+                None,
+                &vec![0; size],
+            );
+
+            let dds = read_dds(dds).unwrap();
+            assert_eq!(dds.format, format);
+            assert_eq!(dds.pixels().len(), 16);
+        }
+    }
+
+    #[test]
+    fn read_unsupported() {
+        let unsupported = [
+            make_dds(4, 4, TestPixelFormat::four_cc(b"ABCD"), None, &[0; 16]),
+            make_dds(
+                4,
+                4,
+                TestPixelFormat::four_cc(b"DX10"),
+                Some(DXGIFormat(2)),
+                &[0; 256],
+            ),
+            make_dds(
+                4,
+                4,
+                TestPixelFormat::masks(0x200, 32, [0; 4]),
+                None,
+                &[0; 64],
+            ),
+        ];
+
+        for dds in unsupported {
+            let err = read_dds(dds).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::Unsupported);
+        }
+    }
+
+    #[test]
+    fn read_truncated() {
+        let dxt1 = TestPixelFormat::four_cc(b"DXT1");
+
+        // 5x5 needs 4 blocks
+        let dds = make_dds(5, 5, dxt1, None, &[0; 24]);
+        let err = read_dds(dds.clone()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        // Truncated header
+        let err = read_dds(dds[..64].to_vec()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        let bgra = TestPixelFormat::masks(0x41, 32, [0xff_0000, 0xff00, 0xff, 0xff00_0000]);
+        let dds = make_dds(2, 2, bgra, None, &[0; 15]);
+        let err = read_dds(dds).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_oversized() {
+        let pf = TestPixelFormat::four_cc(b"DX10");
+        let dds = make_dds(
+            u32::MAX,
+            u32::MAX,
+            pf,
+            Some(DXGIFormat::R8G8B8A8_UNORM),
+            &[0; 16],
+        );
+
+        assert!(read_dds(dds).is_err());
+    }
+
+    fn solid(color: [u8; 4], count: usize) -> Vec<u8> {
+        color.repeat(count)
+    }
+
+    fn rgba(color: [u8; 4]) -> Rgba {
+        let [r, g, b, a] = color;
+        Rgba { r, g, b, a }
+    }
+
+    #[test]
+    fn read_mip_maps() {
+        let pf = TestPixelFormat::four_cc(b"DX10");
+        let layout = TestLayout {
+            mip_map_count: 4,
+            ..Default::default()
+        };
+
+        // 8x4, 4x2, 2x1, 1x1
+        let data = [
+            solid([1, 0, 0, 255], 32),
+            solid([2, 0, 0, 255], 8),
+            solid([3, 0, 0, 255], 2),
+            solid([4, 0, 0, 255], 1),
+        ]
+        .concat();
+
+        let dds = make_dds_with_layout(8, 4, pf, Some(DXGIFormat::R8G8B8A8_UNORM), &layout, &data);
+        let dds = read_dds(dds).unwrap();
+
+        let sizes = dds
+            .mip_levels(0, None)
+            .map(|x| (x.mip_level, x.width, x.height, x.pixels.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sizes,
+            [(0, 8, 4, 32), (1, 4, 2, 8), (2, 2, 1, 2), (3, 1, 1, 1)]
+        );
+
+        for surface in &dds.surfaces {
+            let expected = rgba([surface.mip_level as u8 + 1, 0, 0, 255]);
+            assert!(surface.pixels.iter().all(|x| *x == expected));
+        }
+
+        assert_eq!(dds.mip_for_size(3, 2).unwrap().mip_level, 1);
+        assert_eq!(dds.mip_for_size(16, 16).unwrap().mip_level, 0);
+
+        // Missing the last mip level
+        let pf = TestPixelFormat::four_cc(b"DX10");
+        let dds = make_dds_with_layout(
+            8,
+            4,
+            pf,
+            Some(DXGIFormat::R8G8B8A8_UNORM),
+            &layout,
+            &data[..data.len() - 4],
+        );
+        assert_eq!(read_dds(dds).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_cube_map() {
+        let pf = TestPixelFormat::masks(0x41, 32, [0xff, 0xff00, 0xff_0000, 0xff00_0000]);
+        let layout = TestLayout {
+            caps2: Caps2::Cubemap.0 | Caps2::PositiveX.0 | Caps2::NegativeY.0 | Caps2::PositiveZ.0,
+            ..Default::default()
+        };
+        let data = [
+            solid([1, 0, 0, 255], 4),
+            solid([2, 0, 0, 255], 4),
+            solid([3, 0, 0, 255], 4),
+        ]
+        .concat();
+
+        let dds = read_dds(make_dds_with_layout(2, 2, pf, None, &layout, &data)).unwrap();
+
+        let faces = dds.surfaces.iter().map(|x| x.face).collect::<Vec<_>>();
+        assert_eq!(
+            faces,
+            [
+                Some(CubeFace::PositiveX),
+                Some(CubeFace::NegativeY),
+                Some(CubeFace::PositiveZ)
+            ]
+        );
+
+        let surface = dds.surface(0, Some(CubeFace::NegativeY), 0).unwrap();
+        assert_eq!(surface.pixels, vec![rgba([2, 0, 0, 255]); 4]);
+    }
+
+    #[test]
+    fn read_texture_array() {
+        let pf = TestPixelFormat::four_cc(b"DX10");
+        let layout = TestLayout {
+            mip_map_count: 2,
+            array_size: 2,
+            ..Default::default()
+        };
+
+        // Each layer is stored with all of its mip levels
+        let data = [
+            solid([1, 0, 0, 255], 4),
+            solid([2, 0, 0, 255], 1),
+            solid([3, 0, 0, 255], 4),
+            solid([4, 0, 0, 255], 1),
+        ]
+        .concat();
+
+        let dds = make_dds_with_layout(2, 2, pf, Some(DXGIFormat::R8G8B8A8_UNORM), &layout, &data);
+        let dds = read_dds(dds).unwrap();
+
+        assert_eq!(dds.surfaces.len(), 4);
+        assert_eq!(
+            dds.surface(1, None, 0).unwrap().pixels[0],
+            rgba([3, 0, 0, 255])
+        );
+        assert_eq!(
+            dds.surface(1, None, 1).unwrap().pixels,
+            [rgba([4, 0, 0, 255])]
+        );
+
+        // DX10 cube maps store all six faces per array element
+        let pf = TestPixelFormat::four_cc(b"DX10");
+        let layout = TestLayout {
+            misc_flag: 0x4,
+            ..Default::default()
+        };
+        let data = solid([0; 4], 6);
+        let dds = make_dds_with_layout(1, 1, pf, Some(DXGIFormat::R8G8B8A8_UNORM), &layout, &data);
+        let dds = read_dds(dds).unwrap();
+
+        let faces = dds
+            .surfaces
+            .iter()
+            .filter_map(|x| x.face)
+            .collect::<Vec<_>>();
+        assert_eq!(faces, CubeFace::ALL);
+    }
+
+    #[test]
+    fn read_implausible_layout() {
+        let pf = || TestPixelFormat::four_cc(b"DX10");
+        let format = Some(DXGIFormat::R8G8B8A8_UNORM);
+        let data = solid([1, 0, 0, 255], 4);
+
+        // Levels past 1x1 are ignored
+        let layout = TestLayout {
+            mip_map_count: u32::MAX,
+            ..Default::default()
+        };
+        let data_with_mips = [data.clone(), solid([2, 0, 0, 255], 1)].concat();
+        let dds = make_dds_with_layout(2, 2, pf(), format, &layout, &data_with_mips);
+        assert_eq!(read_dds(dds).unwrap().surfaces.len(), 2);
+
+        // The data can't hold this many array elements
+        let layout = TestLayout {
+            array_size: u32::MAX,
+            ..Default::default()
+        };
+        let dds = make_dds_with_layout(2, 2, pf(), format, &layout, &data);
+        assert_eq!(read_dds(dds).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+        let dds = make_dds(0, 2, pf(), format, &data);
+        assert_eq!(read_dds(dds).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    fn write_dds(dds: &Dds) -> Dds {
+        let mut buf = vec![];
+        dds.write(&mut buf).unwrap();
+        read_dds(buf).unwrap()
+    }
+
+    #[test]
+    fn write_uncompressed() {
+        let pixels = [
+            [255, 0, 0, 255],
+            [0, 255, 0, 128],
+            [0, 0, 255, 0],
+            [1, 2, 3, 4],
+        ]
+        .map(rgba)
+        .to_vec();
+
+        for masks in [PixelMasks::R8G8B8A8, PixelMasks::B8G8R8A8] {
+            let dds = Dds::new(2, 2, pixels.clone(), Format::Uncompressed(masks), false).unwrap();
+            assert_eq!(write_dds(&dds), dds);
+        }
+
+        // Rewriting a file that was read keeps its DX10 header
+        let pf = TestPixelFormat::four_cc(b"DX10");
+        let data = [255, 0, 0, 255];
+        let file = make_dds(1, 1, pf, Some(DXGIFormat::R8G8B8A8_UNORM), &data);
+        let dds = read_dds(file.clone()).unwrap();
+
+        let mut buf = vec![];
+        dds.write(&mut buf).unwrap();
+        assert_eq!(buf, file);
+    }
+
+    #[test]
+    fn write_bc1() {
+        let transparent = rgba([0; 4]);
+        let pixels = (0..64)
+            .map(|i| match i % 3 {
+                0 => transparent.clone(),
+                _ => RED,
+            })
+            .collect();
+
+        let dds = Dds::new(8, 8, pixels, Format::Bc1, false).unwrap();
+        let read = write_dds(&dds);
+
+        assert_eq!(read.format, Format::Bc1);
+        assert_eq!(read.header.pitch_or_linear_size, 32);
+        assert_eq!(read.surfaces, dds.surfaces);
+    }
+
+    #[test]
+    fn write_bc3() {
+        let pixels = (0..16)
+            .map(|i| rgba([128, 128, 128, i * 17]))
+            .collect::<Vec<_>>();
+
+        let dds = Dds::new(4, 4, pixels.clone(), Format::Bc3, false).unwrap();
+        let read = write_dds(&dds);
+
+        assert_eq!(read.format, Format::Bc3);
+        for (expected, px) in pixels.iter().zip(read.pixels()) {
+            assert!(expected.a.abs_diff(px.a) <= 19, "{expected:?} {px:?}");
+            assert!(expected.r.abs_diff(px.r) <= 4, "{expected:?} {px:?}");
+        }
+    }
+
+    #[test]
+    fn write_mip_maps() {
+        let pixels = (0..15).map(|_| RED).collect();
+        let dds = Dds::new(5, 3, pixels, Format::Bc3, true).unwrap();
+
+        let sizes = dds
+            .surfaces
+            .iter()
+            .map(|x| (x.width, x.height))
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, [(5, 3), (2, 1), (1, 1)]);
+        assert_eq!(dds.header.mip_map_count, 3);
+
+        let read = write_dds(&dds);
+        assert_eq!(read.surfaces, dds.surfaces);
+
+        // Box filtered
+        let pixels = [
+            [0, 0, 0, 255],
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [255, 255, 0, 255],
+        ]
+        .map(rgba)
+        .to_vec();
+        let dds = Dds::new(
+            2,
+            2,
+            pixels,
+            Format::Uncompressed(PixelMasks::R8G8B8A8),
+            true,
+        )
+        .unwrap();
+        assert_eq!(dds.surfaces[1].pixels, [rgba([128, 128, 0, 255])]);
+    }
+
+    #[test]
+    fn write_unsupported() {
+        let err = Dds::new(1, 1, vec![RED], Format::Bc7, false).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        let err = Dds::new(2, 2, vec![RED], Format::Bc1, false).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        for (width, height) in [(0, 0), (0, 4), (4, 0)] {
+            let err = Dds::new(width, height, vec![], Format::Bc1, true).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+    }
 }
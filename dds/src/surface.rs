@@ -0,0 +1,185 @@
+use crate::{Header, HeaderExtra, ResourceDimension, Rgba, decode, format::Format};
+use std::io::{Error, ErrorKind};
+
+common::open_enum! {
+  pub enum Caps2: u32 {
+    Cubemap = 0x200,
+    PositiveX = 0x400,
+    NegativeX = 0x800,
+    PositiveY = 0x1000,
+    NegativeY = 0x2000,
+    PositiveZ = 0x4000,
+    NegativeZ = 0x8000,
+    Volume = 0x200000,
+  }
+}
+impl Caps2 {
+    fn has_flag(&self, flag: Caps2) -> bool {
+        self.0 & flag.0 == flag.0
+    }
+}
+
+/// `D3D11_RESOURCE_MISC_TEXTURECUBE`
+const MISC_TEXTURE_CUBE: u32 = 0x4;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+impl CubeFace {
+    /// Faces in the order they are stored
+    pub const ALL: [CubeFace; 6] = [
+        Self::PositiveX,
+        Self::NegativeX,
+        Self::PositiveY,
+        Self::NegativeY,
+        Self::PositiveZ,
+        Self::NegativeZ,
+    ];
+
+    fn flag(&self) -> Caps2 {
+        match self {
+            Self::PositiveX => Caps2::PositiveX,
+            Self::NegativeX => Caps2::NegativeX,
+            Self::PositiveY => Caps2::PositiveY,
+            Self::NegativeY => Caps2::NegativeY,
+            Self::PositiveZ => Caps2::PositiveZ,
+            Self::NegativeZ => Caps2::NegativeZ,
+        }
+    }
+}
+
+/// A single image in a DDS file
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Surface {
+    /// Array slice, or cube index for cube map arrays
+    pub layer: u32,
+    pub face: Option<CubeFace>,
+    /// 0 is the full size image
+    pub mip_level: u32,
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Rgba>,
+}
+
+/// Array size and the faces of each array element stored in the file, in storage order
+fn layers(
+    header: &Header,
+    header_extra: Option<&HeaderExtra>,
+) -> Result<(u32, Vec<Option<CubeFace>>), Error> {
+    let caps2 = Caps2(header.caps2);
+
+    let is_volume = match header_extra {
+        Some(extra) => extra.resource_dimension == ResourceDimension::Texture3D,
+        None => caps2.has_flag(Caps2::Volume),
+    };
+    if is_volume {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            "Volume textures are not supported",
+        ));
+    }
+
+    let faces = match header_extra {
+        Some(extra) if extra.misc_flag & MISC_TEXTURE_CUBE != 0 => CubeFace::ALL.map(Some).to_vec(),
+        None if caps2.has_flag(Caps2::Cubemap) => CubeFace::ALL
+            .into_iter()
+            .filter(|face| caps2.has_flag(face.flag()))
+            .map(Some)
+            .collect(),
+        _ => vec![None],
+    };
+
+    let array_size = header_extra.map_or(1, |extra| extra.array_size.max(1));
+
+    Ok((array_size, faces))
+}
+
+/// Mip levels of a `width` by `height` image down to 1x1, at most 32
+fn max_mip_levels(width: u32, height: u32) -> u32 {
+    u32::BITS - width.max(height).leading_zeros()
+}
+
+/// Decodes every surface, each layer is stored with all of its mip levels
+pub fn read_surfaces(
+    format: Format,
+    header: &Header,
+    header_extra: Option<&HeaderExtra>,
+    data: &[u8],
+) -> Result<Vec<Surface>, Error> {
+    if header.width == 0 || header.height == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("DDS is {}x{}", header.width, header.height),
+        ));
+    }
+
+    // Files may claim more levels than the size allows
+    let mip_count = header
+        .mip_map_count
+        .clamp(1, max_mip_levels(header.width, header.height));
+    let mip_size = |mip_level: u32| {
+        let size = |x: u32| (x >> mip_level).max(1);
+        (size(header.width), size(header.height))
+    };
+
+    // Every layer needs its data, check before trusting the header's array size
+    let (array_size, faces) = layers(header, header_extra)?;
+    let layer_size = (0..mip_count).try_fold(0usize, |acc, mip_level| {
+        let (width, height) = mip_size(mip_level);
+        acc.checked_add(format.data_size(width, height)?)
+    });
+    let needed = layer_size.and_then(|x| {
+        x.checked_mul(faces.len())?
+            .checked_mul(usize::try_from(array_size).ok()?)
+    });
+    match needed {
+        Some(needed) if needed <= data.len() => {}
+        Some(needed) => {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "DDS with {array_size} array elements needs {needed} bytes, it has {}",
+                    data.len()
+                ),
+            ));
+        }
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "DDS surfaces are too large",
+            ));
+        }
+    }
+
+    let mut offset = 0;
+    let mut surfaces = vec![];
+
+    let layers = (0..array_size).flat_map(|layer| faces.iter().map(move |face| (layer, *face)));
+    for (layer, face) in layers {
+        for mip_level in 0..mip_count {
+            let (width, height) = mip_size(mip_level);
+
+            let pixels = decode::decode(format, width, height, data.get(offset..).unwrap_or(&[]))?;
+
+            // Checked by `decode`
+            offset += format.data_size(width, height).unwrap_or_default();
+
+            surfaces.push(Surface {
+                layer,
+                face,
+                mip_level,
+                width,
+                height,
+                pixels,
+            });
+        }
+    }
+
+    Ok(surfaces)
+}
@@ -198,6 +198,26 @@ impl Field {
     impl_expect_field!(ref Struct, Struct);
     impl_expect_field!(ref Void, Void);
     impl_expect_field!(Word, u16);
+
+    /// Clone without sharing the [`StructField`](super::r#struct::StructField) handles of nested structs
+    pub fn deep_clone(&self) -> Self {
+        match self {
+            Field::Struct(s) => Field::Struct(s.deep_clone()),
+            Field::List(list) => Field::List(list.iter().map(Struct::deep_clone).collect()),
+            x => x.clone(),
+        }
+    }
+
+    /// Compare values, ignoring where nested structs were read from
+    pub fn eq_content(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Field::Struct(lhs), Field::Struct(rhs)) => lhs.eq_content(rhs),
+            (Field::List(lhs), Field::List(rhs)) => {
+                lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(l, r)| l.eq_content(r))
+            }
+            (lhs, rhs) => lhs == rhs,
+        }
+    }
 }
 
 #[derive(PartialEq, Clone)]
@@ -13,6 +13,7 @@ pub mod bin;
 pub mod exo_string;
 pub mod field;
 pub mod label;
+pub mod path;
 pub mod r#struct;
 pub mod void;
 use r#struct::Struct;
@@ -187,6 +188,22 @@ impl Gff {
         })
     }
 
+    /// Copy with new field handles, edits to the copy don't affect `self`
+    pub fn deep_clone(&self) -> Self {
+        Self {
+            file_type: self.file_type,
+            file_version: self.file_version,
+            root: self.root.deep_clone(),
+        }
+    }
+
+    /// Compare headers and field values, see [`Struct::eq_content`]
+    pub fn eq_content(&self, other: &Self) -> bool {
+        self.file_type == other.file_type
+            && self.file_version == other.file_version
+            && self.root.eq_content(&other.root)
+    }
+
     pub fn to_binary(&self) -> bin::Gff {
         bin::Gff::from_data(self)
     }
@@ -0,0 +1,305 @@
+// Field paths name a field by the labels leading to it, with list entries
+// selected by index, e.g. `ClassList[0].KnownList1[2].Spell`.
+
+use super::{
+    exo_string::{ExoLocString, ExoLocSubString, ExoString},
+    field::{Field, U32Char},
+    r#struct::{Struct, StructField},
+};
+use crate::{
+    error::Error,
+    files::{Gender, Language, res_ref::ResRef},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSegment {
+    pub label: String,
+    /// Entry of a list field, or `None` for the field itself
+    pub index: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldPath(pub Vec<PathSegment>);
+impl std::str::FromStr for FieldPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::ParseError(format!("Invalid field path: {s}"));
+
+        let segments = s
+            .split('.')
+            .map(|segment| {
+                let (label, index) = match segment.split_once('[') {
+                    Some((label, index)) => {
+                        let index = index.strip_suffix(']').ok_or_else(invalid)?;
+                        (label, Some(index.parse().map_err(|_| invalid())?))
+                    }
+                    None => (segment, None),
+                };
+
+                if label.is_empty() {
+                    return Err(invalid());
+                }
+
+                Ok(PathSegment {
+                    label: label.to_string(),
+                    index,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self(segments))
+    }
+}
+impl std::fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+
+            f.write_str(&segment.label)?;
+
+            if let Some(index) = segment.index {
+                write!(f, "[{index}]")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Struct {
+    /// Finds the field at `path`, the last segment can't have an index
+    /// since list entries are structs rather than fields
+    pub fn find_path(&self, path: &FieldPath) -> Result<StructField, Error> {
+        let not_found = |i: usize| {
+            let path = FieldPath(path.0[..=i].to_vec());
+            Error::ParseError(format!("Field not found: {path}"))
+        };
+
+        let (last, parents) = path
+            .0
+            .split_last()
+            .ok_or_else(|| Error::ParseError("Empty field path".into()))?;
+
+        if last.index.is_some() {
+            return Err(Error::ParseError(format!(
+                "{path} is a list entry rather than a field"
+            )));
+        }
+
+        let mut current = self.clone();
+
+        for (i, segment) in parents.iter().enumerate() {
+            let field = current
+                .find_direct(&segment.label)
+                .ok_or_else(|| not_found(i))?;
+
+            current = field.read_field(|f| match (f, segment.index) {
+                (Field::Struct(s), None) => Ok(s.clone()),
+                (Field::List(list), Some(index)) => list.get(index).cloned().ok_or_else(|| {
+                    Error::ParseError(format!(
+                        "Index {index} out of range for {} with {} entries",
+                        segment.label,
+                        list.len()
+                    ))
+                }),
+                (f, _) => Err(Error::ParseError(format!(
+                    "Can't follow {} with type {:?}",
+                    FieldPath(path.0[..=i].to_vec()),
+                    f.get_field_type()
+                ))),
+            })?;
+        }
+
+        current
+            .find_direct(&last.label)
+            .ok_or_else(|| not_found(path.0.len() - 1))
+    }
+}
+
+impl Field {
+    /// Parses `value` as the same type as `self`.
+    ///
+    /// Localized strings keep their `str_ref` and are replaced by a single substring
+    pub fn parse_as(&self, value: &str) -> Result<Field, Error> {
+        let field = match self {
+            Field::Byte(_) => Field::Byte(value.parse()?),
+            Field::Char(_) => {
+                let mut c = U32Char(0);
+                match value.parse() {
+                    Ok(x) => c.0 = x,
+                    Err(_) => {
+                        let mut chars = value.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(x), None) => c.set_char(x),
+                            _ => {
+                                return Err(Error::ParseError(format!(
+                                    "Expected a single character but found {value:?}"
+                                )));
+                            }
+                        }
+                    }
+                }
+                Field::Char(c)
+            }
+            Field::Word(_) => Field::Word(value.parse()?),
+            Field::Short(_) => Field::Short(value.parse()?),
+            Field::DWord(_) => Field::DWord(value.parse()?),
+            Field::Int(_) => Field::Int(value.parse()?),
+            Field::DWord64(_) => Field::DWord64(value.parse()?),
+            Field::Int64(_) => Field::Int64(value.parse()?),
+            Field::Float(_) => Field::Float(value.parse()?),
+            Field::Double(_) => Field::Double(value.parse()?),
+            Field::ExoString(_) => Field::ExoString(ExoString(value.to_string())),
+            Field::ResRef(_) => {
+                if value.len() > 32 {
+                    return Err(Error::ParseError(format!(
+                        "ResRef is longer than 32 characters: {value}"
+                    )));
+                }
+                Field::ResRef(ResRef(value.to_string()))
+            }
+            Field::ExoLocString(s) => {
+                let (language, gender) = s
+                    .substrings
+                    .first()
+                    .map(|x| (x.language, x.gender))
+                    .unwrap_or((Language::English, Gender::Masculine));
+
+                Field::ExoLocString(ExoLocString {
+                    str_ref: s.str_ref,
+                    tlk_string: None,
+                    substrings: vec![ExoLocSubString {
+                        gender,
+                        language,
+                        data: value.to_string(),
+                    }],
+                })
+            }
+            Field::Void(_) | Field::Struct(_) | Field::List(_) => {
+                return Err(Error::ParseError(format!(
+                    "Can't set a {:?} field from text",
+                    self.get_field_type()
+                )));
+            }
+        };
+
+        Ok(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::gff::{field::LabeledField, label::Label};
+
+    fn field(label: &str, field: Field) -> StructField {
+        StructField::new(LabeledField::new(Label::from_string(label), field))
+    }
+
+    fn make_struct(fields: Vec<StructField>) -> Struct {
+        Struct {
+            id: 0,
+            original_data_or_data_offset: u32::MAX,
+            fields,
+        }
+    }
+
+    #[test]
+    fn parse_path() {
+        let path: FieldPath = "ClassList[0].KnownList1[12].Spell".parse().unwrap();
+
+        assert_eq!(
+            path.0,
+            [
+                PathSegment {
+                    label: "ClassList".into(),
+                    index: Some(0)
+                },
+                PathSegment {
+                    label: "KnownList1".into(),
+                    index: Some(12)
+                },
+                PathSegment {
+                    label: "Spell".into(),
+                    index: None
+                },
+            ]
+        );
+        assert_eq!(path.to_string(), "ClassList[0].KnownList1[12].Spell");
+
+        assert!("".parse::<FieldPath>().is_err());
+        assert!("A..B".parse::<FieldPath>().is_err());
+        assert!("A[x].B".parse::<FieldPath>().is_err());
+        assert!("A[0".parse::<FieldPath>().is_err());
+    }
+
+    #[test]
+    fn find_path() {
+        let class = make_struct(vec![field("Class", Field::Int(9))]);
+        let root = make_struct(vec![
+            field("Str", Field::Byte(10)),
+            field("ClassList", Field::List(vec![class])),
+        ]);
+
+        let find = |path: &str| root.find_path(&path.parse().unwrap());
+
+        assert_eq!(
+            find("Str").unwrap().read_field(Field::clone),
+            Field::Byte(10)
+        );
+
+        let class = find("ClassList[0].Class").unwrap();
+        class.write().unwrap().field = Field::Int(10);
+        assert_eq!(
+            find("ClassList[0].Class").unwrap().read_field(Field::clone),
+            Field::Int(10)
+        );
+
+        assert!(find("Dex").is_err());
+        assert!(find("ClassList[1].Class").is_err());
+        assert!(find("ClassList[0]").is_err());
+        assert!(find("Str[0].Class").is_err());
+    }
+
+    #[test]
+    fn parse_as() {
+        assert_eq!(Field::Byte(0).parse_as("12"), Ok(Field::Byte(12)));
+        assert!(Field::Byte(0).parse_as("256").is_err());
+        assert_eq!(Field::Short(0).parse_as("-3"), Ok(Field::Short(-3)));
+        assert_eq!(Field::Float(0.0).parse_as("1.5"), Ok(Field::Float(1.5)));
+        assert_eq!(
+            Field::Char(U32Char(0)).parse_as("A"),
+            Ok(Field::Char(U32Char(65)))
+        );
+        assert_eq!(
+            Field::ResRef(ResRef(String::new())).parse_as("po_hu_m_01"),
+            Ok(Field::ResRef(ResRef("po_hu_m_01".into())))
+        );
+        assert!(Field::List(vec![]).parse_as("1").is_err());
+
+        let name = ExoLocString {
+            str_ref: 12,
+            tlk_string: None,
+            substrings: vec![ExoLocSubString {
+                gender: Gender::Feminine,
+                language: Language::French,
+                data: "Old".into(),
+            }],
+        };
+        let Ok(Field::ExoLocString(name)) = Field::ExoLocString(name).parse_as("New") else {
+            panic!("Expected an ExoLocString");
+        };
+        assert_eq!(name.str_ref, 12);
+        assert_eq!(
+            name.substrings,
+            [ExoLocSubString {
+                gender: Gender::Feminine,
+                language: Language::French,
+                data: "New".into(),
+            }]
+        );
+    }
+}
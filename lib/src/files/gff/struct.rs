@@ -162,4 +162,38 @@ impl Struct {
     pub fn find_direct(&self, name: &str) -> Option<StructField> {
         self.fields.iter().find(|f| f.has_label(name)).cloned()
     }
+
+    /// Copy with new field handles, edits to the copy don't affect `self`
+    pub fn deep_clone(&self) -> Self {
+        let fields = self
+            .fields
+            .iter()
+            .map(|f| {
+                let lock = f.read().expect("Field poisoned");
+                StructField::new(LabeledField::new(
+                    lock.label.clone(),
+                    lock.field.deep_clone(),
+                ))
+            })
+            .collect();
+
+        Self {
+            id: self.id,
+            original_data_or_data_offset: self.original_data_or_data_offset,
+            fields,
+        }
+    }
+
+    /// Compare labels and values, unlike [`PartialEq`] this ignores
+    /// `original_data_or_data_offset` so structs from different files can be compared
+    pub fn eq_content(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.fields.len() == other.fields.len()
+            && self.fields.iter().zip(&other.fields).all(|(lhs, rhs)| {
+                let lhs = lhs.read().expect("Field poisoned");
+                let rhs = rhs.read().expect("Field poisoned");
+
+                lhs.label == rhs.label && lhs.field.eq_content(&rhs.field)
+            })
+    }
 }
@@ -1,16 +1,14 @@
-#![allow(unused)]
-
 use crate::error::Error::{self, *};
 
 use roxmltree::Node;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Entry<T> {
     pub name: String,
     pub value: T,
 }
 
-#[derive(Default, Debug, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct Globals {
     pub integers: Vec<Entry<i32>>,
     pub booleans: Vec<Entry<bool>>,
@@ -18,27 +16,117 @@ pub struct Globals {
     pub strings: Vec<Entry<String>>,
 }
 
+/// Value types stored in `globals.xml`
+pub trait GlobalValue: Sized {
+    /// Category element, e.g. `Integers`
+    const CATEGORY: &'static str;
+    /// Entry element, e.g. `Integer`
+    const ELEMENT: &'static str;
+
+    fn entries(globals: &Globals) -> &Vec<Entry<Self>>;
+    fn entries_mut(globals: &mut Globals) -> &mut Vec<Entry<Self>>;
+
+    fn parse(s: &str) -> Result<Self, Error>;
+    fn to_xml_string(&self) -> String;
+}
+
+impl GlobalValue for i32 {
+    const CATEGORY: &'static str = "Integers";
+    const ELEMENT: &'static str = "Integer";
+
+    fn entries(globals: &Globals) -> &Vec<Entry<Self>> {
+        &globals.integers
+    }
+    fn entries_mut(globals: &mut Globals) -> &mut Vec<Entry<Self>> {
+        &mut globals.integers
+    }
+
+    fn parse(s: &str) -> Result<Self, Error> {
+        Ok(s.trim().parse::<i32>()?)
+    }
+    fn to_xml_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl GlobalValue for bool {
+    const CATEGORY: &'static str = "Booleans";
+    const ELEMENT: &'static str = "Boolean";
+
+    fn entries(globals: &Globals) -> &Vec<Entry<Self>> {
+        &globals.booleans
+    }
+    fn entries_mut(globals: &mut Globals) -> &mut Vec<Entry<Self>> {
+        &mut globals.booleans
+    }
+
+    fn parse(s: &str) -> Result<Self, Error> {
+        parse_bool(s.trim())
+    }
+    /// The game writes `1` and `0`
+    fn to_xml_string(&self) -> String {
+        u8::from(*self).to_string()
+    }
+}
+
+impl GlobalValue for f32 {
+    const CATEGORY: &'static str = "Floats";
+    const ELEMENT: &'static str = "Float";
+
+    fn entries(globals: &Globals) -> &Vec<Entry<Self>> {
+        &globals.floats
+    }
+    fn entries_mut(globals: &mut Globals) -> &mut Vec<Entry<Self>> {
+        &mut globals.floats
+    }
+
+    fn parse(s: &str) -> Result<Self, Error> {
+        Ok(s.trim().parse::<f32>()?)
+    }
+    fn to_xml_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl GlobalValue for String {
+    const CATEGORY: &'static str = "Strings";
+    const ELEMENT: &'static str = "String";
+
+    fn entries(globals: &Globals) -> &Vec<Entry<Self>> {
+        &globals.strings
+    }
+    fn entries_mut(globals: &mut Globals) -> &mut Vec<Entry<Self>> {
+        &mut globals.strings
+    }
+
+    fn parse(s: &str) -> Result<Self, Error> {
+        Ok(s.to_string())
+    }
+    fn to_xml_string(&self) -> String {
+        self.clone()
+    }
+}
+
 impl Globals {
     pub fn read(data: &str) -> Result<Self, Error> {
         let mut globals = Globals::default();
 
         let doc = roxmltree::Document::parse(data)?;
+        let root = doc.root_element();
 
-        for child in doc
-            .root()
-            .first_child()
-            .expect("XML doc is empty")
-            .children()
-        {
+        if root.tag_name().name() != "Globals" {
+            return Err(ParseError(format!(
+                "Expected Globals element but found: {}",
+                root.tag_name().name()
+            )));
+        }
+
+        for child in root.children() {
             match child.tag_name().name() {
-                "Integers" => {
-                    add_pairs_to_vec(child, &mut globals.integers, |s| Ok(s.parse::<i32>()?))?
-                }
-                "Booleans" => add_pairs_to_vec(child, &mut globals.booleans, |s| parse_bool(&s))?,
-                "Floats" => {
-                    add_pairs_to_vec(child, &mut globals.floats, |s| Ok(s.parse::<f32>()?))?
-                }
-                "Strings" => add_pairs_to_vec(child, &mut globals.strings, Ok)?,
+                i32::CATEGORY => add_pairs_to_vec(child, &mut globals.integers)?,
+                bool::CATEGORY => add_pairs_to_vec(child, &mut globals.booleans)?,
+                f32::CATEGORY => add_pairs_to_vec(child, &mut globals.floats)?,
+                String::CATEGORY => add_pairs_to_vec(child, &mut globals.strings)?,
 
                 _ => continue,
             }
@@ -46,50 +134,135 @@ impl Globals {
 
         Ok(globals)
     }
+
+    /// Serializes to XML in the layout the game writes
+    pub fn write(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Globals>\n");
+
+        write_category(&mut xml, &self.integers);
+        write_category(&mut xml, &self.booleans);
+        write_category(&mut xml, &self.floats);
+        write_category(&mut xml, &self.strings);
+
+        xml.push_str("</Globals>\n");
+        xml
+    }
+
+    pub fn get<T: GlobalValue>(&self, name: &str) -> Option<&T> {
+        T::entries(self)
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| &e.value)
+    }
+
+    pub fn get_mut<T: GlobalValue>(&mut self, name: &str) -> Option<&mut T> {
+        T::entries_mut(self)
+            .iter_mut()
+            .find(|e| e.name == name)
+            .map(|e| &mut e.value)
+    }
+
+    /// Updates `name` or inserts it if missing.
+    ///
+    /// *Returns*: the previous value
+    pub fn set<T: GlobalValue>(&mut self, name: &str, value: T) -> Option<T> {
+        match self.get_mut(name) {
+            Some(x) => Some(std::mem::replace(x, value)),
+            None => {
+                T::entries_mut(self).push(Entry {
+                    name: name.to_string(),
+                    value,
+                });
+                None
+            }
+        }
+    }
+
+    pub fn remove<T: GlobalValue>(&mut self, name: &str) -> Option<T> {
+        let entries = T::entries_mut(self);
+        let index = entries.iter().position(|e| e.name == name)?;
+
+        Some(entries.remove(index).value)
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+fn write_category<T: GlobalValue>(xml: &mut String, entries: &[Entry<T>]) {
+    use std::fmt::Write;
+
+    if entries.is_empty() {
+        let _ = writeln!(xml, "  <{} />", T::CATEGORY);
+        return;
+    }
+
+    let _ = writeln!(xml, "  <{}>", T::CATEGORY);
+    for e in entries {
+        let _ = writeln!(xml, "    <{}>", T::ELEMENT);
+        let _ = writeln!(xml, "      <Name>{}</Name>", escape_xml(&e.name));
+        let _ = writeln!(
+            xml,
+            "      <Value>{}</Value>",
+            escape_xml(&e.value.to_xml_string())
+        );
+        let _ = writeln!(xml, "    </{}>", T::ELEMENT);
+    }
+    let _ = writeln!(xml, "  </{}>", T::CATEGORY);
 }
 
-fn get_name_value_pairs(category_node: Node) -> Vec<(String, String)> {
+fn get_name_value_pairs(category_node: Node) -> Result<Vec<(String, String)>, Error> {
     category_node
         .children()
-        .filter(|node| node.has_children())
+        .filter(|node| node.is_element())
         .map(|node| {
             let mut name = None;
             let mut value = None;
 
             for e in node.children() {
                 match e.tag_name().name() {
-                    "Name" => name = e.text(),
-                    "Value" => value = e.text(),
+                    "Name" => name = Some(e.text().unwrap_or_default()),
+                    "Value" => value = Some(e.text().unwrap_or_default()),
                     _ => continue,
                 }
             }
 
+            let element = node.tag_name().name();
+            let position = node.document().text_pos_at(node.range().start);
+
             let name =
-                name.unwrap_or_else(|| panic!("Missing name in: {}", node.tag_name().name()));
-            let value =
-                value.unwrap_or_else(|| panic!("Missing value in: {}", node.tag_name().name()));
+                name.ok_or_else(|| ParseError(format!("Missing Name in {element} at {position}")))?;
+            let value = value.ok_or_else(|| {
+                ParseError(format!(
+                    "Missing Value for {name} in {element} at {position}"
+                ))
+            })?;
 
-            (name.into(), value.into())
+            Ok((name.into(), value.into()))
         })
         .collect()
 }
 
-fn add_pairs_to_vec<T>(
-    node: Node,
-    v: &mut Vec<Entry<T>>,
-    parse_fn: impl Fn(String) -> Result<T, Error>,
-) -> Result<(), Error> {
-    let elements = get_name_value_pairs(node)
-        .into_iter()
-        .map(|(name, value)| (name, parse_fn(value)))
-        .map(|(name, value)| match value {
-            Ok(value) => Ok(Entry { name, value }),
-            Err(e) => Err(e),
-        });
+fn add_pairs_to_vec<T: GlobalValue>(node: Node, v: &mut Vec<Entry<T>>) -> Result<(), Error> {
+    for (name, value) in get_name_value_pairs(node)? {
+        let value =
+            T::parse(&value).map_err(|e| ParseError(format!("Invalid value for {name}: {e}")))?;
 
-    for e in elements {
-        let e = e?;
-        v.push(e)
+        v.push(Entry { name, value });
     }
 
     Ok(())
@@ -97,8 +270,8 @@ fn add_pairs_to_vec<T>(
 
 fn parse_bool(b: &str) -> Result<bool, Error> {
     match b.to_ascii_lowercase().as_ref() {
-        "true" => Ok(true),
-        "false" => Ok(false),
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
         x => Err(ParseError(format!("Unexpected bool value: {x}"))),
     }
 }
@@ -155,7 +328,6 @@ mod tests {
         ";
 
         let globals = Globals::read(xml).unwrap();
-        println!("{globals:#?}");
 
         assert_eq!(
             globals,
@@ -203,4 +375,69 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn write_test() {
+        let mut globals = Globals::default();
+        globals.set("Int 1", 1);
+        globals.set("Bool 1", true);
+        globals.set("Bool 2", false);
+        globals.set("Float 1", 1.5f32);
+        globals.set("String <1>", "One & \"Two\"".to_string());
+
+        let xml = globals.write();
+        assert!(xml.contains("<Name>String &lt;1&gt;</Name>"));
+        assert!(xml.contains("<Value>One &amp; &quot;Two&quot;</Value>"));
+        assert!(xml.contains("<Name>Bool 1</Name>\n      <Value>1</Value>"));
+        assert!(xml.contains("<Name>Bool 2</Name>\n      <Value>0</Value>"));
+
+        assert_eq!(Globals::read(&xml).unwrap(), globals);
+        assert_eq!(
+            Globals::read(&Globals::default().write()).unwrap(),
+            Globals::default()
+        );
+    }
+
+    #[test]
+    fn edit_test() {
+        let mut globals = Globals::default();
+
+        assert_eq!(globals.set("Influence", 10), None);
+        assert_eq!(globals.set("Influence", 20), Some(10));
+        assert_eq!(globals.get::<i32>("Influence"), Some(&20));
+        assert_eq!(globals.get::<f32>("Influence"), None);
+
+        *globals.get_mut::<i32>("Influence").unwrap() += 5;
+        assert_eq!(globals.get::<i32>("Influence"), Some(&25));
+
+        assert_eq!(globals.remove::<i32>("Influence"), Some(25));
+        assert_eq!(globals.remove::<i32>("Influence"), None);
+        assert!(globals.integers.is_empty());
+    }
+
+    #[test]
+    fn read_error_test() {
+        let cases = [
+            "<Globals><Integers>",
+            "<NotGlobals />",
+            "<Globals><Integers><Integer><Value>1</Value></Integer></Integers></Globals>",
+            "<Globals><Integers><Integer><Name>A</Name></Integer></Integers></Globals>",
+            "<Globals><Integers><Integer><Name>A</Name><Value>x</Value></Integer></Integers></Globals>",
+            "<Globals><Booleans><Boolean><Name>A</Name><Value>maybe</Value></Boolean></Booleans></Globals>",
+        ];
+
+        for xml in cases {
+            assert!(
+                matches!(Globals::read(xml), Err(ParseError(_))),
+                "Expected error for {xml}"
+            );
+        }
+
+        let empty_string =
+            "<Globals><Strings><String><Name>A</Name><Value /></String></Strings></Globals>";
+        assert_eq!(
+            Globals::read(empty_string).unwrap().get::<String>("A"),
+            Some(&String::new())
+        );
+    }
 }
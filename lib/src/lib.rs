@@ -4,4 +4,5 @@ mod tests;
 pub mod error;
 pub mod files;
 pub mod globals;
+pub mod save;
 pub mod utils;
@@ -0,0 +1,292 @@
+// A `.bic` holds the same creature struct as a `Mod_PlayerList` entry,
+// minus the fields tied to the module the player is in.
+
+use super::{
+    PLAYER_LIST_FIELD, ResGff, SaveGame,
+    companions::{is_shared_field, player_list_entries},
+};
+use crate::{
+    error::{Error, IntoError},
+    files::gff::{
+        FixedSizeString, Gff,
+        field::{Field, LabeledField},
+        r#struct::{Struct, StructField},
+    },
+};
+use std::path::{Path, PathBuf};
+
+pub const BIC_FILE_TYPE: &str = "BIC ";
+pub const BIC_FILE_VERSION: &str = "V3.2";
+pub const LOCAL_VAULT: &str = "localvault";
+pub const PORTRAITS: &str = "portraits";
+
+/// Fields only stored in a `Mod_PlayerList` entry
+const PLAYER_LIST_ONLY_FIELDS: &[&str] = &[
+    "Mod_FirstName",
+    "Mod_LastName",
+    "PersonalRepList",
+    "ReputationList",
+    "EffectList",
+    "VarTable",
+    "ActionList",
+    "Mod_ModuleList",
+];
+
+/// `Mod_FirstName`/`Mod_LastName` mirror these fields
+const NAME_FIELDS: &[(&str, &str)] =
+    &[("Mod_FirstName", "FirstName"), ("Mod_LastName", "LastName")];
+
+fn fixed_string(s: &str) -> Result<FixedSizeString<4>, Error> {
+    let bytes = s
+        .as_bytes()
+        .try_into()
+        .map_err(|_| Error::WriteError(format!("Invalid file header: {s}")))?;
+
+    FixedSizeString::new(bytes)
+}
+
+fn label_of(field: &StructField) -> String {
+    field
+        .read()
+        .expect("Field poisoned")
+        .label
+        .as_str()
+        .to_string()
+}
+
+fn deep_clone_field(field: &StructField) -> StructField {
+    let lock = field.read().expect("Field poisoned");
+    StructField::new(LabeledField::new(
+        lock.label.clone(),
+        lock.field.deep_clone(),
+    ))
+}
+
+fn find_field(s: &Struct, label: &str) -> Option<StructField> {
+    s.fields.iter().find(|f| f.has_label(label)).cloned()
+}
+
+/// Converts a `Mod_PlayerList` entry to a `.bic`
+pub fn player_to_bic(entry: &Struct) -> Result<Gff, Error> {
+    let fields = entry
+        .fields
+        .iter()
+        .filter(|f| !PLAYER_LIST_ONLY_FIELDS.contains(&label_of(f).as_str()))
+        .map(deep_clone_field)
+        .collect();
+
+    Ok(Gff {
+        file_type: fixed_string(BIC_FILE_TYPE)?,
+        file_version: fixed_string(BIC_FILE_VERSION)?,
+        root: Struct {
+            id: u32::MAX,
+            original_data_or_data_offset: 0,
+            fields,
+        },
+    })
+}
+
+/// Builds a `Mod_PlayerList` entry from `bic`, taking the fields only stored in the
+/// player list and the player's position in the module from `entry`
+pub fn bic_to_player(bic: &Gff, entry: &Struct) -> Result<Struct, Error> {
+    if bic.file_type.to_str() != BIC_FILE_TYPE {
+        return Err(Error::ParseError(format!(
+            "Expected a BIC file but found {:?}",
+            bic.file_type.to_str()
+        )));
+    }
+
+    let mut fields = entry
+        .fields
+        .iter()
+        .map(|f| {
+            let lock = f.read().expect("Field poisoned");
+            let label = lock.label.as_str();
+
+            let bic_label = NAME_FIELDS
+                .iter()
+                .find(|(mod_label, _)| *mod_label == label)
+                .map_or(label, |(_, bic_label)| bic_label);
+
+            let value = match find_field(&bic.root, bic_label) {
+                Some(bic_field) if bic_label != label || is_shared_field(label) => {
+                    bic_field.read_field(Field::deep_clone)
+                }
+                _ => lock.field.deep_clone(),
+            };
+
+            StructField::new(LabeledField::new(lock.label.clone(), value))
+        })
+        .collect::<Vec<_>>();
+
+    // Fields the existing entry didn't have
+    fields.extend(
+        bic.root
+            .fields
+            .iter()
+            .filter(|f| find_field(entry, &label_of(f)).is_none())
+            .map(deep_clone_field),
+    );
+
+    let entry = Struct {
+        id: entry.id,
+        original_data_or_data_offset: entry.original_data_or_data_offset,
+        fields,
+    };
+
+    if let Some(is_pc) = find_field(&entry, "IsPC") {
+        is_pc.write().expect("Field poisoned").field = Field::Byte(1);
+    }
+
+    Ok(entry)
+}
+
+impl ResGff {
+    /// `Mod_PlayerList` entry `index` as a `.bic`
+    pub fn export_player(&self, index: usize) -> Result<Gff, Error> {
+        let entries = self
+            .player_list()
+            .map(player_list_entries)
+            .unwrap_or_default();
+
+        let entry = entries
+            .get(index)
+            .ok_or_else(|| Error::WriteError(format!("Player {index} not found")))?;
+
+        player_to_bic(entry)
+    }
+
+    /// Replaces `Mod_PlayerList` entry `index` with `bic`
+    pub fn import_player(&mut self, index: usize, bic: &Gff) -> Result<(), Error> {
+        let player_list = self
+            .player_list()
+            .ok_or_else(|| Error::ParseError(format!("Missing {}", super::PLAYER_LIST)))?;
+
+        import_player(player_list, index, bic)
+    }
+}
+
+/// Replaces `Mod_PlayerList` entry `index` of `player_list` with `bic`
+pub fn import_player(player_list: &Gff, index: usize, bic: &Gff) -> Result<(), Error> {
+    let list = player_list
+        .root
+        .find_direct(PLAYER_LIST_FIELD)
+        .ok_or_else(|| Error::ParseError(format!("Missing {PLAYER_LIST_FIELD}")))?;
+
+    let mut lock = list.write().expect("Field poisoned");
+    let entries = match &mut lock.field {
+        Field::List(x) => x,
+        x => {
+            return Err(Error::ParseError(format!(
+                "Expected {PLAYER_LIST_FIELD} to be a list but found {:?}",
+                x.get_field_type()
+            )));
+        }
+    };
+
+    let entry = entries
+        .get_mut(index)
+        .ok_or_else(|| Error::ParseError(format!("Player {index} not found")))?;
+
+    *entry = bic_to_player(bic, entry)?;
+
+    Ok(())
+}
+
+impl SaveGame {
+    /// User folder containing the `saves` folder
+    pub fn user_dir(&self) -> Option<&Path> {
+        self.path.parent()?.parent()
+    }
+
+    /// `localvault` in the same user folder as the `saves` folder
+    pub fn local_vault_dir(&self) -> Option<PathBuf> {
+        Some(self.user_dir()?.join(LOCAL_VAULT))
+    }
+
+    /// Custom portraits in the same user folder as the `saves` folder
+    pub fn portraits_dir(&self) -> Option<PathBuf> {
+        Some(self.user_dir()?.join(PORTRAITS))
+    }
+}
+
+pub fn read_bic(path: &Path) -> Result<Gff, Error> {
+    let file = std::fs::File::open(path).into_parse_error()?;
+    Gff::read_without_tlk(std::io::BufReader::new(file))
+}
+
+pub fn write_bic(bic: &Gff, path: &Path) -> Result<(), Error> {
+    let mut buf = vec![];
+    bic.write(&mut buf)?;
+    std::fs::write(path, buf).into_write_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn read_gff(data: &[u8]) -> Gff {
+        Gff::read_without_tlk(Cursor::new(data)).unwrap()
+    }
+
+    fn write_gff(gff: &Gff) -> Vec<u8> {
+        let mut buf = vec![];
+        gff.write(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn export_test() {
+        let player_list = read_gff(include_bytes!("../tests/files/playerlist.ifo"));
+        let entry = &player_list_entries(&player_list)[0];
+
+        let bic = player_to_bic(entry).unwrap();
+
+        assert_eq!(write_gff(&bic), include_bytes!("../tests/files/player.bic"),);
+    }
+
+    #[test]
+    fn import_test() {
+        let player_list_data = include_bytes!("../tests/files/playerlist.ifo");
+        let player_list = read_gff(player_list_data);
+        let bic = read_gff(include_bytes!("../tests/files/player.bic"));
+
+        let entry = &player_list_entries(&player_list)[0];
+        let imported = bic_to_player(&bic, entry).unwrap();
+        assert!(imported.eq_content(entry));
+
+        // Fields kept from the entry aren't shared with it
+        let var_table = imported.find_direct("VarTable").unwrap();
+        var_table.write().unwrap().field = Field::List(vec![]);
+        assert!(!imported.eq_content(entry));
+
+        assert!(bic_to_player(&player_list, entry).is_err());
+    }
+
+    #[test]
+    fn import_name_test() {
+        let player_list = read_gff(include_bytes!("../tests/files/playerlist.ifo"));
+        let bic = read_gff(include_bytes!("../tests/files/player.bic"));
+
+        let first_name = bic.root.find_direct("FirstName").unwrap();
+        match &mut first_name.write().unwrap().field {
+            Field::ExoLocString(s) => s.substrings[0].data = "Imported".to_string(),
+            x => panic!("Unexpected field: {x:?}"),
+        }
+
+        let entry = &player_list_entries(&player_list)[0];
+        let imported = bic_to_player(&bic, entry).unwrap();
+
+        let read_name = |label| {
+            imported
+                .find_direct(label)
+                .unwrap()
+                .read_field(|f| f.expect_exolocstring().unwrap().substrings[0].data.clone())
+        };
+
+        assert_eq!(read_name("FirstName"), "Imported");
+        assert_eq!(read_name("Mod_FirstName"), "Imported");
+        assert_eq!(read_name("Mod_LastName"), "Tallow");
+    }
+}
@@ -0,0 +1,344 @@
+// Companions in the party are stored twice, as an entry in `Mod_PlayerList`
+// and as their own `.ros` file. The game reads both, so an edit made to only
+// one copy is reverted or duplicated when the save is loaded.
+
+use super::{GffMember, MemberData, ResGff};
+use crate::{
+    error::Error,
+    files::gff::{
+        Gff,
+        r#struct::{Struct, StructField},
+    },
+};
+use std::path::Path;
+
+pub const PLAYER_LIST_FIELD: &str = "Mod_PlayerList";
+
+/// Per-instance state that is expected to differ between the two copies
+const RUNTIME_FIELDS: &[&str] = &[
+    "ObjectId",
+    "AreaId",
+    "XPosition",
+    "YPosition",
+    "ZPosition",
+    "XOrientation",
+    "YOrientation",
+    "ZOrientation",
+    "ActionList",
+    "CombatRoundData",
+    "PerceptionList",
+    "PersonalRepList",
+    "AnimationDay",
+    "AnimationTime",
+    "AmbientAnimState",
+    "BumpState",
+    "SitObject",
+    "Listening",
+    "MasterID",
+];
+
+pub(super) fn is_shared_field(label: &str) -> bool {
+    !label.starts_with("Mod_") && !RUNTIME_FIELDS.iter().any(|x| x.eq_ignore_ascii_case(label))
+}
+
+/// Entries of `Mod_PlayerList`, the returned structs share their fields with `player_list`
+pub fn player_list_entries(player_list: &Gff) -> Vec<Struct> {
+    player_list
+        .root
+        .find_direct(PLAYER_LIST_FIELD)
+        .and_then(|f| f.read_field(|f| f.try_list().cloned()))
+        .unwrap_or_default()
+}
+
+fn read_string(s: &Struct, label: &str) -> Option<String> {
+    s.find_direct(label)?
+        .read_field(|f| f.try_exostring().map(|x| x.0.clone()))
+        .filter(|x| !x.is_empty())
+}
+
+/// Names a creature can be matched by: `RosterTag` then `Tag`
+fn creature_keys(s: &Struct) -> Vec<String> {
+    ["RosterTag", "Tag"]
+        .into_iter()
+        .filter_map(|label| read_string(s, label))
+        .collect()
+}
+
+fn find_field(s: &Struct, label: &str) -> Option<StructField> {
+    s.fields
+        .iter()
+        .find(|f| f.has_label_case_insensitive(label))
+        .cloned()
+}
+
+/// Labels of direct fields present in both structs
+fn shared_labels(lhs: &Struct, rhs: &Struct) -> Vec<String> {
+    lhs.fields
+        .iter()
+        .filter_map(|f| {
+            let label = f.read().ok()?.label.as_str().to_string();
+            (is_shared_field(&label) && find_field(rhs, &label).is_some()).then_some(label)
+        })
+        .collect()
+}
+
+fn field_eq(lhs: &StructField, rhs: &StructField) -> bool {
+    let lhs = lhs.read().expect("Field poisoned");
+    let rhs = rhs.read().expect("Field poisoned");
+    lhs.field.eq_content(&rhs.field)
+}
+
+fn copy_field(from: &StructField, to: &StructField) {
+    let value = from.read_field(|f| f.deep_clone());
+    to.write().expect("Field poisoned").field = value;
+}
+
+/// A companion found in both `Mod_PlayerList` and a `.ros` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompanionCopies {
+    /// Tag the two copies were matched by
+    pub tag: String,
+    /// Index into `Mod_PlayerList`
+    pub player_index: usize,
+    /// Name of the `.ros` member
+    pub ros_name: String,
+}
+
+/// Fields that already differ between the two copies of a companion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompanionMismatch {
+    pub copies: CompanionCopies,
+    pub fields: Vec<String>,
+}
+
+impl ResGff {
+    fn gff_member(&self, name: &str) -> Option<&GffMember> {
+        match &self.get(name)?.data {
+            MemberData::Gff(x) => Some(x),
+            MemberData::Raw(_) => None,
+        }
+    }
+
+    /// Companions with an entry in `Mod_PlayerList`, matched to their `.ros`
+    /// by `RosterTag`, `Tag` or the roster name
+    pub fn companion_copies(&self) -> Vec<CompanionCopies> {
+        let Some(player_list) = self.player_list() else {
+            return vec![];
+        };
+
+        let rosters = self
+            .roster_members()
+            .map(|(name, gff)| {
+                let mut keys = creature_keys(&gff.root);
+                if let Some(stem) = Path::new(name).file_stem() {
+                    keys.push(stem.to_string_lossy().into_owned());
+                }
+
+                (name, keys)
+            })
+            .collect::<Vec<_>>();
+
+        player_list_entries(player_list)
+            .iter()
+            .enumerate()
+            .filter_map(|(player_index, entry)| {
+                creature_keys(entry).into_iter().find_map(|tag| {
+                    let (ros_name, _) = rosters
+                        .iter()
+                        .find(|(_, keys)| keys.iter().any(|k| k.eq_ignore_ascii_case(&tag)))?;
+
+                    Some(CompanionCopies {
+                        tag,
+                        player_index,
+                        ros_name: ros_name.to_string(),
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Both copies of `copies` as (*player list entry*, *`.ros` root*)
+    fn companion_structs(&self, copies: &CompanionCopies) -> Option<(Struct, Struct)> {
+        let entry = player_list_entries(self.player_list()?)
+            .into_iter()
+            .nth(copies.player_index)?;
+        let ros = self.gff(&copies.ros_name)?.root.clone();
+
+        Some((entry, ros))
+    }
+
+    /// Companions whose two copies disagree, ignoring per-instance state like position
+    pub fn companion_mismatches(&self) -> Vec<CompanionMismatch> {
+        self.companion_copies()
+            .into_iter()
+            .filter_map(|copies| {
+                let (entry, ros) = self.companion_structs(&copies)?;
+
+                let fields = shared_labels(&entry, &ros)
+                    .into_iter()
+                    .filter(
+                        |label| match (find_field(&entry, label), find_field(&ros, label)) {
+                            (Some(lhs), Some(rhs)) => !field_eq(&lhs, &rhs),
+                            _ => false,
+                        },
+                    )
+                    .collect::<Vec<_>>();
+
+                (!fields.is_empty()).then_some(CompanionMismatch { copies, fields })
+            })
+            .collect()
+    }
+
+    /// Copies fields edited since the last save from one copy of each companion to the other.
+    ///
+    /// When both copies of a field were edited the player list entry wins
+    pub fn sync_companions(&self) -> Result<(), Error> {
+        let original = |name: &str| self.gff_member(name).map(GffMember::original_gff);
+
+        let Some(original_list) = original(super::PLAYER_LIST) else {
+            return Ok(());
+        };
+        let original_entries = player_list_entries(original_list);
+
+        for copies in self.companion_copies() {
+            let Some((entry, ros)) = self.companion_structs(&copies) else {
+                continue;
+            };
+            let Some(original_entry) = original_entries.get(copies.player_index) else {
+                continue;
+            };
+            let Some(original_ros) = original(&copies.ros_name) else {
+                continue;
+            };
+
+            // Skip entries that were replaced since the last save
+            if creature_keys(original_entry) != creature_keys(&entry) {
+                continue;
+            }
+
+            for label in shared_labels(&entry, &ros) {
+                let fields = (
+                    find_field(&entry, &label),
+                    find_field(&ros, &label),
+                    find_field(original_entry, &label),
+                    find_field(&original_ros.root, &label),
+                );
+
+                let (Some(entry_field), Some(ros_field), orig_entry, orig_ros) = fields else {
+                    continue;
+                };
+
+                let edited = |current: &StructField, original: Option<StructField>| {
+                    original.is_none_or(|original| !field_eq(current, &original))
+                };
+
+                if edited(&entry_field, orig_entry) {
+                    copy_field(&entry_field, &ros_field);
+                } else if edited(&ros_field, orig_ros) {
+                    copy_field(&ros_field, &entry_field);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::gff::field::Field;
+
+    fn read_res_gff() -> ResGff {
+        ResGff::read_zip(include_bytes!("../tests/files/resgff.zip").to_vec()).unwrap()
+    }
+
+    /// Adds a copy of `khelgar.ros` to the party
+    fn add_khelgar(res_gff: &mut ResGff) {
+        let khelgar = res_gff.gff("khelgar.ros").unwrap().root.deep_clone();
+
+        let list = res_gff
+            .player_list()
+            .unwrap()
+            .root
+            .find_direct(PLAYER_LIST_FIELD)
+            .unwrap();
+
+        match &mut list.write().unwrap().field {
+            Field::List(list) => list.push(khelgar),
+            x => panic!("Unexpected field: {x:?}"),
+        }
+
+        res_gff.mark_saved().unwrap();
+    }
+
+    fn set_byte(s: &Struct, label: &str, value: u8) {
+        find_field(s, label).unwrap().write().unwrap().field = Field::Byte(value);
+    }
+
+    fn get_byte(s: &Struct, label: &str) -> u8 {
+        find_field(s, label)
+            .unwrap()
+            .read_field(|f| f.expect_byte().unwrap())
+    }
+
+    #[test]
+    fn companion_copies_test() {
+        let mut res_gff = read_res_gff();
+        assert!(res_gff.companion_copies().is_empty());
+
+        add_khelgar(&mut res_gff);
+
+        assert_eq!(
+            res_gff.companion_copies(),
+            [CompanionCopies {
+                tag: "khelgar".to_string(),
+                player_index: 1,
+                ros_name: "khelgar.ros".to_string(),
+            }]
+        );
+        assert!(res_gff.companion_mismatches().is_empty());
+    }
+
+    #[test]
+    fn companion_mismatch_test() {
+        let mut res_gff = read_res_gff();
+        add_khelgar(&mut res_gff);
+
+        let ros = res_gff.gff("khelgar.ros").unwrap().root.clone();
+        set_byte(&ros, "Str", 10);
+        find_field(&ros, "XPosition")
+            .unwrap()
+            .write()
+            .unwrap()
+            .field = Field::Float(1.0);
+
+        let mismatches = res_gff.companion_mismatches();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].fields, ["Str"]);
+    }
+
+    #[test]
+    fn sync_companions_test() {
+        let mut res_gff = read_res_gff();
+        add_khelgar(&mut res_gff);
+
+        let copies = &res_gff.companion_copies()[0];
+        let (entry, ros) = res_gff.companion_structs(copies).unwrap();
+
+        set_byte(&entry, "Str", 20);
+        set_byte(&ros, "Dex", 18);
+        res_gff.sync_companions().unwrap();
+
+        assert_eq!(get_byte(&ros, "Str"), 20);
+        assert_eq!(get_byte(&entry, "Dex"), 18);
+        assert!(res_gff.companion_mismatches().is_empty());
+
+        // Fields edited in both copies take the player list value
+        set_byte(&entry, "Con", 8);
+        set_byte(&ros, "Con", 9);
+        res_gff.sync_companions().unwrap();
+
+        assert_eq!(get_byte(&ros, "Con"), 8);
+    }
+}
@@ -0,0 +1,712 @@
+// Save folder layout:
+//   - resgff.zip
+//     - playerlist.ifo
+//     - player.bic
+//     - *.ros [Companion rosters]
+//     - roster.rst
+//   - globals.xml
+//   - savename.txt
+//   - screen.tga
+//   - *.z [Module archives]
+
+mod bic;
+mod companions;
+
+pub use bic::{
+    BIC_FILE_TYPE, BIC_FILE_VERSION, LOCAL_VAULT, PORTRAITS, bic_to_player, import_player,
+    player_to_bic, read_bic, write_bic,
+};
+pub use companions::{CompanionCopies, CompanionMismatch, PLAYER_LIST_FIELD, player_list_entries};
+
+use crate::{
+    error::{Error, IntoError},
+    files::gff::Gff,
+    globals::Globals,
+};
+use std::{
+    io::{Cursor, Read, Write},
+    path::{Path, PathBuf},
+};
+
+pub const RES_GFF: &str = "resgff.zip";
+pub const PLAYER_LIST: &str = "playerlist.ifo";
+pub const ROSTER: &str = "roster.rst";
+pub const GLOBALS: &str = "globals.xml";
+pub const SAVE_NAME: &str = "savename.txt";
+pub const SCREENSHOT: &str = "screen.tga";
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|x| x.to_str())
+        .is_some_and(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+fn is_gff_name(name: &str) -> bool {
+    has_extension(name, &["ifo", "bic", "ros", "rst"])
+}
+
+fn member_error(name: &str, e: Error) -> Error {
+    match e {
+        Error::ParseError(msg) => Error::ParseError(format!("{name}: {msg}")),
+        Error::WriteError(msg) => Error::WriteError(format!("{name}: {msg}")),
+        e => e,
+    }
+}
+
+/// GFF file along with a copy of it as it was read
+#[derive(Debug)]
+pub struct GffMember {
+    pub gff: Gff,
+    /// Separate field handles, so edits through `gff` don't reach it
+    original: Gff,
+}
+impl GffMember {
+    pub fn read(data: Vec<u8>) -> Result<Self, Error> {
+        let gff = Gff::read_without_tlk(Cursor::new(&data))?;
+        let original = gff.deep_clone();
+
+        Ok(Self { gff, original })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![];
+        self.gff.write(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Fields are edited in place through their [`StructField`](crate::files::gff::r#struct::StructField)
+    /// handles, so this compares against the copy made when the file was read
+    pub fn is_modified(&self) -> bool {
+        !self.gff.eq_content(&self.original)
+    }
+
+    /// The file as it was when read or last saved
+    fn original_gff(&self) -> &Gff {
+        &self.original
+    }
+
+    fn mark_saved(&mut self) {
+        self.original = self.gff.deep_clone();
+    }
+}
+
+/// File kept as-is, e.g. images and module archives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawMember {
+    pub data: Vec<u8>,
+    modified: bool,
+}
+impl RawMember {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            modified: false,
+        }
+    }
+
+    pub fn set(&mut self, data: Vec<u8>) {
+        self.data = data;
+        self.modified = true;
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+}
+
+#[derive(Debug)]
+pub enum MemberData {
+    Gff(GffMember),
+    Raw(RawMember),
+}
+
+#[derive(Debug)]
+pub struct Member {
+    /// Path relative to the containing folder or archive, using `/` as a separator
+    pub name: String,
+    pub data: MemberData,
+}
+impl Member {
+    /// Reads GFF files based on their extension, anything else is kept as raw data
+    pub fn read(name: String, data: Vec<u8>) -> Result<Self, Error> {
+        let data = if is_gff_name(&name) {
+            MemberData::Gff(GffMember::read(data).map_err(|e| member_error(&name, e))?)
+        } else {
+            MemberData::Raw(RawMember::new(data))
+        };
+
+        Ok(Self { name, data })
+    }
+
+    pub fn raw(name: String, data: Vec<u8>) -> Self {
+        Self {
+            name,
+            data: MemberData::Raw(RawMember::new(data)),
+        }
+    }
+
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    pub fn gff(&self) -> Option<&Gff> {
+        match &self.data {
+            MemberData::Gff(x) => Some(&x.gff),
+            MemberData::Raw(_) => None,
+        }
+    }
+
+    pub fn raw_data(&self) -> Option<&[u8]> {
+        match &self.data {
+            MemberData::Raw(x) => Some(&x.data),
+            MemberData::Gff(_) => None,
+        }
+    }
+
+    pub fn is_modified(&self) -> bool {
+        match &self.data {
+            MemberData::Gff(x) => x.is_modified(),
+            MemberData::Raw(x) => x.is_modified(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        match &self.data {
+            MemberData::Gff(x) => x.to_bytes().map_err(|e| member_error(&self.name, e)),
+            MemberData::Raw(x) => Ok(x.data.clone()),
+        }
+    }
+
+    fn mark_saved(&mut self) {
+        match &mut self.data {
+            MemberData::Gff(x) => x.mark_saved(),
+            MemberData::Raw(x) => x.modified = false,
+        }
+    }
+}
+
+/// Contents of `resgff.zip`
+#[derive(Debug)]
+pub struct ResGff {
+    /// `false` if the members are loose files in the save folder instead of in `resgff.zip`
+    pub packed: bool,
+    pub members: Vec<Member>,
+    /// Written back as-is if no members were modified
+    original: Option<Vec<u8>>,
+}
+impl ResGff {
+    pub fn read_zip(data: Vec<u8>) -> Result<Self, Error> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(&data)).into_parse_error()?;
+
+        let members = (0..archive.len())
+            .filter_map(|i| {
+                let mut file = match archive.by_index(i) {
+                    Ok(file) => file,
+                    Err(e) => return Some(Err(e).into_parse_error()),
+                };
+
+                if file.is_dir() {
+                    return None;
+                }
+
+                let name = file.name().to_string();
+                let mut buf = Vec::with_capacity(file.size() as usize);
+
+                Some(
+                    file.read_to_end(&mut buf)
+                        .into_parse_error()
+                        .and_then(|_| Member::read(name, buf)),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        drop(archive);
+
+        Ok(Self {
+            packed: true,
+            members,
+            original: Some(data),
+        })
+    }
+
+    pub fn write_zip(&self) -> Result<Vec<u8>, Error> {
+        if !self.is_modified()
+            && let Some(original) = &self.original
+        {
+            return Ok(original.clone());
+        }
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+        for m in &self.members {
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file(&m.name, options).into_write_error()?;
+            writer.write_all(&m.to_bytes()?).into_write_error()?;
+        }
+
+        let buf = writer.finish().into_write_error()?;
+        Ok(buf.into_inner())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Member> {
+        self.members.iter().find(|m| m.has_name(name))
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Member> {
+        self.members.iter_mut().find(|m| m.has_name(name))
+    }
+
+    pub fn gff(&self, name: &str) -> Option<&Gff> {
+        self.get(name).and_then(Member::gff)
+    }
+
+    pub fn player_list(&self) -> Option<&Gff> {
+        self.gff(PLAYER_LIST)
+    }
+
+    pub fn roster(&self) -> Option<&Gff> {
+        self.gff(ROSTER)
+    }
+
+    /// Companion `.ros` files as (*file name*, *file*)
+    pub fn roster_members(&self) -> impl Iterator<Item = (&str, &Gff)> {
+        self.members
+            .iter()
+            .filter(|m| has_extension(&m.name, &["ros"]))
+            .filter_map(|m| Some((m.name.as_str(), m.gff()?)))
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.members.iter().any(Member::is_modified)
+    }
+
+    fn mark_saved(&mut self) -> Result<(), Error> {
+        for m in &mut self.members {
+            m.mark_saved();
+        }
+
+        if self.packed {
+            self.original = None;
+            self.original = Some(self.write_zip()?);
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads every file under `dir` as (*relative path*, *data*)
+fn read_dir_files(dir: &Path) -> Result<Vec<(String, Vec<u8>)>, Error> {
+    fn read_into(base: &Path, dir: &Path, out: &mut Vec<(String, Vec<u8>)>) -> Result<(), Error> {
+        let mut entries = dir
+            .read_dir()
+            .into_parse_error()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_parse_error()?;
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            let file_type = entry.file_type().into_parse_error()?;
+
+            if file_type.is_dir() {
+                read_into(base, &path, out)?;
+            } else {
+                let name = path
+                    .strip_prefix(base)
+                    .into_parse_error()?
+                    .iter()
+                    .map(|x| x.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                let data = std::fs::read(&path).into_parse_error()?;
+                out.push((name, data));
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut files = vec![];
+    read_into(dir, dir, &mut files)?;
+    Ok(files)
+}
+
+fn sibling_path(dir: &Path, suffix: &str) -> PathBuf {
+    let name = dir
+        .file_name()
+        .map(|x| x.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    dir.with_file_name(format!(".{name}.{suffix}"))
+}
+
+/// Save game folder, read and written as one unit
+#[derive(Debug)]
+pub struct SaveGame {
+    pub path: PathBuf,
+    pub res_gff: ResGff,
+    /// Every other file in the save folder
+    pub files: Vec<Member>,
+}
+impl SaveGame {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+
+        let mut res_gff = None;
+        let mut loose_gffs = vec![];
+        let mut files = vec![];
+
+        for (name, data) in read_dir_files(&path)? {
+            if name.eq_ignore_ascii_case(RES_GFF) {
+                res_gff = Some(ResGff::read_zip(data).map_err(|e| member_error(&name, e))?);
+            } else if is_gff_name(&name) && !name.contains('/') {
+                loose_gffs.push((name, data));
+            } else {
+                files.push(Member::raw(name, data));
+            }
+        }
+
+        let res_gff = match res_gff {
+            Some(res_gff) => {
+                files.extend(loose_gffs.into_iter().map(|(n, d)| Member::raw(n, d)));
+                res_gff
+            }
+            None => ResGff {
+                packed: false,
+                members: loose_gffs
+                    .into_iter()
+                    .map(|(n, d)| Member::read(n, d))
+                    .collect::<Result<Vec<_>, _>>()?,
+                original: None,
+            },
+        };
+
+        if res_gff.player_list().is_none() {
+            return Err(Error::ParseError(format!(
+                "Missing {PLAYER_LIST} in save: {}",
+                path.display()
+            )));
+        }
+
+        Ok(Self {
+            path,
+            res_gff,
+            files,
+        })
+    }
+
+    pub fn get_file(&self, name: &str) -> Option<&Member> {
+        self.files.iter().find(|m| m.has_name(name))
+    }
+
+    fn get_file_mut(&mut self, name: &str) -> Option<&mut Member> {
+        self.files.iter_mut().find(|m| m.has_name(name))
+    }
+
+    /// Replaces the contents of `name`, adding it if missing
+    pub fn set_file(&mut self, name: &str, data: Vec<u8>) {
+        match self.get_file_mut(name).map(|m| &mut m.data) {
+            Some(MemberData::Raw(raw)) => raw.set(data),
+            _ => {
+                self.files.retain(|m| !m.has_name(name));
+
+                let mut raw = RawMember::new(data);
+                raw.modified = true;
+
+                self.files.push(Member {
+                    name: name.to_string(),
+                    data: MemberData::Raw(raw),
+                });
+            }
+        }
+    }
+
+    pub fn save_name(&self) -> Option<String> {
+        self.get_file(SAVE_NAME)
+            .and_then(Member::raw_data)
+            .map(|x| String::from_utf8_lossy(x).trim().to_string())
+    }
+
+    pub fn set_save_name(&mut self, name: &str) {
+        self.set_file(SAVE_NAME, name.as_bytes().to_vec());
+    }
+
+    /// `screen.tga` as raw TGA data
+    pub fn screenshot(&self) -> Option<&[u8]> {
+        self.get_file(SCREENSHOT).and_then(Member::raw_data)
+    }
+
+    pub fn globals(&self) -> Option<Result<Globals, Error>> {
+        self.get_file(GLOBALS)
+            .and_then(Member::raw_data)
+            .map(|x| Globals::read(&String::from_utf8_lossy(x)))
+    }
+
+    pub fn set_globals(&mut self, globals: &Globals) {
+        self.set_file(GLOBALS, globals.write().into_bytes());
+    }
+
+    /// Module state archives
+    pub fn module_archives(&self) -> impl Iterator<Item = &Member> {
+        self.files
+            .iter()
+            .filter(|m| has_extension(&m.name, &["z", "zip"]))
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.res_gff.is_modified() || self.files.iter().any(Member::is_modified)
+    }
+
+    /// Names of modified files, members of `resgff.zip` are prefixed with `resgff.zip/`
+    pub fn modified_members(&self) -> Vec<String> {
+        let res_gff = self
+            .res_gff
+            .members
+            .iter()
+            .filter(|m| m.is_modified())
+            .map(|m| match self.res_gff.packed {
+                true => format!("{RES_GFF}/{}", m.name),
+                false => m.name.clone(),
+            });
+
+        let files = self
+            .files
+            .iter()
+            .filter(|m| m.is_modified())
+            .map(|m| m.name.clone());
+
+        res_gff.chain(files).collect()
+    }
+
+    fn write_into(&self, dir: &Path) -> Result<(), Error> {
+        let write_file = |name: &str, data: &[u8]| -> Result<(), Error> {
+            let path = dir.join(name);
+
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).into_write_error()?;
+            }
+
+            std::fs::write(path, data).into_write_error()
+        };
+
+        std::fs::create_dir_all(dir).into_write_error()?;
+
+        if self.res_gff.packed {
+            write_file(RES_GFF, &self.res_gff.write_zip()?)?;
+        } else {
+            for m in &self.res_gff.members {
+                write_file(&m.name, &m.to_bytes()?)?;
+            }
+        }
+
+        for m in &self.files {
+            write_file(&m.name, &m.to_bytes()?)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&mut self) -> Result<(), Error> {
+        let path = self.path.clone();
+        self.save_to(&path)
+    }
+
+    /// Writes the whole save folder to `dest`, replacing it if it exists.
+    ///
+    /// The folder is written next to `dest` first then swapped in,
+    /// so a failed write leaves any existing save untouched.
+    /// Companion edits are copied between `playerlist.ifo` and their `.ros` beforehand
+    pub fn save_to(&mut self, dest: &Path) -> Result<(), Error> {
+        self.res_gff.sync_companions()?;
+
+        let temp_dir = sibling_path(dest, "tmp");
+        let old_dir = sibling_path(dest, "old");
+
+        if temp_dir.exists() {
+            std::fs::remove_dir_all(&temp_dir).into_write_error()?;
+        }
+
+        if let Err(e) = self.write_into(&temp_dir) {
+            let _ = std::fs::remove_dir_all(&temp_dir);
+            return Err(e);
+        }
+
+        if dest.exists() {
+            if old_dir.exists() {
+                std::fs::remove_dir_all(&old_dir).into_write_error()?;
+            }
+
+            std::fs::rename(dest, &old_dir).into_write_error()?;
+        }
+
+        if let Err(e) = std::fs::rename(&temp_dir, dest) {
+            if old_dir.exists() {
+                let _ = std::fs::rename(&old_dir, dest);
+            }
+            return Err(e).into_write_error();
+        }
+
+        if old_dir.exists() {
+            std::fs::remove_dir_all(&old_dir).into_write_error()?;
+        }
+
+        self.res_gff.mark_saved()?;
+        for m in &mut self.files {
+            m.mark_saved();
+        }
+        self.path = dest.to_path_buf();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::gff::field::Field;
+
+    struct TempDir(PathBuf);
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("nwn2-charedit-{name}-{}", std::process::id()));
+
+            if path.exists() {
+                std::fs::remove_dir_all(&path).unwrap();
+            }
+            std::fs::create_dir_all(&path).unwrap();
+
+            Self(path)
+        }
+    }
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn create_save(dir: &Path) -> PathBuf {
+        let save_dir = dir.join("000001 - 01-01-2025-12-00");
+        std::fs::create_dir_all(&save_dir).unwrap();
+
+        std::fs::write(
+            save_dir.join(RES_GFF),
+            include_bytes!("../tests/files/resgff.zip"),
+        )
+        .unwrap();
+        std::fs::write(
+            save_dir.join(GLOBALS),
+            "<Globals><Integers><Integer><Name>Int 1</Name><Value>1</Value></Integer></Integers></Globals>",
+        )
+        .unwrap();
+        std::fs::write(save_dir.join(SAVE_NAME), "Test Save").unwrap();
+        std::fs::write(save_dir.join(SCREENSHOT), [0u8; 18]).unwrap();
+        std::fs::write(save_dir.join("module.z"), [1u8, 2, 3]).unwrap();
+
+        save_dir
+    }
+
+    fn set_first_name(gff: &Gff, name: &str) {
+        let first_name = gff
+            .root
+            .bfs_iter()
+            .find(|x| x.has_label("FirstName"))
+            .unwrap();
+
+        let mut lock = first_name.write().unwrap();
+        match &mut lock.field {
+            Field::ExoLocString(s) => s.substrings[0].data = name.to_string(),
+            x => panic!("Unexpected field: {x:?}"),
+        }
+    }
+
+    fn get_first_name(gff: &Gff) -> String {
+        gff.root
+            .bfs_iter()
+            .find(|x| x.has_label("FirstName"))
+            .unwrap()
+            .read_field(|f| f.expect_exolocstring().unwrap().substrings[0].data.clone())
+    }
+
+    #[test]
+    fn open_test() {
+        let dir = TempDir::new("save-open");
+        let save_dir = create_save(&dir.0);
+
+        let save = SaveGame::open(&save_dir).unwrap();
+
+        assert!(save.res_gff.packed);
+        assert!(save.res_gff.player_list().is_some());
+        assert!(save.res_gff.roster().is_some());
+        assert_eq!(save.res_gff.roster_members().count(), 13);
+
+        assert_eq!(save.save_name().as_deref(), Some("Test Save"));
+        assert_eq!(save.screenshot().map(|x| x.len()), Some(18));
+        assert_eq!(save.globals().unwrap().unwrap().integers.len(), 1);
+        assert_eq!(save.module_archives().count(), 1);
+
+        assert!(!save.is_modified());
+        assert!(save.modified_members().is_empty());
+    }
+
+    #[test]
+    fn save_unmodified_test() {
+        let dir = TempDir::new("save-unmodified");
+        let save_dir = create_save(&dir.0);
+        let dest = dir.0.join("copy");
+
+        let mut save = SaveGame::open(&save_dir).unwrap();
+        save.save_to(&dest).unwrap();
+
+        for name in [RES_GFF, GLOBALS, SAVE_NAME, SCREENSHOT, "module.z"] {
+            assert_eq!(
+                std::fs::read(save_dir.join(name)).unwrap(),
+                std::fs::read(dest.join(name)).unwrap(),
+                "{name} was changed"
+            );
+        }
+
+        assert_eq!(save.path, dest);
+    }
+
+    #[test]
+    fn save_modified_test() {
+        let dir = TempDir::new("save-modified");
+        let save_dir = create_save(&dir.0);
+
+        let mut save = SaveGame::open(&save_dir).unwrap();
+
+        set_first_name(save.res_gff.player_list().unwrap(), "Test");
+        save.set_save_name("Renamed");
+
+        let mut globals = save.globals().unwrap().unwrap();
+        globals.set("Int 1", 5);
+        save.set_globals(&globals);
+
+        assert!(save.is_modified());
+        assert_eq!(
+            save.modified_members(),
+            [
+                format!("{RES_GFF}/{PLAYER_LIST}"),
+                GLOBALS.to_string(),
+                SAVE_NAME.to_string()
+            ]
+        );
+
+        save.save().unwrap();
+        assert!(!save.is_modified());
+        assert!(!sibling_path(&save_dir, "tmp").exists());
+        assert!(!sibling_path(&save_dir, "old").exists());
+
+        let save = SaveGame::open(&save_dir).unwrap();
+        assert_eq!(get_first_name(save.res_gff.player_list().unwrap()), "Test");
+        assert_eq!(save.save_name().as_deref(), Some("Renamed"));
+        assert_eq!(
+            save.globals().unwrap().unwrap().get::<i32>("Int 1"),
+            Some(&5)
+        );
+        assert_eq!(save.res_gff.members.len(), 16);
+    }
+}
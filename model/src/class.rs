@@ -0,0 +1,571 @@
+use crate::{
+    error::Error, ids::class::Class, player::ABILITY_NAMES, skill::SkillId,
+    two_d_array::FileReader2DA,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Fortitude, reflex and will save bonuses
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Saves {
+    pub fort: i8,
+    pub reflex: i8,
+    pub will: i8,
+}
+
+/// Alignments a class is closed to, from `AlignRestrict`, `AlignRstrctType` and `InvertRestrict`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AlignmentRestriction {
+    /// Neutral `0x01`, lawful `0x02`, chaotic `0x04`, good `0x08` and evil `0x10`
+    pub alignments: u8,
+    /// Law and chaos `0x01`, good and evil `0x02`
+    pub axes: u8,
+    /// Whether `alignments` are the only ones allowed instead of the ones barred
+    pub invert: bool,
+}
+impl AlignmentRestriction {
+    const NEUTRAL: u8 = 0x01;
+    const LAWFUL: u8 = 0x02;
+    const CHAOTIC: u8 = 0x04;
+    const GOOD: u8 = 0x08;
+    const EVIL: u8 = 0x10;
+
+    /// Whether a character with `GoodEvil` and `LawfulChaotic` values can take the class
+    pub fn allows(&self, good_evil: u8, lawful_chaotic: u8) -> bool {
+        if self.alignments == 0 {
+            return true;
+        }
+
+        let axis = |value: u8, high: u8, low: u8| match value {
+            70.. => high,
+            31..=69 => Self::NEUTRAL,
+            _ => low,
+        };
+
+        let mut matches = false;
+        if self.axes & 0x01 != 0 {
+            matches |= self.alignments & axis(lawful_chaotic, Self::LAWFUL, Self::CHAOTIC) != 0;
+        }
+        if self.axes & 0x02 != 0 {
+            matches |= self.alignments & axis(good_evil, Self::GOOD, Self::EVIL) != 0;
+        }
+
+        matches == self.invert
+    }
+}
+
+/// Entry requirement of a prestige class, a row of its `cls_pres_*` table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassPrereq {
+    Feat(u16),
+    /// One of the class's `FeatOr` feats is needed
+    FeatOr(u16),
+    Skill {
+        skill: SkillId,
+        ranks: u8,
+    },
+    AttackBonus(u8),
+    /// One of the class's `Race` rows is needed
+    Race(u8),
+    /// Casting arcane spells of this level
+    ArcaneSpell(u8),
+    /// One of the class's `ClassOr` classes is needed
+    ClassOr(Class),
+    ClassNot(Class),
+    /// A requirement this editor doesn't check, like a script variable
+    Other(String),
+}
+impl ClassPrereq {
+    fn from_row(kind: &str, param1: Option<&str>, param2: Option<&str>) -> Option<Self> {
+        fn param<T: TryFrom<i32>>(x: Option<i32>) -> Option<T> {
+            x?.try_into().ok()
+        }
+
+        let param1 = param1.and_then(|x| x.parse::<i32>().ok());
+        let param2 = param2.and_then(|x| x.parse::<i32>().ok());
+
+        Some(match kind.to_ascii_uppercase().as_str() {
+            "FEAT" => Self::Feat(param(param1)?),
+            "FEATOR" => Self::FeatOr(param(param1)?),
+            "SKILL" => Self::Skill {
+                skill: param(param1)?,
+                ranks: param(param2)?,
+            },
+            "BAB" => Self::AttackBonus(param(param1)?),
+            "RACE" => Self::Race(param(param1)?),
+            "ARCSPELL" => Self::ArcaneSpell(param(param1)?),
+            "CLASSOR" => Self::ClassOr(Class(param1?)),
+            "CLASSNOT" => Self::ClassNot(Class(param1?)),
+            _ => Self::Other(kind.to_string()),
+        })
+    }
+}
+
+/// Level progression of a class from `classes.2da` and its `cls_*` tables
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClassInfo {
+    pub label: String,
+    /// Whether players can take the class, monster classes can't
+    pub player_class: bool,
+    pub hit_die: u8,
+    /// Skill points per level before the intelligence modifier
+    pub skill_point_base: u8,
+    /// Highest level of the class, `None` if it's only capped by the character level
+    pub max_level: Option<u16>,
+    /// Base attack bonus at each class level, from `cls_atk_*`
+    pub attack_bonus: Vec<u8>,
+    /// Saves at each class level, from `cls_savthr_*`
+    pub saves: Vec<Saves>,
+    /// Feats granted automatically and the class level they're granted at, from `cls_feat_*`
+    pub granted_feats: Vec<(u16, u16)>,
+    /// Every feat on the class's `cls_feat_*` table, granted or chosen
+    pub class_feats: HashSet<u16>,
+    pub alignment: AlignmentRestriction,
+    /// Whether the class casts arcane spells, from `HasArcane`
+    pub arcane: bool,
+    /// Ability bonus spells are based on, `0..6` for Str to Cha, from `SpellAbil`
+    pub spell_ability: Option<u8>,
+    /// Whether the class prepares a domain spell of each level, from `HasDomains`
+    pub has_domains: bool,
+    /// Bonus feats chosen at each class level, from `cls_bfeat_*`
+    pub bonus_feats: Vec<u8>,
+    /// Spells known of levels 0 to 9 at each class level, from `cls_spkn_*`.
+    /// Empty for classes that learn any number of spells
+    pub spells_known: Vec<[u8; 10]>,
+    /// Spells per day of levels 0 to 9 at each class level, from `cls_spgn_*`
+    pub spells_per_day: Vec<[u8; 10]>,
+    /// Entry requirements of prestige classes, from `cls_pres_*`
+    pub prereqs: Vec<ClassPrereq>,
+}
+impl ClassInfo {
+    /// Value for `level` from a per-level table, tables stop at the last level they change
+    fn at_level<T: Copy + Default>(table: &[T], level: u16) -> T {
+        match level {
+            0 => T::default(),
+            level => table
+                .get(level as usize - 1)
+                .or(table.last())
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn attack_bonus_at(&self, level: u16) -> u8 {
+        Self::at_level(&self.attack_bonus, level)
+    }
+
+    pub fn saves_at(&self, level: u16) -> Saves {
+        Self::at_level(&self.saves, level)
+    }
+
+    pub fn bonus_feats_at(&self, level: u16) -> u8 {
+        match level {
+            0 => 0,
+            level => self
+                .bonus_feats
+                .get(level as usize - 1)
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Spells of each level the class can know at `level`, `None` if it isn't limited
+    pub fn spells_known_at(&self, level: u16) -> Option<[u8; 10]> {
+        (!self.spells_known.is_empty()).then(|| Self::at_level(&self.spells_known, level))
+    }
+
+    /// Spells of each level the class casts per day at `level`, `None` for non-casters
+    pub fn spells_per_day_at(&self, level: u16) -> Option<[u8; 10]> {
+        (!self.spells_per_day.is_empty()).then(|| Self::at_level(&self.spells_per_day, level))
+    }
+
+    pub fn feats_granted_at(&self, level: u16) -> impl Iterator<Item = u16> + '_ {
+        self.granted_feats
+            .iter()
+            .filter(move |(_, x)| *x == level)
+            .map(|(feat, _)| *feat)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClassRecord {
+    pub classes: HashMap<Class, ClassInfo>,
+}
+impl ClassRecord {
+    pub fn new(reader: &mut FileReader2DA) -> Result<Self, Error> {
+        let file_name = "classes.2da";
+        let table = reader.read(file_name)?;
+
+        let [
+            label_idx,
+            player_class_idx,
+            hit_die_idx,
+            skill_point_idx,
+            max_level_idx,
+            attack_idx,
+            saves_idx,
+            feats_idx,
+            align_idx,
+            align_type_idx,
+            invert_idx,
+            arcane_idx,
+            bonus_feats_idx,
+            spells_known_idx,
+            spells_per_day_idx,
+            prereqs_idx,
+        ] = table
+            .find_column_indices([
+                "Label",
+                "PlayerClass",
+                "HitDie",
+                "SkillPointBase",
+                "MaxLevel",
+                "AttackBonusTable",
+                "SavingThrowTable",
+                "FeatsTable",
+                "AlignRestrict",
+                "AlignRstrctType",
+                "InvertRestrict",
+                "HasArcane",
+                "BonusFeatsTable",
+                "SpellKnownTable",
+                "SpellGainTable",
+                "PreReqTable",
+            ])
+            .map_err(|e| Error::MissingTableColumn {
+                file: file_name,
+                column: e,
+            })?;
+        let spell_ability_idx = table.find_column_index("SpellAbil");
+        let domains_idx = table.find_column_index("HasDomains");
+
+        let rows = table
+            .data
+            .row_iter()
+            .enumerate()
+            .filter_map(|(i, row)| {
+                let get = |idx: usize| row.get(idx).and_then(|x| x.as_deref());
+                let table_name = |idx: usize| get(idx).map(|x| x.to_ascii_lowercase());
+
+                let info = ClassInfo {
+                    label: get(label_idx)?.to_string(),
+                    player_class: get(player_class_idx) == Some("1"),
+                    hit_die: get(hit_die_idx)?.parse().ok()?,
+                    skill_point_base: get(skill_point_idx)?.parse().ok()?,
+                    max_level: get(max_level_idx)
+                        .and_then(|x| x.parse().ok())
+                        .filter(|x| *x > 0),
+                    alignment: AlignmentRestriction {
+                        alignments: get(align_idx).and_then(parse_flags).unwrap_or(0),
+                        axes: get(align_type_idx).and_then(parse_flags).unwrap_or(0),
+                        invert: get(invert_idx) == Some("1"),
+                    },
+                    arcane: get(arcane_idx) == Some("1"),
+                    spell_ability: spell_ability_idx.and_then(get).and_then(parse_ability),
+                    has_domains: domains_idx.and_then(get) == Some("1"),
+                    ..Default::default()
+                };
+
+                let tables = [
+                    attack_idx,
+                    saves_idx,
+                    feats_idx,
+                    bonus_feats_idx,
+                    spells_known_idx,
+                    spells_per_day_idx,
+                    prereqs_idx,
+                ];
+                Some((Class(i as i32), info, tables.map(table_name)))
+            })
+            .collect::<Vec<_>>();
+
+        let mut classes = HashMap::new();
+
+        for (
+            class,
+            mut info,
+            [
+                attack,
+                saves,
+                feats,
+                bonus_feats,
+                spells_known,
+                spells_per_day,
+                prereqs,
+            ],
+        ) in rows
+        {
+            // Some rows name tables that were never shipped
+            if let Some(name) = attack {
+                info.attack_bonus = read_attack_bonus(reader, &name).unwrap_or_default();
+            }
+            if let Some(name) = saves {
+                info.saves = read_saves(reader, &name).unwrap_or_default();
+            }
+            if let Some(name) = feats
+                && let Ok((granted, listed)) = read_feats(reader, &name)
+            {
+                info.granted_feats = granted;
+                info.class_feats = listed;
+            }
+            if let Some(name) = bonus_feats {
+                info.bonus_feats = read_bonus_feats(reader, &name).unwrap_or_default();
+            }
+            if let Some(name) = spells_known {
+                info.spells_known =
+                    read_spell_levels(reader, &name, "cls_spkn_*.2da").unwrap_or_default();
+            }
+            if let Some(name) = spells_per_day {
+                info.spells_per_day =
+                    read_spell_levels(reader, &name, "cls_spgn_*.2da").unwrap_or_default();
+            }
+            if let Some(name) = prereqs {
+                info.prereqs = read_prereqs(reader, &name).unwrap_or_default();
+            }
+
+            classes.insert(class, info);
+        }
+
+        Ok(Self { classes })
+    }
+
+    pub fn get(&self, class: Class) -> Result<&ClassInfo, Error> {
+        self.classes
+            .get(&class)
+            .ok_or_else(|| Error::MissingField(format!("Class {class} in classes.2da")))
+    }
+}
+
+/// `SpellAbil` like `WIS` as an ability index
+fn parse_ability(value: &str) -> Option<u8> {
+    ABILITY_NAMES
+        .iter()
+        .position(|x| x.eq_ignore_ascii_case(value))
+        .map(|x| x as u8)
+}
+
+/// `AlignRestrict` style flags, written in hex like `0x15`
+fn parse_flags(value: &str) -> Option<u8> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u8::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+fn read_attack_bonus(reader: &mut FileReader2DA, name: &str) -> Result<Vec<u8>, Error> {
+    let table = reader.read(&format!("{name}.2da"))?;
+    let bab_idx = table
+        .find_column_index("BAB")
+        .ok_or(Error::MissingTableColumn {
+            file: "cls_atk_*.2da",
+            column: "BAB",
+        })?;
+
+    Ok(table
+        .get_column_data(bab_idx)
+        .map_while(|x| x?.parse().ok())
+        .collect())
+}
+
+fn read_saves(reader: &mut FileReader2DA, name: &str) -> Result<Vec<Saves>, Error> {
+    let table = reader.read(&format!("{name}.2da"))?;
+    let [fort_idx, reflex_idx, will_idx] = table
+        .find_column_indices(["FortSave", "RefSave", "WillSave"])
+        .map_err(|e| Error::MissingTableColumn {
+            file: "cls_savthr_*.2da",
+            column: e,
+        })?;
+
+    Ok(table
+        .data
+        .row_iter()
+        .map_while(|row| {
+            let get = |idx: usize| row.get(idx)?.as_deref()?.parse().ok();
+
+            Some(Saves {
+                fort: get(fort_idx)?,
+                reflex: get(reflex_idx)?,
+                will: get(will_idx)?,
+            })
+        })
+        .collect())
+}
+
+/// Feats with the class level they're granted at
+type GrantedFeats = Vec<(u16, u16)>;
+
+/// Feats a `cls_feat_*` table lists, and the ones on list 3 the game grants
+/// without a choice
+fn read_feats(
+    reader: &mut FileReader2DA,
+    name: &str,
+) -> Result<(GrantedFeats, HashSet<u16>), Error> {
+    let table = reader.read(&format!("{name}.2da"))?;
+    let [feat_idx, list_idx, level_idx] = table
+        .find_column_indices(["FeatIndex", "List", "GrantedOnLevel"])
+        .map_err(|e| Error::MissingTableColumn {
+            file: "cls_feat_*.2da",
+            column: e,
+        })?;
+
+    fn get(row: &[Option<String>], idx: usize) -> Option<&str> {
+        row.get(idx)?.as_deref()
+    }
+
+    let granted = table
+        .data
+        .row_iter()
+        .filter(|row| get(row, list_idx) == Some("3"))
+        .filter_map(|row| {
+            let feat = get(row, feat_idx)?.parse().ok()?;
+            // -1 means the feat is never granted
+            let level = get(row, level_idx)?.parse().ok()?;
+            Some((feat, level))
+        })
+        .collect();
+
+    let listed = table
+        .data
+        .row_iter()
+        .filter_map(|row| get(row, feat_idx)?.parse().ok())
+        .collect();
+
+    Ok((granted, listed))
+}
+
+fn read_bonus_feats(reader: &mut FileReader2DA, name: &str) -> Result<Vec<u8>, Error> {
+    let table = reader.read(&format!("{name}.2da"))?;
+    let bonus_idx = table
+        .find_column_index("Bonus")
+        .ok_or(Error::MissingTableColumn {
+            file: "cls_bfeat_*.2da",
+            column: "Bonus",
+        })?;
+
+    Ok(table
+        .get_column_data(bonus_idx)
+        .map_while(|x| x?.parse().ok())
+        .collect())
+}
+
+/// `SpellLevel0` to `SpellLevel9` of each row of a `cls_spkn_*` or `cls_spgn_*` table
+fn read_spell_levels(
+    reader: &mut FileReader2DA,
+    name: &str,
+    file: &'static str,
+) -> Result<Vec<[u8; 10]>, Error> {
+    let table = reader.read(&format!("{name}.2da"))?;
+    let columns = table
+        .find_column_indices([
+            "SpellLevel0",
+            "SpellLevel1",
+            "SpellLevel2",
+            "SpellLevel3",
+            "SpellLevel4",
+            "SpellLevel5",
+            "SpellLevel6",
+            "SpellLevel7",
+            "SpellLevel8",
+            "SpellLevel9",
+        ])
+        .map_err(|e| Error::MissingTableColumn { file, column: e })?;
+
+    // Levels a class can't know spells of are `****`
+    Ok(table
+        .data
+        .row_iter()
+        .map(|row| {
+            columns.map(|idx| {
+                row.get(idx)
+                    .and_then(|x| x.as_deref()?.parse().ok())
+                    .unwrap_or(0)
+            })
+        })
+        .collect())
+}
+
+fn read_prereqs(reader: &mut FileReader2DA, name: &str) -> Result<Vec<ClassPrereq>, Error> {
+    let table = reader.read(&format!("{name}.2da"))?;
+    let [kind_idx, param1_idx, param2_idx] = table
+        .find_column_indices(["ReqType", "ReqParam1", "ReqParam2"])
+        .map_err(|e| Error::MissingTableColumn {
+            file: "cls_pres_*.2da",
+            column: e,
+        })?;
+
+    Ok(table
+        .data
+        .row_iter()
+        .filter_map(|row| {
+            let get = |idx: usize| row.get(idx).and_then(|x| x.as_deref());
+            ClassPrereq::from_row(get(kind_idx)?, get(param1_idx), get(param2_idx))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ClassRow, GameData, class_feats_2da, classes_2da, sorcerer, two_da};
+
+    #[test]
+    fn class_progression() {
+        let sorcerer = ClassRow {
+            feats: "CLS_FEAT_SORC",
+            bonus_feats: "CLS_BFEAT_SORC",
+            ..sorcerer()
+        };
+        let mut game = GameData::new()
+            .arcane_progression()
+            .table("classes.2da", classes_2da(&[(Class::Sorcerer, sorcerer)]))
+            .table(
+                "cls_feat_sorc.2da",
+                class_feats_2da(&[(46, 3, 1), (500, 3, 5), (501, 0, -1)]),
+            )
+            .table(
+                "cls_bfeat_sorc.2da",
+                two_da("Bonus", &[(0, "0"), (1, "0"), (2, "1")]),
+            )
+            .build();
+        let record = ClassRecord::new(&mut game.reader).unwrap();
+
+        // Rows without a hit die are left out
+        assert_eq!(record.classes.len(), 1);
+        assert!(record.get(Class::Barbarian).is_err());
+
+        let info = record.get(Class::Sorcerer).unwrap();
+        assert!(info.player_class);
+        // Tables stop at the last level they change
+        assert_eq!(info.attack_bonus_at(5), 3);
+        assert_eq!(info.saves_at(8).will, 4);
+        assert_eq!(info.saves_at(0), Saves::default());
+        assert_eq!(info.feats_granted_at(5).collect::<Vec<_>>(), [500]);
+        assert_eq!(info.class_feats, [46, 500, 501].into());
+        assert_eq!(
+            (1..=4).map(|x| info.bonus_feats_at(x)).collect::<Vec<_>>(),
+            [0, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn alignment_restrictions() {
+        // Closed to neutral, chaotic and evil characters, like paladins
+        let paladin = AlignmentRestriction {
+            alignments: 0x15,
+            axes: 0x03,
+            invert: false,
+        };
+        assert!(paladin.allows(94, 80));
+        assert!(!paladin.allows(94, 15));
+        // Only open to characters neutral on one of the axes, like druids
+        let druid = AlignmentRestriction {
+            alignments: 0x01,
+            axes: 0x03,
+            invert: true,
+        };
+        assert!(druid.allows(50, 15));
+        assert!(!druid.allows(94, 15));
+    }
+}
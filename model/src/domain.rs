@@ -0,0 +1,80 @@
+use crate::{
+    Tlk, error::Error, ids::spell::Spell, tlk_string_ref::TlkStringRef, two_d_array::FileReader2DA,
+};
+use std::collections::HashMap;
+
+/// A cleric domain, a row of `domains.2da`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Domain {
+    pub label: String,
+    /// `Name` from the tlk, the label if it has none
+    pub name: String,
+    /// Spells the domain adds at spell levels 1 to 9, from `Level_1` to `Level_9`
+    pub spells: [Option<Spell>; 9],
+    /// Feat every cleric with the domain has, from `GrantedFeat`
+    pub granted_feat: Option<u16>,
+}
+impl Domain {
+    /// Domain spell of `spell_level`, level 0 has none
+    pub fn spell_at(&self, spell_level: usize) -> Option<Spell> {
+        spell_level
+            .checked_sub(1)
+            .and_then(|x| self.spells.get(x).copied().flatten())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DomainRecord {
+    pub domains: HashMap<u8, Domain>,
+}
+impl DomainRecord {
+    pub fn new(tlk: &Tlk, reader: &mut FileReader2DA) -> Result<Self, Error> {
+        let file_name = "domains.2da";
+        let table = reader.read(file_name)?;
+
+        let [label_idx, name_idx, feat_idx] = table
+            .find_column_indices(["Label", "Name", "GrantedFeat"])
+            .map_err(|e| Error::MissingTableColumn {
+                file: file_name,
+                column: e,
+            })?;
+        let spell_idx: [Option<usize>; 9] =
+            std::array::from_fn(|i| table.find_column_index(&format!("Level_{}", i + 1)));
+
+        let domains = table
+            .data
+            .row_iter()
+            .enumerate()
+            .filter_map(|(i, row)| {
+                let get = |idx: usize| row.get(idx).and_then(|x| x.as_deref());
+
+                let label = get(label_idx)?.to_string();
+                let name = get(name_idx)
+                    .and_then(|x| x.parse().ok())
+                    .and_then(|x| TlkStringRef::from_id(tlk, x).ok())
+                    .map(|x| x.data)
+                    .filter(|x| !x.is_empty())
+                    .unwrap_or_else(|| label.clone());
+
+                let domain = Domain {
+                    label,
+                    name,
+                    spells: spell_idx
+                        .map(|idx| idx.and_then(get).and_then(|x| x.parse().ok()).map(Spell)),
+                    granted_feat: get(feat_idx).and_then(|x| x.parse().ok()),
+                };
+
+                Some((u8::try_from(i).ok()?, domain))
+            })
+            .collect();
+
+        Ok(Self { domains })
+    }
+
+    pub fn name(&self, domain: u8) -> String {
+        self.domains
+            .get(&domain)
+            .map(|x| x.name.clone())
+            .unwrap_or_else(|| format!("Domain {domain}"))
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{
+    Tlk,
+    error::Error,
+    icon::Icon,
+    ids::class::Class,
+    resources::{IconName, IconPath, join_path},
+    skill::SkillId,
+    tlk_string_ref::TlkStringRef,
+};
+use nwn_lib::files::two_da::{self, DataTable};
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feat {
+    pub label: String,
+    pub name: TlkStringRef,
+    pub desc: Option<TlkStringRef>,
+    pub icon: Option<Icon>,
+    pub prereqs: FeatPrereqs,
+}
+
+pub type FeatId = usize;
+
+/// Requirements for taking a feat, from `feat.2da`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FeatPrereqs {
+    /// `PREREQFEAT1` and `PREREQFEAT2`, all of them are needed
+    pub feats: Vec<FeatId>,
+    /// `OrReqFeat0` to `OrReqFeat4`, one of them is needed
+    pub any_feats: Vec<FeatId>,
+    /// `MINSTR` to `MINCHA` in Str to Cha order, 0 for no minimum
+    pub min_abilities: [u8; 6],
+    pub min_attack_bonus: u8,
+    /// `MinLevel`, counting only the levels of `MinLevelClass` if it's set
+    pub min_level: Option<(u16, Option<Class>)>,
+    /// `REQSKILL` and `REQSKILL2` with their minimum ranks
+    pub skills: Vec<(SkillId, u8)>,
+    /// Whether any class can take the feat, otherwise it has to be on a class's feat table
+    pub all_classes_can_use: bool,
+}
+impl FeatPrereqs {
+    /// Reads the prerequisite columns of `row`, tables without a column don't have the requirement
+    fn from_row(columns: &PrereqColumns, row: &[Option<String>]) -> Self {
+        fn get<T: std::str::FromStr>(row: &[Option<String>], idx: Option<usize>) -> Option<T> {
+            row.get(idx?)?.as_deref()?.parse().ok()
+        }
+
+        let feats = |indices: &[Option<usize>]| -> Vec<FeatId> {
+            indices.iter().filter_map(|idx| get(row, *idx)).collect()
+        };
+
+        let skills = columns
+            .skills
+            .iter()
+            .filter_map(|(skill_idx, ranks_idx)| {
+                let skill = get(row, *skill_idx)?;
+                Some((skill, get(row, *ranks_idx).unwrap_or(1)))
+            })
+            .collect();
+
+        let min_level = get::<u16>(row, columns.min_level)
+            .filter(|x| *x > 0)
+            .map(|level| (level, get(row, columns.min_level_class).map(Class)));
+
+        Self {
+            feats: feats(&columns.feats),
+            any_feats: feats(&columns.any_feats),
+            min_abilities: columns
+                .min_abilities
+                .map(|idx| get(row, idx).unwrap_or_default()),
+            min_attack_bonus: get(row, columns.min_attack_bonus).unwrap_or_default(),
+            min_level,
+            skills,
+            all_classes_can_use: get::<u8>(row, columns.all_classes_can_use) == Some(1),
+        }
+    }
+}
+
+/// Indices of the `feat.2da` columns [`FeatPrereqs`] reads
+struct PrereqColumns {
+    feats: [Option<usize>; 2],
+    any_feats: [Option<usize>; 5],
+    min_abilities: [Option<usize>; 6],
+    min_attack_bonus: Option<usize>,
+    min_level: Option<usize>,
+    min_level_class: Option<usize>,
+    skills: [(Option<usize>, Option<usize>); 2],
+    all_classes_can_use: Option<usize>,
+}
+impl PrereqColumns {
+    fn new(table: &DataTable) -> Self {
+        let idx = |name: &str| {
+            table
+                .columns
+                .iter()
+                .position(|x| x.eq_ignore_ascii_case(name))
+        };
+
+        Self {
+            feats: ["PREREQFEAT1", "PREREQFEAT2"].map(idx),
+            any_feats: [
+                "OrReqFeat0",
+                "OrReqFeat1",
+                "OrReqFeat2",
+                "OrReqFeat3",
+                "OrReqFeat4",
+            ]
+            .map(idx),
+            min_abilities: ["MINSTR", "MINDEX", "MINCON", "MININT", "MINWIS", "MINCHA"].map(idx),
+            min_attack_bonus: idx("MINATTACKBONUS"),
+            min_level: idx("MinLevel"),
+            min_level_class: idx("MinLevelClass"),
+            skills: [
+                (idx("REQSKILL"), idx("ReqSkillMinRanks")),
+                (idx("REQSKILL2"), idx("ReqSkillMinRanks2")),
+            ],
+            all_classes_can_use: idx("ALLCLASSESCANUSE"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FeatRecord {
+    pub feats: HashMap<FeatId, Feat>,
+}
+impl FeatRecord {
+    pub fn new(
+        tlk: &Tlk,
+        game_dir: &Path,
+        icon_paths: &HashMap<IconName, IconPath>,
+    ) -> Result<Self, Error> {
+        let file_name = "feat.2da";
+        let file_path = join_path(
+            game_dir,
+            &["campaigns", "westgate_campaign", "2da", file_name],
+        );
+
+        let table = {
+            let file = File::open(file_path)?;
+            let reader = BufReader::new(file);
+            two_da::parse(reader)?
+        };
+
+        let [label_idx, name_idx, desc_idx, icon_idx] = table
+            .find_column_indices(["LABEL", "FEAT", "DESCRIPTION", "ICON"])
+            .map_err(|e| Error::MissingTableColumn {
+                file: file_name,
+                column: e,
+            })?;
+
+        let prereq_columns = PrereqColumns::new(&table);
+
+        let from_row = |row: &[Option<String>]| -> Option<Feat> {
+            let label = row.get(label_idx)?.clone()?;
+
+            let name_ref = row.get(name_idx)?.as_deref()?;
+            let name_ref = name_ref.parse().ok()?;
+
+            let desc_ref = row.get(desc_idx)?.as_deref()?;
+            let desc_ref = desc_ref.parse().ok();
+
+            let icon = row
+                .get(icon_idx)?
+                .as_deref()
+                .and_then(|name| icon_paths.get(name))
+                .and_then(|path| Icon::read(path));
+
+            Some(Feat {
+                label,
+                name: TlkStringRef::from_id(tlk, name_ref).ok()?,
+                desc: desc_ref.and_then(|r| TlkStringRef::from_id(tlk, r).ok()),
+                icon,
+                prereqs: FeatPrereqs::from_row(&prereq_columns, row),
+            })
+        };
+
+        let feats = table
+            .data
+            .row_iter()
+            .enumerate()
+            .filter_map(|(i, x)| from_row(x).map(|x| (i, x)))
+            .collect();
+
+        Ok(Self { feats })
+    }
+
+    /// Name of `feat`, or its id if the table doesn't have it
+    pub fn name(&self, feat: FeatId) -> String {
+        self.feats
+            .get(&feat)
+            .map(|x| x.name.data.clone())
+            .unwrap_or_else(|| format!("Feat {feat}"))
+    }
+}
@@ -0,0 +1,33 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+/// Decoded image, `pixels` holds 4 RGBA bytes per pixel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Icon {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+impl Icon {
+    pub fn from_dds(dds: dds::Dds) -> Self {
+        let (width, height) = (dds.header.width, dds.header.height);
+        let pixels = dds
+            .into_pixels()
+            .into_iter()
+            .flat_map(|dds::Rgba { r, g, b, a }| [r, g, b, a])
+            .collect();
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Reads a DDS icon, `None` if the file is missing or can't be decoded
+    pub fn read(path: &Path) -> Option<Self> {
+        let f = File::open(path).ok()?;
+        let dds = dds::Dds::read(BufReader::new(f)).ok()?;
+
+        Some(Self::from_dds(dds))
+    }
+}
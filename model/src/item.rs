@@ -0,0 +1,98 @@
+use crate::{
+    Tlk,
+    error::Error,
+    icon::Icon,
+    resources::{IconName, IconPath},
+    tlk_string_ref::TlkStringRef,
+    two_d_array::FileReader2DA,
+};
+use std::collections::HashMap;
+
+/// A row of `baseitems.2da`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BaseItem {
+    pub label: String,
+    /// `Name` from the tlk, the label if it has none
+    pub name: String,
+    /// `DefaultIcon`
+    pub icon: Option<Icon>,
+    /// Largest stack, from `Stacking`
+    pub max_stack: u16,
+    /// Charges a new item of the type has, from `ChargesStarting`
+    pub starting_charges: u8,
+    /// Whether items of the type hold other items, from `Container`
+    pub is_container: bool,
+}
+
+pub type BaseItemId = i32;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BaseItemRecord {
+    pub items: HashMap<BaseItemId, BaseItem>,
+}
+impl BaseItemRecord {
+    pub fn new(
+        tlk: &Tlk,
+        reader: &mut FileReader2DA,
+        icon_paths: &HashMap<IconName, IconPath>,
+    ) -> Result<Self, Error> {
+        let file_name = "baseitems.2da";
+        let table = reader.read(file_name)?;
+
+        let [label_idx, name_idx] = table.find_column_indices(["label", "Name"]).map_err(|e| {
+            Error::MissingTableColumn {
+                file: file_name,
+                column: e,
+            }
+        })?;
+        let icon_idx = table.find_column_index("DefaultIcon");
+        let stacking_idx = table.find_column_index("Stacking");
+        let charges_idx = table.find_column_index("ChargesStarting");
+        let container_idx = table.find_column_index("Container");
+
+        let items = table
+            .data
+            .row_iter()
+            .enumerate()
+            .filter_map(|(i, row)| {
+                let get = |idx: usize| row.get(idx).and_then(|x| x.as_deref());
+                let number =
+                    |idx: Option<usize>| idx.and_then(get).and_then(|x| x.parse::<u16>().ok());
+
+                let label = get(label_idx)?.to_string();
+                let name = get(name_idx)
+                    .and_then(|x| x.parse().ok())
+                    .and_then(|x| TlkStringRef::from_id(tlk, x).ok())
+                    .map(|x| x.data)
+                    .filter(|x| !x.is_empty())
+                    .unwrap_or_else(|| label.clone());
+
+                let icon = icon_idx
+                    .and_then(get)
+                    .and_then(|name| icon_paths.get(name))
+                    .and_then(|path| Icon::read(path));
+
+                let item = BaseItem {
+                    label,
+                    name,
+                    icon,
+                    max_stack: number(stacking_idx).unwrap_or(1),
+                    starting_charges: number(charges_idx)
+                        .map_or(0, |x| x.min(u8::MAX.into()) as u8),
+                    is_container: number(container_idx).is_some_and(|x| x != 0),
+                };
+
+                Some((BaseItemId::try_from(i).ok()?, item))
+            })
+            .collect();
+
+        Ok(Self { items })
+    }
+
+    pub fn name(&self, base_item: BaseItemId) -> String {
+        self.items
+            .get(&base_item)
+            .map(|x| x.name.clone())
+            .unwrap_or_else(|| format!("Base item {base_item}"))
+    }
+}
@@ -0,0 +1,643 @@
+use crate::{Tlk, error::Error, tlk_string_ref::TlkStringRef, two_d_array::FileReader2DA};
+use std::collections::HashMap;
+
+/// `Param1` of properties without a parameter
+pub const NO_PARAM: u8 = u8::MAX;
+
+pub type PropertyId = u16;
+
+/// A row of a table a property value is chosen from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub value: u16,
+    pub name: String,
+}
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// The values of an entry of an item's `PropertiesList`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ItemProperty {
+    /// `PropertyName`, row in `itempropdef.2da`
+    pub property: PropertyId,
+    /// Row of the property's `SubTypeResRef` table
+    pub subtype: u16,
+    /// Row in `iprp_costtable.2da`
+    pub cost_table: u8,
+    /// Row of the cost table
+    pub cost_value: u16,
+    /// Row in `iprp_paramtable.2da`, [`NO_PARAM`] for none
+    pub param1: u8,
+    /// Row of the param table
+    pub param1_value: u8,
+}
+
+/// A row of `itempropdef.2da`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PropertyDef {
+    pub label: String,
+    /// `GameStrRef` or `Name` from the tlk, the label if it has neither
+    pub name: String,
+    /// Rows of `SubTypeResRef`, empty for properties without subtypes
+    pub subtypes: Vec<Choice>,
+    /// Param tables of subtypes with their own, from `Param1ResRef` of the subtype table
+    pub subtype_param_tables: HashMap<u16, u8>,
+    /// `CostTableResRef`, row in `iprp_costtable.2da`
+    pub cost_table: Option<u8>,
+    /// `Param1ResRef`, row in `iprp_paramtable.2da`
+    pub param_table: Option<u8>,
+}
+
+/// A row of `iprp_paramtable.2da`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParamTable {
+    pub name: String,
+    /// Rows of `TableResRef`
+    pub values: Vec<Choice>,
+}
+
+/// Name of a row from its `Name` str ref, the label if it has none
+fn row_name(tlk: &Tlk, name: Option<&str>, label: Option<&str>) -> Option<String> {
+    name.and_then(|x| x.parse().ok())
+        .and_then(|x| TlkStringRef::from_id(tlk, x).ok())
+        .map(|x| x.data)
+        .filter(|x| !x.is_empty())
+        .or_else(|| label.map(str::to_string))
+}
+
+/// Named rows of an `iprp_*` table, empty if the game doesn't ship it
+fn read_choices(tlk: &Tlk, reader: &mut FileReader2DA, table_name: &str) -> Vec<Choice> {
+    let Ok(table) = reader.read(&format!("{}.2da", table_name.to_ascii_lowercase())) else {
+        return vec![];
+    };
+    let Some(name_idx) = table.find_column_index("Name") else {
+        return vec![];
+    };
+    let label_idx = table
+        .find_column_index("Label")
+        .or_else(|| table.find_column_index("Lable"));
+
+    table
+        .data
+        .row_iter()
+        .enumerate()
+        .filter_map(|(i, row)| {
+            let get = |idx: usize| row.get(idx).and_then(|x| x.as_deref());
+            Some(Choice {
+                value: u16::try_from(i).ok()?,
+                name: row_name(tlk, get(name_idx), label_idx.and_then(get))?,
+            })
+        })
+        .collect()
+}
+
+fn find_name(choices: &[Choice], value: u16) -> Option<String> {
+    choices
+        .iter()
+        .find(|x| x.value == value)
+        .map(|x| x.name.clone())
+}
+
+/// Table names of `iprp_costtable.2da` or `iprp_paramtable.2da` by row
+fn read_table_names(
+    reader: &mut FileReader2DA,
+    file_name: &'static str,
+    column: &'static str,
+) -> Result<Vec<(u8, String)>, Error> {
+    let table = reader.read(file_name)?;
+    let table_idx = table
+        .find_column_index(column)
+        .ok_or(Error::MissingTableColumn {
+            file: file_name,
+            column,
+        })?;
+
+    Ok(table
+        .get_column_data(table_idx)
+        .enumerate()
+        .filter_map(|(i, x)| Some((u8::try_from(i).ok()?, x?.to_string())))
+        .collect())
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ItemPropertyRecord {
+    pub properties: HashMap<PropertyId, PropertyDef>,
+    /// Rows of the tables `iprp_costtable.2da` lists, by its rows
+    pub cost_tables: HashMap<u8, Vec<Choice>>,
+    pub param_tables: HashMap<u8, ParamTable>,
+}
+impl ItemPropertyRecord {
+    pub fn new(tlk: &Tlk, reader: &mut FileReader2DA) -> Result<Self, Error> {
+        let cost_tables = read_table_names(reader, "iprp_costtable.2da", "Name")?
+            .into_iter()
+            .map(|(i, table)| (i, read_choices(tlk, reader, &table)))
+            .collect();
+
+        let param_file = "iprp_paramtable.2da";
+        // The game's table spells the label column `Lable`
+        let param_names = read_choices(tlk, reader, "iprp_paramtable");
+        let param_tables = read_table_names(reader, param_file, "TableResRef")?
+            .into_iter()
+            .map(|(i, table)| {
+                let param_table = ParamTable {
+                    name: find_name(&param_names, i.into()).unwrap_or_else(|| table.clone()),
+                    values: read_choices(tlk, reader, &table),
+                };
+                (i, param_table)
+            })
+            .collect();
+
+        let properties = Self::read_properties(tlk, reader)?;
+
+        Ok(Self {
+            properties,
+            cost_tables,
+            param_tables,
+        })
+    }
+
+    fn read_properties(
+        tlk: &Tlk,
+        reader: &mut FileReader2DA,
+    ) -> Result<HashMap<PropertyId, PropertyDef>, Error> {
+        let file_name = "itempropdef.2da";
+        let table = reader.read(file_name)?;
+
+        let [label_idx, name_idx] = table.find_column_indices(["Label", "Name"]).map_err(|e| {
+            Error::MissingTableColumn {
+                file: file_name,
+                column: e,
+            }
+        })?;
+        let game_name_idx = table.find_column_index("GameStrRef");
+        let subtype_idx = table.find_column_index("SubTypeResRef");
+        let cost_idx = table.find_column_index("CostTableResRef");
+        let param_idx = table.find_column_index("Param1ResRef");
+
+        // Read up front, the subtype tables need the reader
+        let rows = table
+            .data
+            .row_iter()
+            .enumerate()
+            .filter_map(|(i, row)| {
+                let get = |idx: Option<usize>| {
+                    idx.and_then(|idx| row.get(idx)?.as_deref().map(str::to_string))
+                };
+                let label = get(Some(label_idx))?;
+                let name = row_name(tlk, get(game_name_idx).as_deref(), None)
+                    .or_else(|| row_name(tlk, get(Some(name_idx)).as_deref(), Some(&label)))?;
+
+                let def = PropertyDef {
+                    label,
+                    name,
+                    cost_table: get(cost_idx).and_then(|x| x.parse().ok()),
+                    param_table: get(param_idx).and_then(|x| x.parse().ok()),
+                    ..Default::default()
+                };
+                Some((PropertyId::try_from(i).ok()?, def, get(subtype_idx)))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(rows
+            .into_iter()
+            .map(|(i, mut def, subtype_table)| {
+                if let Some(subtype_table) = subtype_table {
+                    def.subtypes = read_choices(tlk, reader, &subtype_table);
+                    def.subtype_param_tables = Self::read_subtype_params(reader, &subtype_table);
+                }
+                (i, def)
+            })
+            .collect())
+    }
+
+    /// `Param1ResRef` of the rows of a subtype table, like the effects of On Hit
+    fn read_subtype_params(reader: &mut FileReader2DA, table_name: &str) -> HashMap<u16, u8> {
+        let Ok(table) = reader.read(&format!("{}.2da", table_name.to_ascii_lowercase())) else {
+            return HashMap::new();
+        };
+        let Some(param_idx) = table.find_column_index("Param1ResRef") else {
+            return HashMap::new();
+        };
+
+        table
+            .get_column_data(param_idx)
+            .enumerate()
+            .filter_map(|(i, x)| Some((u16::try_from(i).ok()?, x?.parse().ok()?)))
+            .collect()
+    }
+
+    /// Param table of `subtype` of `property`, the subtype's own before the property's
+    pub fn param_table(&self, property: PropertyId, subtype: u16) -> Option<u8> {
+        let def = self.properties.get(&property)?;
+        def.subtype_param_tables
+            .get(&subtype)
+            .copied()
+            .or(def.param_table)
+    }
+
+    /// Values the cost of `property` can take
+    pub fn costs(&self, property: PropertyId) -> &[Choice] {
+        self.properties
+            .get(&property)
+            .and_then(|x| x.cost_table)
+            .and_then(|x| self.cost_tables.get(&x))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Values `Param1Value` of `subtype` of `property` can take
+    pub fn params(&self, property: PropertyId, subtype: u16) -> &[Choice] {
+        self.param_table(property, subtype)
+            .and_then(|x| self.param_tables.get(&x))
+            .map(|x| x.values.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// `property` with the first of each value it can take
+    pub fn default_property(&self, property: PropertyId) -> Result<ItemProperty, Error> {
+        let def = self.properties.get(&property).ok_or_else(|| {
+            Error::RuleViolation(format!("Property {property} isn't in itempropdef.2da"))
+        })?;
+
+        let mut value = ItemProperty {
+            property,
+            subtype: def.subtypes.first().map_or(0, |x| x.value),
+            cost_table: def.cost_table.unwrap_or(0),
+            cost_value: self.costs(property).first().map_or(0, |x| x.value),
+            ..Default::default()
+        };
+        let subtype = value.subtype;
+        self.set_subtype(&mut value, subtype);
+        Ok(value)
+    }
+
+    /// Sets the subtype and the param table that goes with it
+    pub fn set_subtype(&self, property: &mut ItemProperty, subtype: u16) {
+        property.subtype = subtype;
+        let param = self.param_table(property.property, subtype);
+        property.param1 = param.unwrap_or(NO_PARAM);
+        property.param1_value = self
+            .params(property.property, subtype)
+            .first()
+            .map_or(0, |x| x.value as u8);
+    }
+
+    /// Checks the values are rows of the property's tables
+    pub fn check(&self, property: &ItemProperty) -> Result<(), Error> {
+        let id = property.property;
+        let def = self.properties.get(&id).ok_or_else(|| {
+            Error::RuleViolation(format!("Property {id} isn't in itempropdef.2da"))
+        })?;
+
+        let invalid = |what: &str, value: u16| {
+            Err(Error::RuleViolation(format!(
+                "{value} isn't a valid {what} of {}",
+                def.name
+            )))
+        };
+        let has = |choices: &[Choice], value: u16| choices.iter().any(|x| x.value == value);
+
+        if !def.subtypes.is_empty() && !has(&def.subtypes, property.subtype) {
+            return invalid("subtype", property.subtype);
+        }
+        if def.cost_table.is_some() {
+            if Some(property.cost_table) != def.cost_table {
+                return invalid("cost table", property.cost_table.into());
+            }
+            if !has(self.costs(id), property.cost_value) {
+                return invalid("cost", property.cost_value);
+            }
+        }
+        if let Some(param) = self.param_table(id, property.subtype) {
+            if property.param1 != param {
+                return invalid("param table", property.param1.into());
+            }
+            if !has(
+                self.params(id, property.subtype),
+                property.param1_value.into(),
+            ) {
+                return invalid("parameter", property.param1_value.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Readable text of the property, like "Damage Bonus: Fire 1d6"
+    pub fn describe(&self, property: &ItemProperty) -> String {
+        let Some(def) = self.properties.get(&property.property) else {
+            return format!("Property {}", property.property);
+        };
+        let mut text = def.name.clone();
+        if !def.subtypes.is_empty() {
+            let subtype = find_name(&def.subtypes, property.subtype)
+                .unwrap_or_else(|| property.subtype.to_string());
+            text = format!("{text}: {subtype}");
+        }
+        if def.cost_table.is_some()
+            && let Some(cost) = self
+                .cost_tables
+                .get(&property.cost_table)
+                .and_then(|x| find_name(x, property.cost_value))
+        {
+            text = format!("{text} {cost}");
+        }
+        if property.param1 != NO_PARAM
+            && let Some(param) = self
+                .param_tables
+                .get(&property.param1)
+                .and_then(|x| find_name(&x.values, property.param1_value.into()))
+        {
+            text = format!("{text} {param}");
+        }
+        text
+    }
+
+    /// Properties sorted by name
+    pub fn choices(&self) -> Vec<Choice> {
+        let mut choices = self
+            .properties
+            .iter()
+            .map(|(id, x)| Choice {
+                value: *id,
+                name: x.name.clone(),
+            })
+            .collect::<Vec<_>>();
+        choices.sort_by(|a, b| a.name.cmp(&b.name));
+        choices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        player::inventory::ItemSlot,
+        tests::{GameData, TestGame, fixture, two_da, write_and_read},
+    };
+    use nwn_lib::files::gff::{field::Field, r#struct::Struct};
+
+    const ENHANCEMENT: PropertyId = 1;
+    const DAMAGE_BONUS: PropertyId = 16;
+    const ON_HIT: PropertyId = 48;
+    const SKILL_BONUS: PropertyId = 52;
+    const MIGHTY: PropertyId = 55;
+    const USE_LIMITATION: PropertyId = 63;
+
+    /// `itempropdef.2da` with Enhancement, Damage Bonus and On Hit named by `GameStrRef`,
+    /// Mighty only by `Name` and use limitations only by label, and the tables they use.
+    /// Only the Daze subtype of On Hit has a duration parameter.
+    fn item_property_game() -> (TestGame, ItemPropertyRecord) {
+        let mut data = GameData::new();
+        let mut names = |names: &[(usize, &str)]| {
+            names
+                .iter()
+                .map(|&(i, name)| (i, format!("{}\tRow{i}", data.string(name))))
+                .collect::<Vec<_>>()
+        };
+        let bonuses = names(&[(1, "+1"), (2, "+2"), (3, "+3")]);
+        let damage_costs = names(&[(1, "1d6"), (2, "1d8")]);
+        let on_hit_costs = names(&[(1, "DC 14"), (2, "DC 16")]);
+        let damage_types = names(&[(0, "Acid"), (1, "Fire")]);
+        let durations = names(&[(0, "1 round"), (1, "2 rounds")]);
+        let properties = names(&[
+            (ENHANCEMENT as usize, "Enhancement Bonus"),
+            (DAMAGE_BONUS as usize, "Damage Bonus"),
+            (ON_HIT as usize, "On Hit"),
+            (MIGHTY as usize, "Mighty"),
+        ]);
+        let daze = data.string("Daze");
+        let vorpal = data.string("Vorpal");
+
+        let str_ref = |id: PropertyId| {
+            let (_, row) = properties.iter().find(|(i, _)| *i == id as usize).unwrap();
+            row.split('\t').next().unwrap().to_string()
+        };
+        let property_defs = [
+            (ENHANCEMENT, "Enhancement\t****\t2\t****", true),
+            (DAMAGE_BONUS, "DamageBonus\tIPRP_DAMAGETYPE\t4\t****", true),
+            (ON_HIT, "OnHit\tIPRP_ONHIT\t24\t****", true),
+            (MIGHTY, "Mighty\t****\t2\t****", false),
+            (
+                USE_LIMITATION,
+                "UseLimitationClass\t****\t****\t****",
+                false,
+            ),
+        ]
+        .map(|(id, row, game_name)| {
+            let name = match id {
+                MIGHTY => str_ref(id),
+                _ => "****".into(),
+            };
+            let game_name = match game_name {
+                true => str_ref(id),
+                false => "****".into(),
+            };
+            (id as usize, format!("{name}\t{row}\t{game_name}"))
+        });
+
+        let game = data
+            .table(
+                "itempropdef.2da",
+                two_da(
+                    "Name\tLabel\tSubTypeResRef\tCostTableResRef\tParam1ResRef\tGameStrRef",
+                    &property_defs,
+                ),
+            )
+            .table(
+                "iprp_costtable.2da",
+                two_da(
+                    "Name\tLabel\tClientLoad",
+                    &[
+                        (2, "IPRP_BONUSCOST\t****\t0"),
+                        (4, "IPRP_DAMAGECOST\t****\t0"),
+                        (24, "IPRP_ONHITCOST\t****\t0"),
+                    ],
+                ),
+            )
+            .table("iprp_bonuscost.2da", two_da("Name\tLabel", &bonuses))
+            .table("iprp_damagecost.2da", two_da("Name\tLabel", &damage_costs))
+            .table("iprp_onhitcost.2da", two_da("Name\tLabel", &on_hit_costs))
+            .table("iprp_damagetype.2da", two_da("Name\tLabel", &damage_types))
+            .table(
+                "iprp_onhit.2da",
+                two_da(
+                    "Name\tLabel\tParam1ResRef",
+                    &[
+                        (0, format!("{daze}\tDaze\t0")),
+                        (1, format!("{vorpal}\tVorpal\t****")),
+                    ],
+                ),
+            )
+            .table(
+                "iprp_paramtable.2da",
+                two_da(
+                    "Name\tLable\tTableResRef",
+                    &[(0, "****\tDuration\tIPRP_ONHITDUR")],
+                ),
+            )
+            .table("iprp_onhitdur.2da", two_da("Name\tLabel", &durations));
+
+        let mut game = game.build();
+        let record = ItemPropertyRecord::new(&game.tlk, &mut game.reader).unwrap();
+        (game, record)
+    }
+
+    fn values(choices: &[Choice]) -> Vec<u16> {
+        choices.iter().map(|x| x.value).collect()
+    }
+
+    #[test]
+    fn item_properties() {
+        let (_, record) = item_property_game();
+        let mut ids = record.properties.keys().copied().collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(
+            ids,
+            [ENHANCEMENT, DAMAGE_BONUS, ON_HIT, MIGHTY, USE_LIMITATION]
+        );
+        assert_eq!(record.properties[&MIGHTY].name, "Mighty");
+        assert_eq!(
+            record.properties[&USE_LIMITATION].name,
+            record.properties[&USE_LIMITATION].label
+        );
+        assert_eq!(
+            values(&record.choices()),
+            [DAMAGE_BONUS, ENHANCEMENT, MIGHTY, ON_HIT, USE_LIMITATION]
+        );
+
+        let damage = &record.properties[&DAMAGE_BONUS];
+        assert_eq!(values(&damage.subtypes), [0, 1]);
+        assert_eq!((damage.cost_table, damage.param_table), (Some(4), None));
+        assert_eq!(values(record.costs(ENHANCEMENT)), [1, 2, 3]);
+        assert!(record.costs(USE_LIMITATION).is_empty());
+
+        let on_hit = &record.properties[&ON_HIT];
+        assert_eq!(on_hit.subtype_param_tables, HashMap::from([(0, 0)]));
+        assert_eq!(record.param_tables[&0].name, "Duration");
+        assert_eq!(values(record.params(ON_HIT, 0)), [0, 1]);
+        assert!(record.params(ON_HIT, 1).is_empty());
+
+        let enhancement = record.default_property(ENHANCEMENT).unwrap();
+        assert_eq!(
+            enhancement,
+            ItemProperty {
+                property: ENHANCEMENT,
+                subtype: 0,
+                cost_table: 2,
+                cost_value: 1,
+                param1: NO_PARAM,
+                param1_value: 0,
+            }
+        );
+        record.check(&enhancement).unwrap();
+
+        // Values outside the property's tables
+        for invalid in [
+            ItemProperty {
+                cost_value: 9,
+                ..enhancement
+            },
+            ItemProperty {
+                cost_table: 4,
+                ..enhancement
+            },
+            ItemProperty::default(),
+        ] {
+            assert!(
+                matches!(record.check(&invalid), Err(Error::RuleViolation(_))),
+                "{invalid:?}"
+            );
+        }
+        assert!(record.default_property(99).is_err());
+
+        let mut on_hit = record.default_property(ON_HIT).unwrap();
+        assert_eq!((on_hit.param1, on_hit.param1_value), (0, 0));
+        assert_eq!(record.describe(&on_hit), "On Hit: Daze DC 14 1 round");
+        record.set_subtype(&mut on_hit, 1);
+        assert_eq!(on_hit.param1, NO_PARAM);
+        assert_eq!(record.describe(&on_hit), "On Hit: Vorpal DC 14");
+        record.set_subtype(&mut on_hit, 0);
+        on_hit.param1_value = 5;
+        assert!(record.check(&on_hit).is_err());
+    }
+
+    #[test]
+    fn property_list() {
+        let bic = fixture!("player.bic");
+        let (mut game, record) = item_property_game();
+        let mut player = game.read_player(&bic);
+
+        let skill_bonus = player
+            .inventory
+            .equipped
+            .items
+            .iter()
+            .position(|x| {
+                x.properties
+                    .as_ref()
+                    .is_some_and(|x| x.properties.iter().any(|x| x.property == SKILL_BONUS))
+            })
+            .unwrap();
+        let slot = ItemSlot::Equipped(skill_bonus);
+        let list = player
+            .inventory
+            .get_mut(slot)
+            .unwrap()
+            .properties_mut()
+            .unwrap();
+        let count = list.properties.len();
+        assert_eq!(list.properties[0].property, SKILL_BONUS);
+
+        let enhancement = record.default_property(ENHANCEMENT).unwrap();
+        let index = list.add(enhancement, &record).unwrap();
+        assert_eq!(index, count);
+        assert!(matches!(
+            list.set(
+                index,
+                ItemProperty {
+                    cost_value: 9,
+                    ..enhancement
+                },
+                &record
+            ),
+            Err(Error::RuleViolation(_))
+        ));
+        assert!(list.add(ItemProperty::default(), &record).is_err());
+
+        let mut on_hit = record.default_property(ON_HIT).unwrap();
+        on_hit.param1_value = 1;
+        list.add(on_hit, &record).unwrap();
+
+        let damage = ItemProperty {
+            property: DAMAGE_BONUS,
+            subtype: 0,
+            cost_table: 4,
+            cost_value: 2,
+            param1: NO_PARAM,
+            param1_value: 0,
+        };
+        list.set(index, damage, &record).unwrap();
+        assert_eq!(list.remove(0).unwrap().property, SKILL_BONUS);
+        assert!(list.remove(10).is_err());
+
+        let player = game.read_player(&write_and_read(&bic));
+        let item = player.inventory.get(slot).unwrap();
+        let list = item.properties.as_ref().unwrap();
+        assert_eq!(list.properties[count - 1..], [damage, on_hit]);
+
+        // Edits keep the fields the editor doesn't change
+        let lock = list.list_ref.read().unwrap();
+        let structs = lock.field.expect_list().unwrap();
+        let uses_per_day = |s: &Struct| {
+            s.find_direct("UsesPerDay")
+                .unwrap()
+                .read_field(|x| x.clone())
+        };
+        assert_eq!(uses_per_day(&structs[count - 1]), Field::Byte(255));
+        assert_eq!(uses_per_day(&structs[count]), Field::Byte(255));
+        assert_eq!(structs[count].fields.len(), 9);
+    }
+}
@@ -0,0 +1,26 @@
+//! Character model shared by the editor front ends, reads and edits a save's
+//! players without depending on a UI toolkit
+
+#[cfg(test)]
+mod tests;
+
+pub mod class;
+pub mod domain;
+pub mod error;
+pub mod feat;
+pub mod field_ref;
+pub mod icon;
+pub mod ids;
+pub mod item;
+pub mod item_property;
+pub mod player;
+pub mod race;
+pub mod resources;
+pub mod roster;
+pub mod school;
+pub mod skill;
+pub mod spell;
+pub mod tlk_string_ref;
+pub mod two_d_array;
+
+pub use resources::Tlk;
@@ -0,0 +1,409 @@
+use crate::{
+    domain::DomainRecord,
+    error::Error,
+    field_ref::FieldRef,
+    ids::class::Class,
+    player::{Player, player_class::SpellKnownList},
+    race::RaceRecord,
+    school::SchoolRecord,
+    spell::SpellRecord,
+};
+use nwn_lib::files::gff::field::Field;
+
+/// Grade of the invocations a warlock can learn, a new grade every five levels.
+/// The game stores no field for it, it follows from the warlock's level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InvocationGrade {
+    Least,
+    Lesser,
+    Greater,
+    Dark,
+}
+impl InvocationGrade {
+    pub fn at_level(level: u16) -> Option<Self> {
+        match level {
+            0 => None,
+            1..=5 => Some(Self::Least),
+            6..=10 => Some(Self::Lesser),
+            11..=15 => Some(Self::Greater),
+            _ => Some(Self::Dark),
+        }
+    }
+}
+impl std::fmt::Display for InvocationGrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl Player {
+    /// Swaps `old` for `new` in the creature's feats and the level it was taken at
+    fn replace_feat(&mut self, old: u16, new: u16) {
+        for feats in self
+            .history
+            .levels
+            .iter_mut()
+            .filter_map(|x| x.feats.as_mut())
+        {
+            if let Some(i) = feats.list_ref.get().iter().position(|x| *x.get() == old) {
+                feats.swap_feat(i, new);
+            }
+        }
+
+        let feats = self.feats.list_ref.get();
+        let old_index = feats.iter().position(|x| *x.get() == old);
+        let has_new = feats.iter().any(|x| *x.get() == new);
+        match old_index {
+            Some(i) if has_new => self.feats.remove_feat(i),
+            Some(i) => self.feats.swap_feat(i, new),
+            None if !has_new => self.feats.add_feat(new),
+            None => {}
+        }
+    }
+
+    /// Removes `feat` from the creature's feats and every level it was taken at
+    fn remove_feat_everywhere(&mut self, feat: u16) {
+        for feats in self
+            .history
+            .levels
+            .iter_mut()
+            .filter_map(|x| x.feats.as_mut())
+        {
+            if let Some(i) = feats.list_ref.get().iter().position(|x| *x.get() == feat) {
+                feats.remove_feat(i);
+            }
+        }
+        if let Some(i) = self
+            .feats
+            .list_ref
+            .get()
+            .iter()
+            .position(|x| *x.get() == feat)
+        {
+            self.feats.remove_feat(i);
+        }
+    }
+
+    /// Sets `Domain1` or `Domain2` of the class at `class_index`, swapping the old domain's
+    /// granted feat and domain spells for the new one's. Spells that are also on the
+    /// class's own spell list at that level, or granted by the other domain, are kept.
+    pub fn set_domain(
+        &mut self,
+        class_index: usize,
+        slot: usize,
+        domain: u8,
+        domains: &DomainRecord,
+        spells: &SpellRecord,
+    ) -> Result<(), Error> {
+        let new = domains
+            .domains
+            .get(&domain)
+            .ok_or_else(|| Error::MissingField(format!("Domain {domain} in domains.2da")))?;
+        let class = self
+            .classes
+            .get(class_index)
+            .ok_or_else(|| Error::MissingField(format!("Class {class_index}")))?;
+        if slot > 1 {
+            return Err(Error::ParseError(format!("Invalid domain slot {slot}")));
+        }
+
+        let current = |slot: usize| class.domains[slot].as_ref().map(|x| *x.get());
+        let old = current(slot);
+        let other = current(1 - slot);
+        if old == Some(domain) {
+            return Ok(());
+        }
+        if other == Some(domain) {
+            return Err(Error::RuleViolation(format!(
+                "{} is already the class's other domain",
+                new.name
+            )));
+        }
+
+        let class_id = *class.class.get();
+        let old = old.and_then(|x| domains.domains.get(&x));
+        let other = other.and_then(|x| domains.domains.get(&x));
+
+        // Lists and fields that can fail to be added, before anything is changed
+        for spell_level in 1..10 {
+            let new_spell = new.spell_at(spell_level);
+            if new_spell.is_some() && new_spell != old.and_then(|x| x.spell_at(spell_level)) {
+                self.known_list_mut(class_index, spell_level)?;
+            }
+        }
+
+        // Domain field
+        match &mut self.classes[class_index].domains[slot] {
+            Some(field) => field.set(domain, |x| Field::Byte(*x)),
+            None => {
+                let label = format!("Domain{}", slot + 1);
+                let field = self.add_class_field(class_index, &label, Field::Byte(domain))?;
+                self.classes[class_index].domains[slot] =
+                    Some(FieldRef::new(field, Field::expect_byte)?);
+            }
+        }
+
+        // Domain spells
+        let on_class_list = |spell: crate::ids::spell::Spell, spell_level: usize| {
+            spells
+                .spells
+                .get(&(spell.0 as usize))
+                .and_then(|x| x.spell_levels.for_class(class_id))
+                .is_some_and(|x| usize::from(x) == spell_level)
+        };
+        for spell_level in 1..10 {
+            let old_spell = old.and_then(|x| x.spell_at(spell_level));
+            let new_spell = new.spell_at(spell_level);
+            if old_spell == new_spell {
+                continue;
+            }
+
+            if let Some(spell) = old_spell
+                && other.and_then(|x| x.spell_at(spell_level)) != Some(spell)
+                && !on_class_list(spell, spell_level)
+                && let Some(known) = &mut self.classes[class_index].spell_known_list[spell_level]
+                && let Some(i) = known.spells.iter().position(|x| *x == spell)
+            {
+                known.remove_spell(i)?;
+            }
+
+            if let Some(spell) = new_spell
+                && let Some(known) = &mut self.classes[class_index].spell_known_list[spell_level]
+                && !known.spells.contains(&spell)
+            {
+                known.add_spell(spell)?;
+            }
+        }
+
+        // Granted feat
+        let old_feat = old.and_then(|x| x.granted_feat);
+        let other_feat = other.and_then(|x| x.granted_feat);
+        match (
+            old_feat.filter(|x| Some(*x) != other_feat),
+            new.granted_feat,
+        ) {
+            (Some(old), Some(new)) => self.replace_feat(old, new),
+            (Some(old), None) => self.remove_feat_everywhere(old),
+            (None, Some(new)) => {
+                if !self.has_feat(new) {
+                    self.feats.add_feat(new);
+                }
+            }
+            (None, None) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Known spell list of `spell_level` of the class at `class_index`, adding it if needed
+    fn known_list_mut(
+        &mut self,
+        class_index: usize,
+        spell_level: usize,
+    ) -> Result<&mut SpellKnownList, Error> {
+        if self.classes[class_index].spell_known_list[spell_level].is_none() {
+            let field = self.add_class_field(
+                class_index,
+                &format!("KnownList{spell_level}"),
+                Field::List(vec![]),
+            )?;
+
+            let class = &mut self.classes[class_index];
+            let mut list = SpellKnownList::new(field)?;
+            list.template = class.known_template();
+            class.spell_known_list[spell_level] = Some(list);
+            class.is_caster = true;
+        }
+
+        Ok(self.classes[class_index].spell_known_list[spell_level]
+            .as_mut()
+            .expect("Known list was just added"))
+    }
+
+    /// Sets the `School` of the class at `class_index`
+    pub fn set_school(
+        &mut self,
+        class_index: usize,
+        school: u8,
+        schools: &SchoolRecord,
+    ) -> Result<(), Error> {
+        if !schools.schools.contains_key(&school) {
+            return Err(Error::MissingField(format!(
+                "School {school} in spellschools.2da"
+            )));
+        }
+        let class = self
+            .classes
+            .get_mut(class_index)
+            .ok_or_else(|| Error::MissingField(format!("Class {class_index}")))?;
+
+        match &mut class.school {
+            Some(field) => field.set(school, |x| Field::Byte(*x)),
+            None => {
+                let field = self.add_class_field(class_index, "School", Field::Byte(school))?;
+                self.classes[class_index].school = Some(FieldRef::new(field, Field::expect_byte)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Races the character has the favored enemy feat of
+    pub fn favored_enemies(&self, races: &RaceRecord) -> Vec<u8> {
+        let mut enemies = self
+            .feats
+            .list_ref
+            .get()
+            .iter()
+            .filter_map(|x| races.favored_enemy(*x.get()))
+            .collect::<Vec<_>>();
+        enemies.sort();
+        enemies
+    }
+
+    /// Swaps favored enemy `old` for `new`, replacing the feat where it was taken
+    pub fn swap_favored_enemy(
+        &mut self,
+        old: u8,
+        new: u8,
+        races: &RaceRecord,
+    ) -> Result<(), Error> {
+        let feat = |race: u8| {
+            races
+                .races
+                .get(&race)
+                .and_then(|x| x.favored_enemy_feat)
+                .ok_or_else(|| Error::MissingField(format!("FavoredEnemyFeat of race {race}")))
+        };
+        let (old_feat, new_feat) = (feat(old)?, feat(new)?);
+
+        if !self.has_feat(old_feat) {
+            return Err(Error::MissingField(format!(
+                "Favored enemy {}",
+                races.name(old)
+            )));
+        }
+        if self.has_feat(new_feat) {
+            return Err(Error::RuleViolation(format!(
+                "{} is already a favored enemy",
+                races.name(new)
+            )));
+        }
+
+        self.replace_feat(old_feat, new_feat);
+        Ok(())
+    }
+
+    /// Invocation grade of the character's warlock levels
+    pub fn invocation_grade(&self) -> Option<InvocationGrade> {
+        InvocationGrade::at_level(self.class_level(Class::Warlock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{GameData, fixture, two_da, write_and_read};
+
+    #[test]
+    fn class_options() {
+        // Zhjaeve has the first two domains' feats and Air's spell 449, Death replaces
+        // them with feat 900 and spells 500 and 501
+        let mut game = GameData::new()
+            .races(&[
+                (3, &[("FavoredEnemyFeat", "1003")]),
+                (5, &[("FavoredEnemyFeat", "1005")]),
+                (6, &[("FavoredEnemyFeat", "1006")]),
+            ])
+            .table(
+                "domains.2da",
+                two_da(
+                    "Label\tName\tGrantedFeat\tLevel_1\tLevel_2\tLevel_3",
+                    &[
+                        (0, "AIR\t****\t318\t****\t449\t****"),
+                        (1, "ANIMAL\t****\t322\t****\t6\t****"),
+                        (2, "DEATH\t****\t900\t****\t500\t501"),
+                    ],
+                ),
+            )
+            .table(
+                "spellschools.2da",
+                two_da(
+                    "Label\tStringRef\tOpposition",
+                    &[
+                        (0, "General\t****\t****"),
+                        (1, "Abjuration\t****\t5"),
+                        (5, "Illusion\t****\t1"),
+                    ],
+                ),
+            )
+            .build();
+        let domains = DomainRecord::new(&game.tlk, &mut game.reader).unwrap();
+        let schools = SchoolRecord::new(&game.tlk, &mut game.reader).unwrap();
+        let races = RaceRecord::new(&game.tlk, &mut game.reader).unwrap();
+        let spells = SpellRecord::default();
+
+        let ros = fixture!("zhjaeve.ros");
+        let mut player = game.read_player(&ros);
+
+        let domain = |player: &Player, slot: usize| {
+            player.classes[0].domains[slot].as_ref().map(|x| *x.get())
+        };
+        let known = |player: &Player, spell_level: usize| {
+            player.classes[0].spell_known_list[spell_level]
+                .as_ref()
+                .map(|x| x.spells.iter().map(|x| x.0).collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+        assert_eq!((domain(&player, 0), domain(&player, 1)), (Some(0), Some(1)));
+        assert_eq!(domains.name(2), "DEATH");
+        assert!(known(&player, 2).contains(&449));
+
+        // Nothing changes when Death's level 3 list can't be added
+        let mut broken = game.read_player(&ros);
+        broken.class_list = None;
+        assert!(broken.set_domain(0, 0, 2, &domains, &spells).is_err());
+        assert_eq!(domain(&broken, 0), Some(0));
+        assert!(broken.has_feat(318) && !broken.has_feat(900));
+        assert!(known(&broken, 2).contains(&449));
+
+        // Air's feat and spell make way for Death's
+        player.set_domain(0, 0, 2, &domains, &spells).unwrap();
+        assert!(!player.has_feat(318) && player.has_feat(900) && player.has_feat(322));
+        assert!(!known(&player, 2).contains(&449));
+        assert!(known(&player, 2).contains(&500) && known(&player, 2).contains(&6));
+        assert_eq!(known(&player, 3), [501]);
+
+        let err = player.set_domain(0, 1, 2, &domains, &spells);
+        assert!(matches!(err, Err(Error::RuleViolation(_))));
+        assert!(player.set_domain(0, 0, 7, &domains, &spells).is_err());
+
+        // Favored enemies are the `FavoredEnemyFeat`s of `racialtypes.2da`
+        player.feats.add_feat(1003);
+        assert_eq!(player.favored_enemies(&races), [3]);
+        player.swap_favored_enemy(3, 5, &races).unwrap();
+        assert_eq!(player.favored_enemies(&races), [5]);
+        assert!(player.swap_favored_enemy(5, 9, &races).is_err());
+        assert!(player.swap_favored_enemy(3, 6, &races).is_err());
+
+        let player = game.read_player(&write_and_read(&ros));
+        assert_eq!((domain(&player, 0), domain(&player, 1)), (Some(2), Some(1)));
+        assert_eq!(known(&player, 3), [501]);
+        assert!(player.has_feat(900) && player.has_feat(1005) && !player.has_feat(1003));
+
+        let ros = fixture!("sand.ros");
+        let mut player = game.read_player(&ros);
+        assert_eq!(player.classes[0].school.as_ref().map(|x| *x.get()), Some(0));
+        assert!(player.set_school(0, 3, &schools).is_err());
+        player.set_school(0, 1, &schools).unwrap();
+        assert_eq!(schools.schools[&1].opposition, Some(5));
+
+        let player = game.read_player(&write_and_read(&ros));
+        assert_eq!(player.classes[0].school.as_ref().map(|x| *x.get()), Some(1));
+
+        let player = game.read_player(&fixture!("ammon_jerro.ros"));
+        assert_eq!(player.invocation_grade(), Some(InvocationGrade::Greater));
+        assert_eq!(InvocationGrade::at_level(16), Some(InvocationGrade::Dark));
+        assert_eq!(InvocationGrade::at_level(0), None);
+    }
+}
@@ -25,7 +25,7 @@ impl FeatList {
         .map(|x| Self { list_ref: x })
     }
 
-    fn create_feat_struct(feat: FeatId) -> Struct {
+    pub(crate) fn create_feat_struct(feat: FeatId) -> Struct {
         let label = Label::from_string("Feat");
         let field = StructField::new(LabeledField {
             label,
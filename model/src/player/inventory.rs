@@ -0,0 +1,931 @@
+use crate::{
+    Tlk,
+    error::Error,
+    field_ref::FieldRef,
+    item::{BaseItemId, BaseItemRecord},
+    item_property::{ItemProperty, ItemPropertyRecord, NO_PARAM},
+    player::{
+        Player, read_loc_string,
+        spell_entry::{as_number, with_number},
+    },
+};
+use nwn_lib::files::gff::{
+    field::{Field, LabeledField},
+    label::Label,
+    r#struct::{Struct, StructField},
+};
+
+/// Slot bits of the struct ids of `Equip_ItemList` entries
+pub const EQUIP_SLOT_NAMES: [(u32, &str); 18] = [
+    (0x1, "Head"),
+    (0x2, "Chest"),
+    (0x4, "Boots"),
+    (0x8, "Arms"),
+    (0x10, "Right hand"),
+    (0x20, "Left hand"),
+    (0x40, "Cloak"),
+    (0x80, "Left ring"),
+    (0x100, "Right ring"),
+    (0x200, "Neck"),
+    (0x400, "Belt"),
+    (0x800, "Arrows"),
+    (0x1000, "Bullets"),
+    (0x2000, "Bolts"),
+    (0x4000, "Creature weapon left"),
+    (0x8000, "Creature weapon right"),
+    (0x10000, "Creature weapon bite"),
+    (0x20000, "Creature armor"),
+];
+
+/// Name of the equipment slot of an `Equip_ItemList` struct id
+pub fn equip_slot_name(struct_id: u32) -> String {
+    EQUIP_SLOT_NAMES
+        .iter()
+        .find(|(slot, _)| *slot == struct_id)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("Slot {struct_id:#x}"))
+}
+
+fn bool_field(field: &Field) -> Result<bool, nwn_lib::error::Error> {
+    field.expect_byte().map(|x| x != 0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemFlag {
+    Identified,
+    Plot,
+    Cursed,
+}
+
+/// An item struct of an `ItemList` or `Equip_ItemList`
+#[derive(Debug, Clone)]
+pub struct Item {
+    /// Struct id, the slot bit for equipped items
+    pub struct_id: u32,
+    /// `BaseItem`, row in `baseitems.2da`
+    pub base_item: BaseItemId,
+    /// `LocalizedName`, from the tlk when the item only stores a str_ref
+    pub name: String,
+    pub tag: String,
+    pub object_id: Option<FieldRef<u32>>,
+    pub stack_size: Option<FieldRef<u16>>,
+    pub charges: Option<FieldRef<u8>>,
+    pub identified: Option<FieldRef<bool>>,
+    pub plot: Option<FieldRef<bool>>,
+    pub cursed: Option<FieldRef<bool>>,
+    /// Position in the inventory or container
+    pub repos_index: Option<FieldRef<u16>>,
+    /// `ItemList` of containers
+    pub contents: Option<ItemList>,
+    pub properties: Option<PropertyList>,
+}
+impl Item {
+    fn read(
+        s: &Struct,
+        read_name: &dyn Fn(&Field) -> Result<String, Error>,
+    ) -> Result<Self, Error> {
+        let mut item = Self {
+            struct_id: s.id,
+            base_item: 0,
+            name: String::new(),
+            tag: String::new(),
+            object_id: None,
+            stack_size: None,
+            charges: None,
+            identified: None,
+            plot: None,
+            cursed: None,
+            repos_index: None,
+            contents: None,
+            properties: None,
+        };
+
+        for field in &s.fields {
+            let lock = field.read()?;
+            let field_ref = || field.clone();
+
+            match lock.label.as_str() {
+                "BaseItem" => item.base_item = lock.field.expect_int()?,
+                "LocalizedName" => item.name = read_name(&lock.field)?,
+                "Tag" => item.tag = lock.field.expect_exostring()?.0.clone(),
+                "ObjectId" => {
+                    item.object_id = Some(FieldRef::new(field_ref(), Field::expect_dword)?)
+                }
+                "StackSize" => {
+                    item.stack_size = Some(FieldRef::new(field_ref(), Field::expect_word)?)
+                }
+                "Charges" => item.charges = Some(FieldRef::new(field_ref(), Field::expect_byte)?),
+                "Identified" => item.identified = Some(FieldRef::new(field_ref(), bool_field)?),
+                "Plot" => item.plot = Some(FieldRef::new(field_ref(), bool_field)?),
+                "Cursed" => item.cursed = Some(FieldRef::new(field_ref(), bool_field)?),
+                "Repos_Index" => {
+                    item.repos_index = Some(FieldRef::new(field_ref(), Field::expect_word)?)
+                }
+                "ItemList" => {
+                    drop(lock);
+                    item.contents = Some(ItemList::read(field_ref(), read_name)?);
+                }
+                "PropertiesList" => {
+                    drop(lock);
+                    item.properties = Some(PropertyList::read(field_ref())?);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(item)
+    }
+
+    /// Takes the names of `item`, which this item is a copy of read without a tlk
+    fn copy_names(&mut self, item: &Item) {
+        self.name = item.name.clone();
+        if let (Some(copy), Some(contents)) = (&mut self.contents, &item.contents) {
+            for (copy, item) in copy.items.iter_mut().zip(&contents.items) {
+                copy.copy_names(item);
+            }
+        }
+    }
+
+    /// The item's name, the base item's if it has none
+    pub fn display_name(&self, base_items: &BaseItemRecord) -> String {
+        match self.name.trim() {
+            "" if !self.tag.is_empty() => {
+                format!("{} ({})", base_items.name(self.base_item), self.tag)
+            }
+            "" => base_items.name(self.base_item),
+            name => name.to_string(),
+        }
+    }
+
+    pub fn flag(&self, flag: ItemFlag) -> Option<bool> {
+        let field_ref = match flag {
+            ItemFlag::Identified => &self.identified,
+            ItemFlag::Plot => &self.plot,
+            ItemFlag::Cursed => &self.cursed,
+        };
+        field_ref.as_ref().map(|x| *x.get())
+    }
+
+    pub fn set_flag(&mut self, flag: ItemFlag, value: bool) -> Result<(), Error> {
+        let field_ref = match flag {
+            ItemFlag::Identified => &mut self.identified,
+            ItemFlag::Plot => &mut self.plot,
+            ItemFlag::Cursed => &mut self.cursed,
+        };
+        let field_ref = field_ref
+            .as_mut()
+            .ok_or_else(|| Error::MissingField(format!("{flag:?} in item {}", self.tag)))?;
+
+        field_ref.set(value, |x| Field::Byte((*x).into()));
+        Ok(())
+    }
+
+    pub fn set_stack_size(&mut self, stack_size: u16) -> Result<(), Error> {
+        let field_ref = self
+            .stack_size
+            .as_mut()
+            .ok_or_else(|| Error::MissingField(format!("StackSize in item {}", self.tag)))?;
+        if stack_size == 0 {
+            return Err(Error::RuleViolation("Stacks hold at least one item".into()));
+        }
+
+        field_ref.set(stack_size, |x| Field::Word(*x));
+        Ok(())
+    }
+
+    pub fn set_charges(&mut self, charges: u8) -> Result<(), Error> {
+        let field_ref = self
+            .charges
+            .as_mut()
+            .ok_or_else(|| Error::MissingField(format!("Charges in item {}", self.tag)))?;
+
+        field_ref.set(charges, |x| Field::Byte(*x));
+        Ok(())
+    }
+
+    pub fn properties_mut(&mut self) -> Result<&mut PropertyList, Error> {
+        self.properties
+            .as_mut()
+            .ok_or_else(|| Error::MissingField(format!("PropertiesList in item {}", self.tag)))
+    }
+
+    /// Gives the item and the items it holds new object ids starting at `next_id`
+    fn renumber(&mut self, next_id: &mut u32) -> Result<(), Error> {
+        if let Some(object_id) = &mut self.object_id {
+            object_id.set(*next_id, |x| Field::DWord(*x));
+            *next_id = next_id.checked_add(1).ok_or_else(out_of_object_ids)?;
+        }
+        for item in self.contents.iter_mut().flat_map(|x| &mut x.items) {
+            item.renumber(next_id)?;
+        }
+        Ok(())
+    }
+}
+
+fn out_of_object_ids() -> Error {
+    Error::RuleViolation("The save has no object ids left for new items".into())
+}
+
+/// Object id after the highest one of the items of `players`, the characters of a save
+pub fn next_object_id<'a>(players: impl IntoIterator<Item = &'a Player>) -> Result<u32, Error> {
+    let max = players
+        .into_iter()
+        .flat_map(|x| x.inventory.iter())
+        .filter_map(|x| x.object_id.as_ref().map(|x| *x.get()))
+        .max();
+
+    match max {
+        Some(max) => max.checked_add(1).ok_or_else(out_of_object_ids),
+        None => Ok(0),
+    }
+}
+
+/// Items of an `ItemList` or `Equip_ItemList`
+#[derive(Debug, Default, Clone)]
+pub struct ItemList {
+    pub list_ref: Option<StructField>,
+    pub items: Vec<Item>,
+}
+impl ItemList {
+    pub fn new(tlk: &Tlk, list_field: StructField) -> Result<Self, Error> {
+        Self::read(list_field, &|f| read_loc_string(tlk, f))
+    }
+
+    fn read(
+        list_field: StructField,
+        read_name: &dyn Fn(&Field) -> Result<String, Error>,
+    ) -> Result<Self, Error> {
+        let lock = list_field.read()?;
+        let items = lock
+            .field
+            .expect_list()?
+            .iter()
+            .map(|s| Item::read(s, read_name))
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(lock);
+
+        Ok(Self {
+            list_ref: Some(list_field),
+            items,
+        })
+    }
+
+    fn list_ref(&self) -> Result<&StructField, Error> {
+        self.list_ref
+            .as_ref()
+            .ok_or_else(|| Error::MissingField("ItemList".into()))
+    }
+
+    /// Deep clone of the struct of the item at `index`
+    fn clone_struct(&self, index: usize) -> Result<Struct, Error> {
+        let lock = self.list_ref()?.read()?;
+        lock.field
+            .expect_list()?
+            .get(index)
+            .map(Struct::deep_clone)
+            .ok_or_else(|| Error::MissingField(format!("Item {index}")))
+    }
+
+    fn remove(&mut self, index: usize) -> Result<Item, Error> {
+        if index >= self.items.len() {
+            return Err(Error::MissingField(format!("Item {index}")));
+        }
+
+        let mut lock = self.list_ref()?.write()?;
+        let Field::List(lst) = &mut lock.field else {
+            return Err(Error::ParseError("ItemList isn't a list".into()));
+        };
+        lst.remove(index);
+        drop(lock);
+
+        Ok(self.items.remove(index))
+    }
+
+    fn insert(&mut self, index: usize, s: Struct, item: Item) -> Result<(), Error> {
+        let mut lock = self.list_ref()?.write()?;
+        let Field::List(lst) = &mut lock.field else {
+            return Err(Error::ParseError("ItemList isn't a list".into()));
+        };
+        lst.insert(index, s);
+        drop(lock);
+
+        self.items.insert(index, item);
+        Ok(())
+    }
+
+    /// `Repos_Index` after the last item
+    fn next_repos_index(&self) -> u16 {
+        self.items
+            .iter()
+            .filter_map(|x| x.repos_index.as_ref().map(|x| *x.get() + 1))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Fields of a `PropertiesList` entry holding `property`, with the type they're added as
+fn property_fields(property: &ItemProperty) -> [(&'static str, u32, Field); 6] {
+    [
+        ("PropertyName", property.property.into(), Field::Word(0)),
+        ("Subtype", property.subtype.into(), Field::Word(0)),
+        ("CostTable", property.cost_table.into(), Field::Byte(0)),
+        ("CostValue", property.cost_value.into(), Field::Word(0)),
+        ("Param1", property.param1.into(), Field::Byte(0)),
+        ("Param1Value", property.param1_value.into(), Field::Byte(0)),
+    ]
+}
+
+/// Properties of an item's `PropertiesList`
+#[derive(Debug, Clone)]
+pub struct PropertyList {
+    pub list_ref: StructField,
+    pub properties: Vec<ItemProperty>,
+}
+impl PropertyList {
+    fn read(list_field: StructField) -> Result<Self, Error> {
+        let lock = list_field.read()?;
+        let properties = lock
+            .field
+            .expect_list()?
+            .iter()
+            .map(Self::read_property)
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(lock);
+
+        Ok(Self {
+            list_ref: list_field,
+            properties,
+        })
+    }
+
+    fn read_property(s: &Struct) -> Result<ItemProperty, Error> {
+        let number = |label: &str| s.find_direct(label).and_then(|x| x.read_field(as_number));
+
+        let property = number("PropertyName")
+            .ok_or_else(|| Error::MissingField("PropertyName in item property".into()))?;
+        Ok(ItemProperty {
+            property: property as u16,
+            subtype: number("Subtype").unwrap_or(0) as u16,
+            cost_table: number("CostTable").unwrap_or(0) as u8,
+            cost_value: number("CostValue").unwrap_or(0) as u16,
+            param1: number("Param1").map_or(NO_PARAM, |x| x as u8),
+            param1_value: number("Param1Value").unwrap_or(0) as u8,
+        })
+    }
+
+    /// Writes `property` keeping the types of the fields `s` has
+    fn write_property(s: &mut Struct, property: &ItemProperty) -> Result<(), Error> {
+        for (label, value, default) in property_fields(property) {
+            match s.find_direct(label) {
+                Some(field) => {
+                    let mut lock = field.write()?;
+                    let new = with_number(&lock.field, value)
+                        .or_else(|| with_number(&default, value))
+                        .unwrap_or(default);
+                    lock.field = new;
+                }
+                None => s.fields.push(StructField::new(LabeledField::new(
+                    Label::from_string(label),
+                    with_number(&default, value).unwrap_or(default),
+                ))),
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds `property` after the others, usable and always present like the toolset's
+    pub fn add(
+        &mut self,
+        property: ItemProperty,
+        record: &ItemPropertyRecord,
+    ) -> Result<usize, Error> {
+        record.check(&property)?;
+
+        let mut lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut lock.field else {
+            return Err(Error::ParseError("PropertiesList isn't a list".into()));
+        };
+        let mut s = Struct {
+            id: lst.first().map_or(0, |x| x.id),
+            original_data_or_data_offset: u32::MAX,
+            fields: vec![],
+        };
+        Self::write_property(&mut s, &property)?;
+        s.fields.extend(
+            [
+                ("ChanceAppear", 100),
+                ("UsesPerDay", u8::MAX),
+                ("Useable", 1),
+            ]
+            .map(|(label, value)| {
+                StructField::new(LabeledField::new(
+                    Label::from_string(label),
+                    Field::Byte(value),
+                ))
+            }),
+        );
+        lst.push(s);
+        drop(lock);
+
+        self.properties.push(property);
+        Ok(self.properties.len() - 1)
+    }
+
+    /// Replaces the property at `index`, keeping fields like `UsesPerDay`
+    pub fn set(
+        &mut self,
+        index: usize,
+        property: ItemProperty,
+        record: &ItemPropertyRecord,
+    ) -> Result<(), Error> {
+        record.check(&property)?;
+
+        let mut lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut lock.field else {
+            return Err(Error::ParseError("PropertiesList isn't a list".into()));
+        };
+        let s = lst
+            .get_mut(index)
+            .ok_or_else(|| Error::MissingField(format!("Item property {index}")))?;
+        Self::write_property(s, &property)?;
+        drop(lock);
+
+        self.properties[index] = property;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<ItemProperty, Error> {
+        if index >= self.properties.len() {
+            return Err(Error::MissingField(format!("Item property {index}")));
+        }
+
+        let mut lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut lock.field else {
+            return Err(Error::ParseError("PropertiesList isn't a list".into()));
+        };
+        lst.remove(index);
+        drop(lock);
+
+        Ok(self.properties.remove(index))
+    }
+}
+
+/// Where an item is in the inventory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemSlot {
+    /// Entry of `Equip_ItemList`
+    Equipped(usize),
+    /// Entry of `ItemList`
+    Carried(usize),
+    /// Entry of the `ItemList` of the container at `ItemList` index `container`
+    InContainer { container: usize, index: usize },
+}
+
+/// Equipped and carried items, `Equip_ItemList` and `ItemList`
+#[derive(Debug, Default, Clone)]
+pub struct Inventory {
+    pub equipped: ItemList,
+    pub carried: ItemList,
+}
+impl Inventory {
+    pub fn get(&self, slot: ItemSlot) -> Option<&Item> {
+        let (list, index) = match slot {
+            ItemSlot::Equipped(i) => (&self.equipped, i),
+            ItemSlot::Carried(i) => (&self.carried, i),
+            ItemSlot::InContainer { container, index } => {
+                (self.carried.items.get(container)?.contents.as_ref()?, index)
+            }
+        };
+        list.items.get(index)
+    }
+
+    pub fn get_mut(&mut self, slot: ItemSlot) -> Option<&mut Item> {
+        let (list, index) = self.list_mut(slot)?;
+        list.items.get_mut(index)
+    }
+
+    fn list_mut(&mut self, slot: ItemSlot) -> Option<(&mut ItemList, usize)> {
+        match slot {
+            ItemSlot::Equipped(i) => Some((&mut self.equipped, i)),
+            ItemSlot::Carried(i) => Some((&mut self.carried, i)),
+            ItemSlot::InContainer { container, index } => Some((
+                self.carried.items.get_mut(container)?.contents.as_mut()?,
+                index,
+            )),
+        }
+    }
+
+    /// Every item including the contents of containers
+    pub fn iter(&self) -> impl Iterator<Item = &Item> {
+        let contents = self
+            .carried
+            .items
+            .iter()
+            .flat_map(|x| x.contents.iter().flat_map(|x| &x.items));
+        self.equipped
+            .items
+            .iter()
+            .chain(&self.carried.items)
+            .chain(contents)
+    }
+
+    pub fn remove(&mut self, slot: ItemSlot) -> Result<Item, Error> {
+        let (list, index) = self
+            .list_mut(slot)
+            .ok_or_else(|| Error::MissingField(format!("Container of {slot:?}")))?;
+        list.remove(index)
+    }
+
+    /// Copies the item at `slot` with new object ids counting up from `next_id`, which has
+    /// to be past the ids of every character of the save, see [`next_object_id`]. Copies
+    /// of equipped items go to the end of `ItemList` since a slot holds one item, other
+    /// copies follow the original.
+    pub fn duplicate(&mut self, slot: ItemSlot, mut next_id: u32) -> Result<ItemSlot, Error> {
+        let (list, index) = self
+            .list_mut(slot)
+            .ok_or_else(|| Error::MissingField(format!("Container of {slot:?}")))?;
+        let original = list
+            .items
+            .get(index)
+            .cloned()
+            .ok_or_else(|| Error::MissingField(format!("Item {slot:?}")))?;
+        let mut s = list.clone_struct(index)?;
+
+        let (list, new_slot) = match slot {
+            ItemSlot::Equipped(_) => {
+                // Carried items share one struct id
+                s.id = self.carried.items.first().map_or(0, |x| x.struct_id);
+                let index = self.carried.items.len();
+                (&mut self.carried, ItemSlot::Carried(index))
+            }
+            ItemSlot::Carried(i) => (&mut self.carried, ItemSlot::Carried(i + 1)),
+            ItemSlot::InContainer { container, index } => (
+                self.carried.items[container]
+                    .contents
+                    .as_mut()
+                    .expect("Container was found above"),
+                ItemSlot::InContainer {
+                    container,
+                    index: index + 1,
+                },
+            ),
+        };
+
+        // Equipped items have no inventory position
+        if s.find_direct("Repos_Index").is_none()
+            && list.items.iter().any(|x| x.repos_index.is_some())
+        {
+            s.fields.push(StructField::new(LabeledField::new(
+                Label::from_string("Repos_Index"),
+                Field::Word(0),
+            )));
+        }
+
+        let mut copy = Item::read(&s, &|_| Ok(String::new()))?;
+        copy.copy_names(&original);
+        copy.renumber(&mut next_id)?;
+
+        let repos_index = list.next_repos_index();
+        if let Some(x) = &mut copy.repos_index {
+            x.set(repos_index, |x| Field::Word(*x));
+        }
+
+        let index = match new_slot {
+            ItemSlot::Equipped(i) | ItemSlot::Carried(i) => i,
+            ItemSlot::InContainer { index, .. } => index,
+        };
+        list.insert(index, s, copy)?;
+        Ok(new_slot)
+    }
+}
+
+impl Player {
+    /// Changes the stack size of the item at `slot`, limited by `Stacking` of its base item
+    pub fn set_stack_size(
+        &mut self,
+        slot: ItemSlot,
+        stack_size: u16,
+        base_items: &BaseItemRecord,
+    ) -> Result<(), Error> {
+        let item = self
+            .inventory
+            .get_mut(slot)
+            .ok_or_else(|| Error::MissingField(format!("Item {slot:?}")))?;
+
+        if let Some(base) = base_items.items.get(&item.base_item)
+            && stack_size > base.max_stack
+        {
+            return Err(Error::RuleViolation(format!(
+                "{} stack up to {}",
+                base.name, base.max_stack
+            )));
+        }
+        item.set_stack_size(stack_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        class::ClassRecord,
+        domain::DomainRecord,
+        feat::FeatRecord,
+        item_property::ItemPropertyRecord,
+        player::legality::{Area, FindingKind, Rules, Severity},
+        race::RaceRecord,
+        school::SchoolRecord,
+        skill::SkillRecord,
+        spell::SpellRecord,
+        tests::{GameData, TestGame, fixture, two_da, write_and_read},
+    };
+    use nwn_lib::files::gff::Gff;
+    use std::collections::{BTreeSet, HashMap};
+
+    const CLOTHING: usize = 16;
+    const BAG: usize = 66;
+
+    /// Game with a `baseitems.2da` row for each base item of the character in `gff` and
+    /// bags, labelled `Row<row>`. Clothing doesn't stack, the others stack up to 50.
+    fn read_base_items(gff: &Gff) -> (TestGame, BaseItemRecord) {
+        let player = GameData::new().build().read_player(gff);
+        let rows = player
+            .inventory
+            .iter()
+            .map(|x| x.base_item as usize)
+            .chain([BAG])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|i| {
+                let stacking = if i == CLOTHING { 1 } else { 50 };
+                let container = u8::from(i == BAG);
+                (i, format!("****\tRow{i}\t****\t{stacking}\t0\t{container}"))
+            })
+            .collect::<Vec<_>>();
+        let table = two_da(
+            "Name\tlabel\tDefaultIcon\tStacking\tChargesStarting\tContainer",
+            &rows,
+        );
+
+        let mut game = GameData::new().table("baseitems.2da", table).build();
+        let base_items = BaseItemRecord::new(&game.tlk, &mut game.reader, &HashMap::new()).unwrap();
+        (game, base_items)
+    }
+
+    #[test]
+    fn inventory() {
+        let bic = fixture!("player.bic");
+        let (mut game, base_items) = read_base_items(&bic);
+        let mut player = game.read_player(&bic);
+        let inventory = &player.inventory;
+        assert_eq!(inventory.equipped.items.len(), 4);
+        assert_eq!(inventory.carried.items.len(), 22);
+        assert_eq!(
+            equip_slot_name(inventory.equipped.items[0].struct_id),
+            "Chest"
+        );
+        assert!(base_items.items[&66].is_container);
+
+        // The test tlk has no item names, they fall back to the base item
+        let moss = &inventory.carried.items[0];
+        assert_eq!(moss.display_name(&base_items), "Row49 (11_swamp_moss)");
+        assert_eq!(moss.flag(ItemFlag::Identified), Some(true));
+        assert_eq!(moss.flag(ItemFlag::Cursed), Some(false));
+        let last_id = inventory
+            .iter()
+            .filter_map(|x| x.object_id.as_ref().map(|x| *x.get()))
+            .max()
+            .unwrap();
+
+        // Clothing doesn't stack
+        let err = player.set_stack_size(ItemSlot::Equipped(0), 2, &base_items);
+        assert!(matches!(err, Err(Error::RuleViolation(_))));
+        player
+            .set_stack_size(ItemSlot::Carried(1), 9, &base_items)
+            .unwrap();
+
+        let potion = player.inventory.get_mut(ItemSlot::Carried(1)).unwrap();
+        potion.set_charges(3).unwrap();
+        potion.set_flag(ItemFlag::Plot, true).unwrap();
+        potion.set_flag(ItemFlag::Identified, false).unwrap();
+
+        let next_id = next_object_id([&player]).unwrap();
+        assert_eq!(next_id, last_id + 1);
+        let copy = player
+            .inventory
+            .duplicate(ItemSlot::Carried(1), next_id)
+            .unwrap();
+        assert_eq!(copy, ItemSlot::Carried(2));
+        let equipped_copy = player
+            .inventory
+            .duplicate(ItemSlot::Equipped(0), next_object_id([&player]).unwrap())
+            .unwrap();
+        assert_eq!(equipped_copy, ItemSlot::Carried(23));
+        player.inventory.remove(ItemSlot::Carried(0)).unwrap();
+        assert!(player.inventory.remove(ItemSlot::Carried(40)).is_err());
+
+        let player = game.read_player(&write_and_read(&bic));
+        let inventory = &player.inventory;
+        assert_eq!(inventory.carried.items.len(), 23);
+        let object_id = |slot| {
+            let item = inventory.get(slot).unwrap();
+            *item.object_id.as_ref().unwrap().get()
+        };
+        let repos_index = |slot| {
+            let item = inventory.get(slot).unwrap();
+            item.repos_index.as_ref().map(|x| *x.get())
+        };
+
+        let (potion, copy) = (
+            inventory.get(ItemSlot::Carried(0)).unwrap(),
+            inventory.get(ItemSlot::Carried(1)).unwrap(),
+        );
+        assert_eq!(copy.tag, potion.tag);
+        assert_eq!(*copy.stack_size.as_ref().unwrap().get(), 9);
+        assert_eq!(*copy.charges.as_ref().unwrap().get(), 3);
+        assert_eq!(
+            (copy.flag(ItemFlag::Plot), copy.flag(ItemFlag::Identified)),
+            (Some(true), Some(false))
+        );
+        assert_eq!(object_id(ItemSlot::Carried(1)), last_id + 1);
+        assert_eq!(repos_index(ItemSlot::Carried(1)), Some(22));
+
+        let cloth = inventory.get(ItemSlot::Carried(22)).unwrap();
+        assert_eq!(cloth.tag, "NW_CLOTH008");
+        assert_eq!(cloth.struct_id, inventory.carried.items[0].struct_id);
+        assert_eq!(object_id(ItemSlot::Carried(22)), last_id + 2);
+        assert_eq!(repos_index(ItemSlot::Carried(22)), Some(23));
+        assert_eq!(inventory.equipped.items.len(), 4);
+
+        // Items over the stack size of their base item
+        let mut player = player;
+        let arrows = player
+            .inventory
+            .carried
+            .items
+            .iter()
+            .position(|x| x.base_item == 25 && *x.stack_size.as_ref().unwrap().get() == 20)
+            .unwrap();
+        let item = player.inventory.get_mut(ItemSlot::Carried(arrows)).unwrap();
+        item.set_stack_size(60).unwrap();
+        let rules = Rules {
+            classes: &ClassRecord::default(),
+            skills: &SkillRecord::default(),
+            feats: &FeatRecord::default(),
+            races: &RaceRecord::default(),
+            domains: &DomainRecord::default(),
+            schools: &SchoolRecord::default(),
+            spells: &SpellRecord::default(),
+            base_items: &base_items,
+            item_properties: &ItemPropertyRecord::default(),
+        };
+        // Properties are left to the item property tests
+        let findings = player
+            .validate(rules)
+            .into_iter()
+            .filter(|x| x.area() == Area::Items)
+            .filter(|x| !matches!(x.kind, FindingKind::InvalidItemProperty { .. }))
+            .map(|x| (x.severity, x.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            findings,
+            [(
+                Severity::Warning,
+                FindingKind::StackSize {
+                    tag: "NW_WAMMBO008".into(),
+                    size: 60,
+                    max: 50
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn container_items() {
+        let bic = fixture!("player.bic");
+        let (mut game, base_items) = read_base_items(&bic);
+
+        // Turns the first item into a bag holding a copy of the second
+        let item_list = bic.root.find_direct("ItemList").unwrap();
+        let mut lock = item_list.write().unwrap();
+        let Field::List(items) = &mut lock.field else {
+            panic!("ItemList isn't a list");
+        };
+        let potion = items[1].deep_clone();
+        let bag = &mut items[0];
+        for f in &bag.fields {
+            let mut f = f.write().unwrap();
+            if f.label.as_str() == "BaseItem" {
+                f.field = Field::Int(66);
+            }
+        }
+        bag.fields.push(StructField::new(LabeledField::new(
+            Label::from_string("ItemList"),
+            Field::List(vec![potion]),
+        )));
+        drop(lock);
+
+        let mut player = game.read_player(&bic);
+        let bag = &player.inventory.carried.items[0];
+        assert!(base_items.items[&bag.base_item].is_container);
+        assert_eq!(bag.contents.as_ref().unwrap().items.len(), 1);
+
+        let inside = ItemSlot::InContainer {
+            container: 0,
+            index: 0,
+        };
+        let copy = player
+            .inventory
+            .duplicate(inside, next_object_id([&player]).unwrap())
+            .unwrap();
+        assert_eq!(
+            copy,
+            ItemSlot::InContainer {
+                container: 0,
+                index: 1
+            }
+        );
+        // Copying the bag copies what it holds
+        assert_eq!(
+            player
+                .inventory
+                .duplicate(ItemSlot::Carried(0), next_object_id([&player]).unwrap())
+                .unwrap(),
+            ItemSlot::Carried(1)
+        );
+        assert_eq!(player.inventory.iter().count(), 4 + 23 + 4);
+        player.inventory.remove(inside).unwrap();
+
+        let player = game.read_player(&write_and_read(&bic));
+        let contents = |i: usize| {
+            player.inventory.carried.items[i]
+                .contents
+                .as_ref()
+                .unwrap()
+                .items
+                .iter()
+                .map(|x| *x.object_id.as_ref().unwrap().get())
+                .collect::<Vec<_>>()
+        };
+        let ids = player
+            .inventory
+            .iter()
+            .filter_map(|x| x.object_id.as_ref().map(|x| *x.get()))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(ids.len(), player.inventory.iter().count());
+        assert_eq!(contents(0).len(), 1);
+        assert_eq!(contents(1).len(), 2);
+        assert_eq!(
+            player.inventory.carried.items[1].tag,
+            player.inventory.carried.items[0].tag
+        );
+    }
+
+    #[test]
+    fn object_ids_across_characters() {
+        let mut game = GameData::new().build();
+        let player = game.read_player(&fixture!("player.bic"));
+        let mut companion = game.read_player(&fixture!("zhjaeve.ros"));
+        let ids = |x: &Player| {
+            x.inventory
+                .iter()
+                .filter_map(|x| x.object_id.as_ref().map(|x| *x.get()))
+                .collect::<Vec<_>>()
+        };
+
+        // The player's items have ids past the companion's
+        let next_id = next_object_id([&player, &companion]).unwrap();
+        assert!(next_id > next_object_id([&companion]).unwrap());
+        assert!(
+            ids(&player)
+                .iter()
+                .chain(&ids(&companion))
+                .all(|x| *x < next_id)
+        );
+
+        // Zhjaeve's only item is equipped, the copy goes to her inventory
+        let copy = companion
+            .inventory
+            .duplicate(ItemSlot::Equipped(0), next_id)
+            .unwrap();
+        let item = companion.inventory.get(copy).unwrap();
+        assert_eq!(*item.object_id.as_ref().unwrap().get(), next_id);
+        assert!(!ids(&player).contains(&next_id));
+
+        // No ids are left after `u32::MAX`
+        let count = companion.inventory.iter().count();
+        let item = companion.inventory.get_mut(copy).unwrap();
+        item.object_id
+            .as_mut()
+            .unwrap()
+            .set(u32::MAX, |x| Field::DWord(*x));
+        assert!(next_object_id([&player, &companion]).is_err());
+        assert!(
+            companion
+                .inventory
+                .duplicate(ItemSlot::Equipped(0), u32::MAX)
+                .is_err()
+        );
+        assert_eq!(companion.inventory.iter().count(), count);
+    }
+}
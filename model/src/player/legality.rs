@@ -0,0 +1,851 @@
+use crate::{
+    class::{ClassInfo, ClassPrereq, ClassRecord},
+    domain::DomainRecord,
+    feat::{FeatId, FeatRecord},
+    ids::class::Class,
+    item::{BaseItemId, BaseItemRecord},
+    item_property::{ItemProperty, ItemPropertyRecord},
+    player::{ABILITY_NAMES, Player, level_up::feats_given, prereqs::Prereq, total_level},
+    race::RaceRecord,
+    school::SchoolRecord,
+    skill::{SkillId, SkillRecord},
+    spell::SpellRecord,
+};
+
+/// Points a new character spends on abilities
+const POINT_BUY: u16 = 32;
+/// Lowest and highest score point buy allows before racial adjustments
+const POINT_BUY_SCORES: std::ops::RangeInclusive<u8> = 8..=18;
+
+/// Point cost of a starting score of 8 to 18
+fn point_cost(score: u8) -> u16 {
+    match score {
+        ..=14 => u16::from(score.saturating_sub(8)),
+        15 => 8,
+        16 => 10,
+        17 => 13,
+        _ => 16,
+    }
+}
+
+/// The game tables the rules checks read
+#[derive(Debug, Clone, Copy)]
+pub struct Rules<'a> {
+    pub classes: &'a ClassRecord,
+    pub skills: &'a SkillRecord,
+    pub feats: &'a FeatRecord,
+    pub races: &'a RaceRecord,
+    pub domains: &'a DomainRecord,
+    pub schools: &'a SchoolRecord,
+    pub spells: &'a SpellRecord,
+    pub base_items: &'a BaseItemRecord,
+    pub item_properties: &'a ItemPropertyRecord,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Unusual but something the game allows, like unspent points
+    Warning,
+    /// Something the game's character creation and level up don't allow
+    Error,
+}
+
+/// Part of the character a finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Area {
+    Abilities,
+    Alignment,
+    Classes,
+    /// Domains, schools and favored enemies
+    ClassOptions,
+    Skills,
+    Feats,
+    Spells,
+    Items,
+}
+
+/// Feats a level of the history took against the ones it gives to choose
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatChoices {
+    pub taken: usize,
+    pub given: usize,
+}
+impl FeatChoices {
+    /// Feats the level still has to choose
+    pub fn pending(&self) -> usize {
+        self.given.saturating_sub(self.taken)
+    }
+}
+
+/// What a finding is about, with the values its message describes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingKind {
+    /// The level history doesn't cover every level, so starting abilities are unknown
+    IncompleteHistory,
+    /// The race or subrace isn't in the race tables
+    UnknownRace,
+    /// Starting score of ability `0..6` outside the point buy range
+    StartingScore {
+        ability: u8,
+        score: u8,
+    },
+    /// Starting abilities cost more or less than point buy gives
+    PointBuy {
+        spent: u16,
+    },
+    /// A class the character's alignment isn't open to
+    Alignment(Class),
+    SkillRanks {
+        skill: SkillId,
+        ranks: u8,
+        max: u8,
+    },
+    IllegalFeat {
+        feat: FeatId,
+        unmet: Vec<Prereq>,
+    },
+    /// Feats a level of the history took, counting levels from 1
+    LevelFeats {
+        level: usize,
+        choices: FeatChoices,
+    },
+    SpellsKnown {
+        class: Class,
+        spell_level: usize,
+        known: usize,
+        allowed: u8,
+    },
+    /// `cls_pres_*` rows the character doesn't meet, every row of an unmet group
+    ClassPrereqs {
+        class: Class,
+        unmet: Vec<ClassPrereq>,
+    },
+    SameDomains {
+        class: Class,
+        domain: u8,
+    },
+    MissingDomain(Class),
+    UnknownDomain {
+        class: Class,
+        domain: u8,
+    },
+    UnknownSchool {
+        class: Class,
+        school: u8,
+    },
+    /// Favored enemies against the ones the ranger levels give
+    FavoredEnemies {
+        count: usize,
+        given: usize,
+    },
+    InvalidItemProperty {
+        tag: String,
+        property: ItemProperty,
+    },
+    UnknownBaseItem {
+        tag: String,
+        base_item: BaseItemId,
+    },
+    StackSize {
+        tag: String,
+        size: u16,
+        max: u16,
+    },
+}
+impl FindingKind {
+    pub fn area(&self) -> Area {
+        match self {
+            Self::IncompleteHistory
+            | Self::UnknownRace
+            | Self::StartingScore { .. }
+            | Self::PointBuy { .. } => Area::Abilities,
+            Self::Alignment(_) => Area::Alignment,
+            Self::ClassPrereqs { .. } => Area::Classes,
+            Self::SameDomains { .. }
+            | Self::MissingDomain(_)
+            | Self::UnknownDomain { .. }
+            | Self::UnknownSchool { .. }
+            | Self::FavoredEnemies { .. } => Area::ClassOptions,
+            Self::SkillRanks { .. } => Area::Skills,
+            Self::IllegalFeat { .. } | Self::LevelFeats { .. } => Area::Feats,
+            Self::SpellsKnown { .. } => Area::Spells,
+            Self::InvalidItemProperty { .. }
+            | Self::UnknownBaseItem { .. }
+            | Self::StackSize { .. } => Area::Items,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub kind: FindingKind,
+    pub message: String,
+}
+impl Finding {
+    pub fn area(&self) -> Area {
+        self.kind.area()
+    }
+}
+
+/// Collects findings of one check
+struct Report(Vec<Finding>);
+impl Report {
+    fn error(&mut self, kind: FindingKind, message: String) {
+        self.0.push(Finding {
+            severity: Severity::Error,
+            kind,
+            message,
+        });
+    }
+
+    fn warning(&mut self, kind: FindingKind, message: String) {
+        self.0.push(Finding {
+            severity: Severity::Warning,
+            kind,
+            message,
+        });
+    }
+}
+
+impl Player {
+    /// Runs every rules check on the character, errors first.
+    ///
+    /// Prestige class requirements are checked against the character as it is
+    /// now rather than the level the class was taken at
+    pub fn validate(&self, rules: Rules<'_>) -> Vec<Finding> {
+        let mut report = Report(vec![]);
+
+        self.check_abilities(rules, &mut report);
+        self.check_alignment(rules, &mut report);
+        self.check_skills(rules, &mut report);
+        self.check_feats(rules, &mut report);
+        self.check_feats_per_level(rules, &mut report);
+        self.check_spells_known(rules, &mut report);
+        self.check_prestige_classes(rules, &mut report);
+        self.check_domains(rules, &mut report);
+        self.check_schools(rules, &mut report);
+        self.check_favored_enemies(rules, &mut report);
+        self.check_items(rules, &mut report);
+
+        let mut findings = report.0;
+        findings.sort_by_key(|x| std::cmp::Reverse(x.severity));
+        findings
+    }
+
+    /// Point buy and racial limits of the scores before level up increases
+    fn check_abilities(&self, rules: Rules<'_>, report: &mut Report) {
+        let level = total_level(&self.classes);
+        if self.history.levels.len() != usize::from(level) {
+            report.warning(
+                FindingKind::IncompleteHistory,
+                format!(
+                    "The level history covers {} of {level} levels, starting abilities can't be worked out",
+                    self.history.levels.len()
+                ),
+            );
+            return;
+        }
+
+        let Some(race) = rules.races.get(self.race.id, self.race.subrace_id) else {
+            report.warning(
+                FindingKind::UnknownRace,
+                format!("{} isn't in the race tables", self.race),
+            );
+            return;
+        };
+
+        let mut starting = [0u8; 6];
+        for (ability, score) in (0..).zip(&mut starting) {
+            let current = self.attributes.get(ability).map(|x| *x.get()).unwrap_or(0);
+            let increases = self
+                .history
+                .levels
+                .iter()
+                .filter(|x| x.ability_increase == Some(ability))
+                .count() as i16;
+            let adjustment = i16::from(race.ability_adjustments[ability as usize]);
+
+            *score = (i16::from(current) - increases - adjustment).clamp(0, u8::MAX.into()) as u8;
+        }
+
+        let mut in_range = true;
+        for ((ability, name), score) in (0..).zip(ABILITY_NAMES).zip(starting) {
+            if !POINT_BUY_SCORES.contains(&score) {
+                in_range = false;
+                report.error(
+                    FindingKind::StartingScore { ability, score },
+                    format!(
+                        "{name} starts at {score} before {} adjustments, point buy allows {} to {}",
+                        race.name,
+                        POINT_BUY_SCORES.start(),
+                        POINT_BUY_SCORES.end()
+                    ),
+                );
+            }
+        }
+
+        if !in_range {
+            return;
+        }
+
+        let spent = starting.iter().map(|x| point_cost(*x)).sum::<u16>();
+        match spent.cmp(&POINT_BUY) {
+            std::cmp::Ordering::Greater => report.error(
+                FindingKind::PointBuy { spent },
+                format!("Starting abilities cost {spent} points, point buy gives {POINT_BUY}"),
+            ),
+            std::cmp::Ordering::Less => report.warning(
+                FindingKind::PointBuy { spent },
+                format!(
+                    "Starting abilities leave {} points unspent",
+                    POINT_BUY - spent
+                ),
+            ),
+            std::cmp::Ordering::Equal => {}
+        }
+    }
+
+    fn check_alignment(&self, rules: Rules<'_>, report: &mut Report) {
+        let good_evil = *self.alignment.good_evil.get();
+        let lawful_chaotic = *self.alignment.lawful_chaotic.get();
+
+        for class in &self.classes {
+            let class = *class.class.get();
+            if let Some(info) = rules.classes.classes.get(&class)
+                && !info.alignment.allows(good_evil, lawful_chaotic)
+            {
+                report.error(
+                    FindingKind::Alignment(class),
+                    format!("{class} isn't open to {} characters", self.alignment),
+                );
+            }
+        }
+    }
+
+    fn check_skills(&self, rules: Rules<'_>, report: &mut Report) {
+        let classes = self
+            .classes
+            .iter()
+            .map(|x| *x.class.get())
+            .collect::<Vec<_>>();
+        let level = total_level(&self.classes);
+
+        for (skill, rank) in self.skills.ranks.ranks.iter().enumerate() {
+            let rank = *rank.get();
+            let max = rules.skills.max_ranks(&classes, level, skill);
+            if rank > max {
+                report.error(
+                    FindingKind::SkillRanks {
+                        skill,
+                        ranks: rank,
+                        max,
+                    },
+                    format!(
+                        "{} has {rank} ranks, the most at level {level} is {max}",
+                        rules.skills.name(skill)
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_feats(&self, rules: Rules<'_>, report: &mut Report) {
+        for (feat, unmet) in self.illegal_feats(rules.feats, rules.classes) {
+            let names = unmet
+                .iter()
+                .map(|x| x.describe(rules.feats, rules.skills))
+                .collect::<Vec<_>>();
+            report.error(
+                FindingKind::IllegalFeat { feat, unmet },
+                format!(
+                    "{} is held without {}",
+                    rules.feats.name(feat),
+                    names.join(", ")
+                ),
+            );
+        }
+    }
+
+    /// Feats chosen at each level of the history against the general, class bonus
+    /// and racial feats the level gives, `None` for levels of unknown classes.
+    /// Granted feats and feats no class can choose, like racial and background
+    /// feats, aren't counted
+    pub fn feat_choices(&self, rules: Rules<'_>) -> Vec<Option<FeatChoices>> {
+        let race = rules.races.get(self.race.id, self.race.subrace_id);
+        let character_classes = self
+            .classes
+            .iter()
+            .filter_map(|x| rules.classes.classes.get(x.class.get()))
+            .collect::<Vec<_>>();
+
+        let chosen = |feat: u16| {
+            rules.feats.feats.get(&feat.into()).is_some_and(|x| {
+                x.prereqs.all_classes_can_use
+                    || character_classes
+                        .iter()
+                        .any(|info| info.class_feats.contains(&feat))
+            })
+        };
+
+        let mut class_levels = std::collections::HashMap::<Class, u16>::new();
+        self.history
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let level = i as u16 + 1;
+                let class_level = class_levels.entry(entry.class).or_default();
+                *class_level += 1;
+
+                let info = rules.classes.classes.get(&entry.class)?;
+                let taken = entry
+                    .feat_ids()
+                    .filter(|x| {
+                        !info
+                            .feats_granted_at(*class_level)
+                            .any(|granted| granted == *x)
+                    })
+                    .filter(|x| chosen(*x))
+                    .count();
+
+                Some(FeatChoices {
+                    taken,
+                    given: feats_given(level, *class_level, info, race).into(),
+                })
+            })
+            .collect()
+    }
+
+    fn check_feats_per_level(&self, rules: Rules<'_>, report: &mut Report) {
+        for (i, choices) in self.feat_choices(rules).into_iter().enumerate() {
+            let level = i + 1;
+            let Some(choices @ FeatChoices { taken, given }) = choices else {
+                continue;
+            };
+            let kind = FindingKind::LevelFeats { level, choices };
+
+            if taken > given {
+                report.error(
+                    kind,
+                    format!("Level {level} takes {taken} feats, it gives {given}"),
+                );
+            } else if taken < given {
+                report.warning(
+                    kind,
+                    format!("Level {level} takes {taken} of the {given} feats it gives"),
+                );
+            }
+        }
+    }
+
+    fn check_spells_known(&self, rules: Rules<'_>, report: &mut Report) {
+        for class in &self.classes {
+            for spell_level in 0..class.spell_known_list.len() {
+                let usage = class.spell_usage(rules.classes, spell_level);
+                if let Some(allowed) = usage.allowed
+                    && usage.over_limit()
+                {
+                    report.error(
+                        FindingKind::SpellsKnown {
+                            class: *class.class.get(),
+                            spell_level,
+                            known: usage.known,
+                            allowed,
+                        },
+                        format!(
+                            "{} knows {} level {spell_level} spells, the most is {allowed}",
+                            class.class.get(),
+                            usage.known
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_prestige_classes(&self, rules: Rules<'_>, report: &mut Report) {
+        for class in &self.classes {
+            let class = *class.class.get();
+            let Some(info) = rules.classes.classes.get(&class) else {
+                continue;
+            };
+
+            let unmet = self.unmet_class_prereqs(rules, info);
+            if !unmet.is_empty() {
+                let message = format!("{class} needs {}", describe_class_prereqs(rules, &unmet));
+                report.error(FindingKind::ClassPrereqs { class, unmet }, message);
+            }
+        }
+    }
+
+    fn check_domains(&self, rules: Rules<'_>, report: &mut Report) {
+        for class in &self.classes {
+            let name = *class.class.get();
+            let has_domains = rules
+                .classes
+                .classes
+                .get(&name)
+                .is_some_and(|x| x.has_domains);
+            let domains = class
+                .domains
+                .each_ref()
+                .map(|x| x.as_ref().map(|x| *x.get()));
+
+            match domains {
+                [Some(a), Some(b)] if a == b => report.error(
+                    FindingKind::SameDomains {
+                        class: name,
+                        domain: a,
+                    },
+                    format!("{name} has {} as both domains", rules.domains.name(a)),
+                ),
+                [None, _] | [_, None] if has_domains => report.error(
+                    FindingKind::MissingDomain(name),
+                    format!("{name} is missing a domain"),
+                ),
+                _ => {}
+            }
+
+            for domain in domains.into_iter().flatten() {
+                if !rules.domains.domains.contains_key(&domain) {
+                    report.error(
+                        FindingKind::UnknownDomain {
+                            class: name,
+                            domain,
+                        },
+                        format!("{name} has domain {domain}, which isn't in domains.2da"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_schools(&self, rules: Rules<'_>, report: &mut Report) {
+        for class in &self.classes {
+            let Some(school) = class.school.as_ref().map(|x| *x.get()) else {
+                continue;
+            };
+            if !rules.schools.schools.contains_key(&school) {
+                let class = *class.class.get();
+                report.error(
+                    FindingKind::UnknownSchool { class, school },
+                    format!("{class} has school {school}, which isn't in spellschools.2da"),
+                );
+            }
+        }
+    }
+
+    /// Rangers choose a favored enemy at level 1 and every 5 levels
+    fn check_favored_enemies(&self, rules: Rules<'_>, report: &mut Report) {
+        let level = usize::from(self.class_level(Class::Ranger));
+        let count = self.favored_enemies(rules.races).len();
+        let given = match level {
+            0 => 0,
+            level => 1 + level / 5,
+        };
+        let kind = FindingKind::FavoredEnemies { count, given };
+
+        if count > given {
+            report.error(
+                kind,
+                format!("{count} favored enemies, ranger levels give {given}"),
+            );
+        } else if count < given {
+            report.warning(
+                kind,
+                format!("{count} of the {given} favored enemies ranger levels give"),
+            );
+        }
+    }
+
+    /// Base items of the inventory and stacks larger than they allow
+    fn check_items(&self, rules: Rules<'_>, report: &mut Report) {
+        for item in self.inventory.iter() {
+            let name = item.display_name(rules.base_items);
+            let tag = item.tag.clone();
+            for property in item.properties.iter().flat_map(|x| &x.properties) {
+                if let Err(e) = rules.item_properties.check(property) {
+                    report.warning(
+                        FindingKind::InvalidItemProperty {
+                            tag: tag.clone(),
+                            property: *property,
+                        },
+                        format!(
+                            "{name} has {}: {e}",
+                            rules.item_properties.describe(property)
+                        ),
+                    );
+                }
+            }
+
+            let Some(base) = rules.base_items.items.get(&item.base_item) else {
+                report.error(
+                    FindingKind::UnknownBaseItem {
+                        tag,
+                        base_item: item.base_item,
+                    },
+                    format!(
+                        "{name} has base item {}, which isn't in baseitems.2da",
+                        item.base_item
+                    ),
+                );
+                continue;
+            };
+
+            if let Some(stack) = &item.stack_size
+                && *stack.get() > base.max_stack
+            {
+                report.warning(
+                    FindingKind::StackSize {
+                        tag,
+                        size: *stack.get(),
+                        max: base.max_stack,
+                    },
+                    format!(
+                        "{name} is a stack of {}, {} stack up to {}",
+                        stack.get(),
+                        base.name,
+                        base.max_stack
+                    ),
+                );
+            }
+        }
+    }
+
+    /// `cls_pres_*` requirements of `info` the character doesn't meet, groups
+    /// like `FeatOr` are unmet when none of their rows are
+    fn unmet_class_prereqs(&self, rules: Rules<'_>, info: &ClassInfo) -> Vec<ClassPrereq> {
+        let has_class = |class: Class| self.classes.iter().any(|x| *x.class.get() == class);
+        let casts_arcane = |spell_level: u8| {
+            self.classes.iter().any(|x| {
+                rules
+                    .classes
+                    .classes
+                    .get(x.class.get())
+                    .is_some_and(|info| info.arcane)
+                    && x.spell_known_list
+                        .get(usize::from(spell_level))
+                        .and_then(|x| x.as_ref())
+                        .is_some_and(|x| !x.spells.is_empty())
+            })
+        };
+        let any_row = |met: &dyn Fn(&ClassPrereq) -> bool| info.prereqs.iter().any(met);
+
+        let met = |prereq: &ClassPrereq| match prereq {
+            ClassPrereq::Feat(feat) => self.has_feat(*feat),
+            ClassPrereq::FeatOr(_) => {
+                any_row(&|x| matches!(x, ClassPrereq::FeatOr(feat) if self.has_feat(*feat)))
+            }
+            ClassPrereq::Skill { skill, ranks } => self.skills.ranks.get(*skill) >= *ranks,
+            ClassPrereq::AttackBonus(bonus) => self.base_attack_bonus(rules.classes) >= *bonus,
+            ClassPrereq::Race(_) => {
+                any_row(&|x| matches!(x, ClassPrereq::Race(race) if *race == self.race.id))
+            }
+            ClassPrereq::ArcaneSpell(level) => casts_arcane(*level),
+            ClassPrereq::ClassOr(_) => {
+                any_row(&|x| matches!(x, ClassPrereq::ClassOr(class) if has_class(*class)))
+            }
+            ClassPrereq::ClassNot(class) => !has_class(*class),
+            ClassPrereq::Other(_) => true,
+        };
+
+        info.prereqs.iter().filter(|x| !met(x)).cloned().collect()
+    }
+}
+
+/// Names unmet class requirements, with the rows of a group joined into one
+fn describe_class_prereqs(rules: Rules<'_>, unmet: &[ClassPrereq]) -> String {
+    let mut names = vec![];
+    let (mut feat_or, mut race, mut class_or) = (vec![], vec![], vec![]);
+
+    for prereq in unmet {
+        match prereq {
+            ClassPrereq::Feat(feat) => names.push(rules.feats.name((*feat).into())),
+            ClassPrereq::FeatOr(feat) => feat_or.push(rules.feats.name((*feat).into())),
+            ClassPrereq::Skill { skill, ranks } => {
+                names.push(format!("{} {ranks} ranks", rules.skills.name(*skill)));
+            }
+            ClassPrereq::AttackBonus(bonus) => names.push(format!("base attack bonus +{bonus}")),
+            ClassPrereq::Race(id) => race.push(rules.races.name(*id)),
+            ClassPrereq::ArcaneSpell(level) => names.push(format!("level {level} arcane spells")),
+            ClassPrereq::ClassOr(class) => class_or.push(class.to_string()),
+            ClassPrereq::ClassNot(class) => names.push(format!("no {class} levels")),
+            ClassPrereq::Other(_) => {}
+        }
+    }
+
+    for group in [feat_or, race] {
+        if !group.is_empty() {
+            names.push(format!("one of {}", group.join(", ")));
+        }
+    }
+    if !class_or.is_empty() {
+        names.push(format!("a level of {}", class_or.join(" or ")));
+    }
+
+    names.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        feat::FeatPrereqs,
+        player::Field,
+        tests::{
+            ClassRow, GameData, class_feats_2da, classes_2da, fixture, make_feat,
+            make_skill_record, spell_levels_2da, two_da,
+        },
+    };
+
+    #[test]
+    fn validate() {
+        let bic = fixture!("player.bic");
+        let sorcerer = ClassRow {
+            label: "Sorcerer",
+            hit_die: 4,
+            feats: "CLS_FEAT_SORC",
+            spells_known: "CLS_SPKN_SORC",
+            prereqs: "CLS_PRES_SORC",
+            // Only open to lawful characters
+            alignment: (0x05, 0x01, false),
+            arcane: true,
+            spell_ability: "CHA",
+            ..Default::default()
+        };
+        let mut game = GameData::new()
+            .subraces(&[(12, &[("StrAdjust", "-2"), ("DexAdjust", "2")])])
+            .table("classes.2da", classes_2da(&[(Class::Sorcerer, sorcerer)]))
+            .table(
+                "cls_feat_sorc.2da",
+                class_feats_2da(&[(46, 3, 1), (501, 0, -1)]),
+            )
+            .table("cls_spkn_sorc.2da", spell_levels_2da(&[(3, &[6, 3, 1])]))
+            .table(
+                "cls_pres_sorc.2da",
+                two_da(
+                    "LABEL\tReqType\tReqParam1\tReqParam2",
+                    &[
+                        (0, "A\tFEAT\t46\t****"),
+                        (1, "B\tFEATOR\t9998\t****"),
+                        (2, "C\tFEATOR\t173\t****"),
+                        (3, "D\tSKILL\t12\t4"),
+                        (4, "E\tCLASSNOT\t10\t****"),
+                        (5, "F\tRACE\t1\t****"),
+                        (6, "G\tVAR\tX\t1"),
+                    ],
+                ),
+            )
+            .build();
+        let mut player = game.read_player(&bic);
+        let classes = ClassRecord::new(&mut game.reader).unwrap();
+        let races = RaceRecord::new(&game.tlk, &mut game.reader).unwrap();
+        let skills = make_skill_record(
+            &(0..30).map(|i| (i, i != 5)).collect::<Vec<_>>(),
+            &[(Class::Sorcerer, &[1, 7, 16, 23])],
+        );
+        let any_class = FeatPrereqs {
+            all_classes_can_use: true,
+            ..Default::default()
+        };
+        let feats = FeatRecord {
+            feats: [
+                make_feat(173, "FEAT_173", any_class.clone()),
+                make_feat(189, "FEAT_189", any_class),
+                make_feat(501, "FEAT_501", FeatPrereqs::default()),
+            ]
+            .into(),
+        };
+        let rules = Rules {
+            classes: &classes,
+            skills: &skills,
+            feats: &feats,
+            races: &races,
+            domains: &DomainRecord::default(),
+            schools: &SchoolRecord::default(),
+            spells: &SpellRecord::default(),
+            base_items: &BaseItemRecord::default(),
+            item_properties: &ItemPropertyRecord::default(),
+        };
+        // Items are left to the inventory tests
+        let findings = |player: &Player| {
+            player
+                .validate(rules)
+                .into_iter()
+                .filter(|x| x.area() != Area::Items)
+                .map(|x| (x.severity, x.kind))
+                .collect::<Vec<_>>()
+        };
+        let spells_known = |spell_level, known, allowed| FindingKind::SpellsKnown {
+            class: Class::Sorcerer,
+            spell_level,
+            known,
+            allowed,
+        };
+
+        // Starting scores are 12 12 16 18 10 18 after halfling adjustments and
+        // the level 4 Cha increase
+        assert_eq!(
+            findings(&player),
+            [
+                (Severity::Error, FindingKind::PointBuy { spent: 52 }),
+                (Severity::Error, FindingKind::Alignment(Class::Sorcerer)),
+                (Severity::Error, spells_known(1, 5, 3)),
+                (Severity::Error, spells_known(2, 2, 1)),
+                (
+                    Severity::Error,
+                    FindingKind::ClassPrereqs {
+                        class: Class::Sorcerer,
+                        unmet: vec![
+                            ClassPrereq::Skill {
+                                skill: 12,
+                                ranks: 4
+                            },
+                            ClassPrereq::Race(1)
+                        ],
+                    }
+                ),
+            ]
+        );
+
+        for (ability, score) in [(2, 15), (3, 14), (5, 15)] {
+            let field = player.attributes.get_mut(ability).unwrap();
+            field.set(score, |x| Field::Byte(*x));
+        }
+        player.alignment.lawful_chaotic.set(80, |x| Field::Byte(*x));
+        player.add_level_feat(1, 501).unwrap();
+        player.skills.ranks.set(12, 4);
+
+        assert_eq!(
+            findings(&player),
+            [
+                (
+                    Severity::Error,
+                    FindingKind::SkillRanks {
+                        skill: 12,
+                        ranks: 4,
+                        max: 3
+                    }
+                ),
+                (
+                    Severity::Error,
+                    FindingKind::LevelFeats {
+                        level: 2,
+                        choices: FeatChoices { taken: 1, given: 0 }
+                    }
+                ),
+                (Severity::Error, spells_known(1, 5, 3)),
+                (Severity::Error, spells_known(2, 2, 1)),
+                (
+                    Severity::Error,
+                    FindingKind::ClassPrereqs {
+                        class: Class::Sorcerer,
+                        unmet: vec![ClassPrereq::Race(1)],
+                    }
+                ),
+                (Severity::Warning, FindingKind::PointBuy { spent: 30 }),
+            ]
+        );
+    }
+}
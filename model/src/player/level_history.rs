@@ -0,0 +1,424 @@
+use crate::{
+    error::Error,
+    field_ref::FieldRef,
+    ids::{class::Class, spell::Spell},
+    player::{Player, feat_list::FeatList, player_class::SpellKnownList, skills::LevelSkills},
+};
+use nwn_lib::files::gff::{
+    field::{Field, LabeledField},
+    label::Label,
+    r#struct::{Struct, StructField},
+};
+
+/// One level of a `LvlStatList`, what the character gained when levelling up
+#[derive(Debug, Clone)]
+pub struct LevelEntry {
+    pub class: Class,
+    /// Hit points rolled for the level
+    pub hit_die: FieldRef<u8>,
+    pub epic_level: bool,
+    /// Index of the ability raised at this level, `0..6` for Str to Cha
+    pub ability_increase: Option<u8>,
+    pub skills: LevelSkills,
+    /// Feats gained, `None` if the entry has no `FeatList`
+    pub feats: Option<FeatList>,
+    /// Spells learned at each spell level
+    pub spells: [Option<SpellKnownList>; 10],
+}
+impl LevelEntry {
+    pub fn new(s: &Struct) -> Result<Self, Error> {
+        let find = |label: &str| {
+            s.find_direct(label)
+                .ok_or_else(|| Error::MissingField(format!("{label} in LvlStatList entry")))
+        };
+
+        let class = find("LvlStatClass")?
+            .read_field(Field::expect_byte)
+            .map(|x| Class(x.into()))?;
+
+        let epic_level = s
+            .find_direct("EpicLevel")
+            .map(|x| x.read_field(Field::expect_byte))
+            .transpose()?
+            .is_some_and(|x| x != 0);
+
+        let ability_increase = s
+            .find_direct("LvlStatAbility")
+            .map(|x| x.read_field(Field::expect_byte))
+            .transpose()?;
+
+        let feats = s
+            .find_direct("FeatList")
+            .map(FeatList::from_field)
+            .transpose()?;
+
+        let mut spells = [const { None }; 10];
+        for (level, spells) in spells.iter_mut().enumerate() {
+            *spells = s
+                .find_direct(&format!("KnownList{level}"))
+                .map(SpellKnownList::new)
+                .transpose()?;
+        }
+
+        Ok(Self {
+            class,
+            hit_die: FieldRef::new(find("LvlStatHitDie")?, Field::expect_byte)?,
+            epic_level,
+            ability_increase,
+            skills: LevelSkills::new(s)?,
+            feats,
+            spells,
+        })
+    }
+
+    pub fn feat_ids(&self) -> impl Iterator<Item = u16> + '_ {
+        self.feats
+            .iter()
+            .flat_map(|x| x.list_ref.get().iter().map(|x| *x.get()))
+    }
+}
+
+/// A creature's `LvlStatList`, the first entry is level 1
+#[derive(Debug, Default, Clone)]
+pub struct LevelHistory {
+    /// The `LvlStatList` field, `None` for creatures without one
+    pub list_ref: Option<StructField>,
+    pub levels: Vec<LevelEntry>,
+}
+impl LevelHistory {
+    pub fn from_field(list: StructField) -> Result<Self, Error> {
+        let levels = list.read_field(|f| -> Result<Vec<_>, Error> {
+            f.expect_list()?.iter().map(LevelEntry::new).collect()
+        })?;
+
+        Ok(Self {
+            list_ref: Some(list),
+            levels,
+        })
+    }
+
+    fn entry(&self, level: usize) -> Result<&LevelEntry, Error> {
+        self.levels
+            .get(level)
+            .ok_or_else(|| Error::MissingField(format!("Level {} in LvlStatList", level + 1)))
+    }
+
+    fn entry_mut(&mut self, level: usize) -> Result<&mut LevelEntry, Error> {
+        self.levels
+            .get_mut(level)
+            .ok_or_else(|| Error::MissingField(format!("Level {} in LvlStatList", level + 1)))
+    }
+
+    /// Removes the latest `LvlStatList` entry
+    pub(super) fn pop(&mut self) -> Result<LevelEntry, Error> {
+        let list_ref = self
+            .list_ref
+            .as_ref()
+            .ok_or_else(|| Error::MissingField("LvlStatList".into()))?;
+
+        let level = self
+            .levels
+            .pop()
+            .ok_or_else(|| Error::RuleViolation("There are no levels to remove".into()))?;
+
+        let mut lock = list_ref.write()?;
+        if let Field::List(entries) = &mut lock.field {
+            entries.pop();
+        }
+
+        Ok(level)
+    }
+
+    /// Known spell list of `level` for `spell_level`, adding an empty one if the entry has none
+    fn known_list_mut(
+        &mut self,
+        level: usize,
+        spell_level: usize,
+    ) -> Result<&mut SpellKnownList, Error> {
+        let list_ref = self.list_ref.clone();
+        let template = self
+            .levels
+            .iter()
+            .flat_map(|x| x.spells.iter().flatten())
+            .find_map(|x| x.entries.first().cloned());
+        let entry = self.entry_mut(level)?;
+
+        let known = entry
+            .spells
+            .get_mut(spell_level)
+            .ok_or_else(|| Error::ParseError(format!("Invalid spell level {spell_level}")))?;
+
+        if known.is_none() {
+            let list_ref = list_ref.ok_or_else(|| Error::MissingField("LvlStatList".into()))?;
+            let field = StructField::new(LabeledField::new(
+                Label::from_string(&format!("KnownList{spell_level}")),
+                Field::List(vec![]),
+            ));
+
+            let mut lock = list_ref.write()?;
+            let Field::List(entries) = &mut lock.field else {
+                return Err(Error::ParseError("LvlStatList isn't a list".into()));
+            };
+            entries
+                .get_mut(level)
+                .ok_or_else(|| Error::MissingField(format!("Level {} in LvlStatList", level + 1)))?
+                .fields
+                .push(field.clone());
+            drop(lock);
+
+            let mut list = SpellKnownList::new(field)?;
+            list.template = template;
+            *known = Some(list);
+        }
+
+        Ok(known.as_mut().expect("Known list was just added"))
+    }
+}
+
+pub(super) fn add_hit_points(field: &mut Option<FieldRef<i16>>, delta: i16) {
+    if let Some(field) = field {
+        let value = field.get().saturating_add(delta);
+        field.set(value, |x| Field::Short(*x));
+    }
+}
+
+impl Player {
+    /// Sets the hit points rolled at `level`, moving the creature's hit points by the difference
+    pub fn set_level_hit_die(&mut self, level: usize, roll: u8) -> Result<(), Error> {
+        let entry = self.history.entry_mut(level)?;
+        let delta = i16::from(roll) - i16::from(*entry.hit_die.get());
+        entry.hit_die.set(roll, |x| Field::Byte(*x));
+
+        add_hit_points(&mut self.hit_points.base, delta);
+        add_hit_points(&mut self.hit_points.current, delta);
+        add_hit_points(&mut self.hit_points.max, delta);
+
+        Ok(())
+    }
+
+    /// Sets the points left unspent after `level`, the latest level also sets the creature's
+    pub fn set_level_skill_points(&mut self, level: usize, points: u16) -> Result<(), Error> {
+        if level + 1 == self.history.levels.len() {
+            self.set_unspent_skill_points(points);
+            return Ok(());
+        }
+
+        let entry = self.history.entry_mut(level)?;
+        let skill_points =
+            entry.skills.skill_points.as_mut().ok_or_else(|| {
+                Error::MissingField(format!("SkillPoints in level {}", level + 1))
+            })?;
+        skill_points.set(points, |x| Field::Word(*x));
+
+        Ok(())
+    }
+
+    /// Adds a feat gained at `level`, and to the creature's feats if it doesn't have it
+    pub fn add_level_feat(&mut self, level: usize, feat: u16) -> Result<(), Error> {
+        let entry = self.history.entry_mut(level)?;
+        let feats = entry
+            .feats
+            .as_mut()
+            .ok_or_else(|| Error::MissingField(format!("FeatList in level {}", level + 1)))?;
+
+        feats.add_feat(feat);
+
+        if !self.feats.list_ref.get().iter().any(|x| *x.get() == feat) {
+            self.feats.add_feat(feat);
+        }
+
+        Ok(())
+    }
+
+    /// Removes feat `index` of `level`, and from the creature's feats unless another level grants it
+    pub fn remove_level_feat(&mut self, level: usize, index: usize) -> Result<(), Error> {
+        let entry = self.history.entry_mut(level)?;
+        let feats = entry
+            .feats
+            .as_mut()
+            .ok_or_else(|| Error::MissingField(format!("FeatList in level {}", level + 1)))?;
+
+        let feat = *feats
+            .list_ref
+            .get()
+            .get(index)
+            .ok_or_else(|| Error::MissingField(format!("Feat {index} in level {}", level + 1)))?
+            .get();
+
+        feats.remove_feat(index);
+        self.remove_ungranted_feat(feat);
+
+        Ok(())
+    }
+
+    /// Removes `feat` from the creature's feats unless a level still grants it
+    pub(super) fn remove_ungranted_feat(&mut self, feat: u16) {
+        let still_granted = self
+            .history
+            .levels
+            .iter()
+            .any(|x| x.feat_ids().any(|x| x == feat));
+
+        let creature_index = self
+            .feats
+            .list_ref
+            .get()
+            .iter()
+            .position(|x| *x.get() == feat);
+
+        if !still_granted && let Some(i) = creature_index {
+            self.feats.remove_feat(i);
+        }
+    }
+
+    /// Removes `spell` from the known spells of `class`
+    pub(super) fn remove_class_spell(
+        &mut self,
+        class: Class,
+        spell_level: usize,
+        spell: Spell,
+    ) -> Result<(), Error> {
+        let class_known = self
+            .classes
+            .iter_mut()
+            .find(|x| *x.class.get() == class)
+            .and_then(|x| x.spell_known_list.get_mut(spell_level)?.as_mut());
+
+        if let Some(class_known) = class_known
+            && let Some(i) = class_known.spells.iter().position(|x| *x == spell)
+        {
+            class_known.remove_spell(i)?;
+        }
+        Ok(())
+    }
+
+    /// Adds a spell learned at `level`, and to the known spells of that level's class
+    pub fn add_level_spell(
+        &mut self,
+        level: usize,
+        spell_level: usize,
+        spell: Spell,
+    ) -> Result<(), Error> {
+        let class = self.history.entry(level)?.class;
+
+        let known = self.history.known_list_mut(level, spell_level)?;
+        if known.spells.contains(&spell) {
+            return Err(Error::RuleViolation(format!(
+                "Spell {spell} is already learned at level {}",
+                level + 1
+            )));
+        }
+        known.add_spell(spell)?;
+
+        let class_known = self
+            .classes
+            .iter_mut()
+            .find(|x| *x.class.get() == class)
+            .and_then(|x| x.spell_known_list.get_mut(spell_level)?.as_mut());
+
+        if let Some(class_known) = class_known
+            && !class_known.spells.contains(&spell)
+        {
+            class_known.add_spell(spell)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes spell `index` learned at `level`, and from the known spells of that level's class
+    pub fn remove_level_spell(
+        &mut self,
+        level: usize,
+        spell_level: usize,
+        index: usize,
+    ) -> Result<(), Error> {
+        let entry = self.history.entry_mut(level)?;
+        let class = entry.class;
+
+        let known = entry
+            .spells
+            .get_mut(spell_level)
+            .and_then(Option::as_mut)
+            .ok_or_else(|| {
+                Error::MissingField(format!("KnownList{spell_level} in level {}", level + 1))
+            })?;
+
+        let spell = *known
+            .spells
+            .get(index)
+            .ok_or_else(|| Error::MissingField(format!("Spell {index} in level {}", level + 1)))?;
+
+        known.remove_spell(index)?;
+        self.remove_class_spell(class, spell_level, spell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{GameData, feat_ids, fixture, write_and_read};
+
+    #[test]
+    fn edit_level_history() {
+        let bic = fixture!("player.bic");
+        let mut player = GameData::new().build().read_player(&bic);
+
+        let levels = &player.history.levels;
+        assert_eq!(levels.len(), 4);
+        assert!(levels.iter().all(|x| x.class == Class::Sorcerer));
+        assert_eq!(*levels[0].hit_die.get(), 4);
+        assert_eq!(levels[3].ability_increase, Some(5));
+        assert_eq!(levels[0].feat_ids().count(), 14);
+        assert_eq!(levels[0].spells[0].as_ref().unwrap().spells.len(), 4);
+        assert!(levels[1].spells[1].is_none());
+
+        player.set_level_hit_die(1, 3).unwrap();
+        assert_eq!(*player.hit_points.base.as_ref().unwrap().get(), 15);
+
+        // Feat 189 is only on the creature, 1 is new
+        player.add_level_feat(1, 1).unwrap();
+        assert_eq!(feat_ids(&player).last(), Some(&1));
+        player.remove_level_feat(0, 0).unwrap();
+        assert!(!feat_ids(&player).contains(&46));
+
+        player.add_level_spell(1, 1, Spell::AcidFog).unwrap();
+        assert!(matches!(
+            player.add_level_spell(1, 1, Spell::AcidFog),
+            Err(Error::RuleViolation(_))
+        ));
+        let class_known = |player: &Player| {
+            player.classes[0].spell_known_list[1]
+                .as_ref()
+                .unwrap()
+                .spells
+                .clone()
+        };
+        assert!(class_known(&player).contains(&Spell::AcidFog));
+
+        player.remove_level_spell(0, 1, 0).unwrap();
+        assert!(!class_known(&player).contains(&Spell(10)));
+
+        let bic = write_and_read(&bic);
+        let history =
+            LevelHistory::from_field(bic.root.find_direct("LvlStatList").unwrap()).unwrap();
+        let hit_points = bic
+            .root
+            .find_direct("HitPoints")
+            .unwrap()
+            .read_field(Field::expect_short)
+            .unwrap();
+
+        assert_eq!(*history.levels[1].hit_die.get(), 3);
+        assert_eq!(hit_points, 15);
+        assert_eq!(history.levels[1].feat_ids().collect::<Vec<_>>(), [1]);
+        assert_eq!(history.levels[0].feat_ids().next(), Some(173));
+        assert_eq!(
+            history.levels[1].spells[1].as_ref().unwrap().spells,
+            [Spell::AcidFog]
+        );
+        assert_eq!(
+            history.levels[0].spells[1].as_ref().unwrap().spells,
+            [Spell(66)]
+        );
+    }
+}
@@ -0,0 +1,533 @@
+use crate::{
+    class::{ClassInfo, ClassRecord},
+    error::Error,
+    field_ref::FieldRef,
+    ids::class::Class,
+    player::{
+        Player, PlayerClass, ability_modifier,
+        feat_list::FeatList,
+        level_history::{LevelEntry, add_hit_points},
+        save_field, total_level,
+    },
+    race::{RaceInfo, RaceRecord},
+};
+use nwn_lib::files::gff::{
+    field::{Field, LabeledField},
+    label::Label,
+    r#struct::{Struct, StructField},
+};
+
+/// Highest character level the game allows
+pub const MAX_LEVEL: u16 = 30;
+/// Levels past this one are epic levels
+const EPIC_LEVEL: u16 = 20;
+
+fn new_struct<'a>(id: u32, fields: impl IntoIterator<Item = (&'a str, Field)>) -> Struct {
+    let fields = fields
+        .into_iter()
+        .map(|(label, field)| StructField::new(LabeledField::new(Label::from_string(label), field)))
+        .collect();
+
+    Struct {
+        id,
+        original_data_or_data_offset: u32::MAX,
+        fields,
+    }
+}
+
+/// Struct id the entries of `list` use
+fn entry_id(list: &StructField, default: u32) -> u32 {
+    list.read_field(|f| f.try_list()?.first().map(|x| x.id))
+        .unwrap_or(default)
+}
+
+/// `ClassList` entry for the first level of `class`
+fn new_class_entry(list: &StructField, class: Class) -> Result<(Struct, PlayerClass), Error> {
+    let entry = new_struct(
+        entry_id(list, 2),
+        [
+            ("Class", Field::Int(class.0)),
+            ("ClassLevel", Field::Short(0)),
+        ],
+    );
+    let player_class = PlayerClass::new(&entry)?;
+    Ok((entry, player_class))
+}
+
+/// Whether character level `level` raises an ability score
+pub fn raises_ability(level: u16) -> bool {
+    level.is_multiple_of(4)
+}
+
+/// Whether character level `level` gives a general feat
+pub fn gives_general_feat(level: u16) -> bool {
+    level == 1 || level.is_multiple_of(3)
+}
+
+/// Feats character level `level`, a level `class_level` of `info`, gives to choose:
+/// the general feat, the class's bonus feats and the race's extra feats at level 1
+pub fn feats_given(level: u16, class_level: u16, info: &ClassInfo, race: Option<&RaceInfo>) -> u8 {
+    let racial = match level {
+        1 => race.map(|x| x.extra_feats_at_first_level).unwrap_or(0),
+        _ => 0,
+    };
+
+    u8::from(gives_general_feat(level))
+        .saturating_add(info.bonus_feats_at(class_level))
+        .saturating_add(racial)
+}
+
+/// Change of `max` hit points when going from `level - 1` to `level`, the
+/// constitution modifier counts for every level so an increase is retroactive
+fn max_hit_points_delta(hit_die: u8, level: u16, con_before: i8, con_after: i8) -> i16 {
+    let level = level as i16;
+    i16::from(hit_die) + i16::from(con_after) * level - i16::from(con_before) * (level - 1)
+}
+
+impl Player {
+    pub(super) fn class_level(&self, class: Class) -> u16 {
+        self.classes
+            .iter()
+            .find(|x| *x.class.get() == class)
+            .map(|x| (*x.level.get()).max(0) as u16)
+            .unwrap_or(0)
+    }
+
+    pub(super) fn has_feat(&self, feat: u16) -> bool {
+        self.feats.list_ref.get().iter().any(|x| *x.get() == feat)
+    }
+
+    /// Moves base attack bonus and saves from `info`'s class level `from` to `to`
+    fn apply_progression(&mut self, info: &ClassInfo, from: u16, to: u16) {
+        let bab = i16::from(info.attack_bonus_at(to)) - i16::from(info.attack_bonus_at(from));
+        if let Some(x) = &mut self.base_attack_bonus {
+            x.modify(
+                |x| *x = (i16::from(*x) + bab).clamp(0, u8::MAX.into()) as u8,
+                |x| Field::Byte(*x),
+            );
+        }
+
+        let (from, to) = (info.saves_at(from), info.saves_at(to));
+        let saves = [
+            (&mut self.saves.fort, to.fort - from.fort),
+            (&mut self.saves.reflex, to.reflex - from.reflex),
+            (&mut self.saves.will, to.will - from.will),
+        ];
+
+        for (save, delta) in saves {
+            if let Some(save) = save {
+                save.modify(|x| *x = x.saturating_add(delta), save_field);
+            }
+        }
+    }
+
+    fn raise_ability(&mut self, ability: u8, delta: i8) {
+        if let Some(x) = self.attributes.get_mut(ability) {
+            x.modify(|x| *x = x.saturating_add_signed(delta), |x| Field::Byte(*x));
+        }
+    }
+
+    /// Adds a level of `class` the way the game's level up does, taking the
+    /// highest hit die roll.
+    ///
+    /// Writes the class level, hit points, base attack bonus, saves, the feats
+    /// the class grants, skill points with the race's extra points and a new
+    /// `LvlStatList` entry. Every fourth level needs an `ability_increase`,
+    /// `0..6` for Str to Cha. Returns how many feats the level gives to
+    /// choose, they're left for the caller to add to the new entry
+    pub fn add_class_level(
+        &mut self,
+        classes: &ClassRecord,
+        races: &RaceRecord,
+        class: Class,
+        ability_increase: Option<u8>,
+    ) -> Result<u8, Error> {
+        let info = classes.get(class)?;
+        let level = total_level(&self.classes) + 1;
+        let class_level = self.class_level(class) + 1;
+
+        if level > MAX_LEVEL {
+            return Err(Error::RuleViolation(format!(
+                "Characters can't go past level {MAX_LEVEL}"
+            )));
+        }
+
+        if let Some(max) = info.max_level
+            && class_level > max
+        {
+            return Err(Error::RuleViolation(format!(
+                "{class} can't go past level {max}"
+            )));
+        }
+
+        if self.history.levels.len() != usize::from(level - 1) {
+            return Err(Error::RuleViolation(format!(
+                "The level history has {} levels but the classes add up to {}",
+                self.history.levels.len(),
+                level - 1
+            )));
+        }
+
+        match (raises_ability(level), ability_increase) {
+            (true, Some(0..6)) | (false, None) => {}
+            (true, _) => {
+                return Err(Error::RuleViolation(format!(
+                    "Level {level} needs an ability to increase"
+                )));
+            }
+            (false, Some(_)) => {
+                return Err(Error::RuleViolation(format!(
+                    "Level {level} doesn't increase an ability"
+                )));
+            }
+        }
+
+        let class_list = self
+            .class_list
+            .clone()
+            .ok_or_else(|| Error::MissingField("ClassList".into()))?;
+        let history_list = self
+            .history
+            .list_ref
+            .clone()
+            .ok_or_else(|| Error::MissingField("LvlStatList".into()))?;
+
+        // Scores after this level's increase
+        let score = |ability: u8, field: &FieldRef<u8>| {
+            let increase = u8::from(ability_increase == Some(ability));
+            field.get().saturating_add(increase)
+        };
+        let con_before = ability_modifier(*self.attributes.con.get());
+        let con_after = ability_modifier(score(2, &self.attributes.con));
+        let int = ability_modifier(score(3, &self.attributes.int));
+
+        let race = races.get(self.race.id, self.race.subrace_id);
+        let racial_points = race.map(|x| x.extra_skill_points_per_level).unwrap_or(0);
+        let per_level = (i16::from(info.skill_point_base) + i16::from(int)).max(1) as u16
+            + u16::from(racial_points);
+        let skill_points = if level == 1 { per_level * 4 } else { per_level };
+        let unspent = self.unspent_skill_points().unwrap_or(0) + skill_points;
+
+        let mut feats = vec![];
+        for feat in info.feats_granted_at(class_level) {
+            if !self.has_feat(feat) && !feats.contains(&feat) {
+                feats.push(feat);
+            }
+        }
+
+        let skill_list = (0..self.skills.ranks.ranks.len())
+            .map(|_| new_struct(0, [("Rank", Field::Byte(0))]))
+            .collect();
+
+        let fields = ability_increase
+            .map(|x| ("LvlStatAbility", Field::Byte(x)))
+            .into_iter()
+            .chain([
+                ("LvlStatHitDie", Field::Byte(info.hit_die)),
+                ("LvlStatClass", Field::Byte(class.0 as u8)),
+                ("EpicLevel", Field::Byte((level > EPIC_LEVEL).into())),
+                ("SkillPoints", Field::Word(unspent)),
+                ("SkillList", Field::List(skill_list)),
+                (
+                    "FeatList",
+                    Field::List(
+                        feats
+                            .iter()
+                            .map(|x| FeatList::create_feat_struct(*x))
+                            .collect(),
+                    ),
+                ),
+            ]);
+        let entry = new_struct(entry_id(&history_list, 0), fields);
+        let level_entry = LevelEntry::new(&entry)?;
+
+        let index = self.classes.iter().position(|x| *x.class.get() == class);
+        let new_class = match index {
+            Some(_) => None,
+            None => Some(new_class_entry(&class_list, class)?),
+        };
+
+        // Both lists are locked before either changes so a failure leaves the character as it was
+        let mut class_lock = class_list.write()?;
+        let mut history_lock = history_list.write()?;
+        let (Field::List(class_entries), Field::List(level_entries)) =
+            (&mut class_lock.field, &mut history_lock.field)
+        else {
+            return Err(Error::ParseError(
+                "ClassList or LvlStatList isn't a list".into(),
+            ));
+        };
+
+        level_entries.push(entry);
+        self.history.levels.push(level_entry);
+
+        let index = index.unwrap_or(self.classes.len());
+        if let Some((entry, player_class)) = new_class {
+            class_entries.push(entry);
+            self.classes.push(player_class);
+        }
+        drop(history_lock);
+        drop(class_lock);
+
+        if let Some(ability) = ability_increase {
+            self.raise_ability(ability, 1);
+        }
+
+        self.classes[index]
+            .level
+            .set(class_level as i16, |x| Field::Short(*x));
+
+        let max_delta = max_hit_points_delta(info.hit_die, level, con_before, con_after);
+        add_hit_points(&mut self.hit_points.base, info.hit_die.into());
+        add_hit_points(&mut self.hit_points.current, max_delta);
+        add_hit_points(&mut self.hit_points.max, max_delta);
+
+        self.apply_progression(info, class_level - 1, class_level);
+
+        for feat in feats {
+            self.feats.add_feat(feat);
+        }
+
+        self.set_unspent_skill_points(unspent);
+
+        Ok(feats_given(level, class_level, info, race))
+    }
+
+    /// Undoes the most recent level using its `LvlStatList` entry.
+    ///
+    /// Ranks, feats and spells gained at the level are removed, feats another
+    /// level also grants are kept, and unspent skill points go back to what the
+    /// previous level left
+    pub fn remove_latest_level(&mut self, record: &ClassRecord) -> Result<(), Error> {
+        let entry = self
+            .history
+            .levels
+            .last()
+            .ok_or_else(|| Error::RuleViolation("There are no levels to remove".into()))?;
+
+        let class = entry.class;
+        let info = record.get(class)?;
+        let level = self.history.levels.len() as u16;
+
+        let class_index = self
+            .classes
+            .iter()
+            .position(|x| *x.class.get() == class)
+            .ok_or_else(|| Error::MissingField(format!("{class} in ClassList")))?;
+        let class_level = self.class_level(class).max(1);
+
+        let entry = self.history.pop()?;
+
+        for (skill, rank) in entry.skills.ranks.ranks.iter().enumerate() {
+            let rank = *rank.get();
+            if rank > 0 {
+                let current = self.skills.ranks.get(skill);
+                self.skills.ranks.set(skill, current.saturating_sub(rank));
+            }
+        }
+
+        let previous_points = self
+            .history
+            .levels
+            .last()
+            .and_then(|x| x.skills.skill_points.as_ref())
+            .map(|x| *x.get())
+            .unwrap_or(0);
+        self.set_unspent_skill_points(previous_points);
+
+        for feat in entry.feat_ids() {
+            self.remove_ungranted_feat(feat);
+        }
+
+        for (spell_level, spells) in entry.spells.iter().enumerate() {
+            for spell in spells.iter().flat_map(|x| x.spells.iter()) {
+                self.remove_class_spell(class, spell_level, *spell)?;
+            }
+        }
+
+        let con_after = ability_modifier(*self.attributes.con.get());
+        if let Some(ability) = entry.ability_increase {
+            self.raise_ability(ability, -1);
+        }
+        let con_before = ability_modifier(*self.attributes.con.get());
+
+        let hit_die = *entry.hit_die.get();
+        let max_delta = max_hit_points_delta(hit_die, level, con_before, con_after);
+        add_hit_points(&mut self.hit_points.base, -i16::from(hit_die));
+        add_hit_points(&mut self.hit_points.current, -max_delta);
+        add_hit_points(&mut self.hit_points.max, -max_delta);
+
+        self.apply_progression(info, class_level, class_level - 1);
+
+        if class_level > 1 {
+            self.classes[class_index]
+                .level
+                .set(class_level as i16 - 1, |x| Field::Short(*x));
+        } else {
+            if let Some(list) = &self.class_list {
+                let mut lock = list.write()?;
+                if let Field::List(entries) = &mut lock.field
+                    && class_index < entries.len()
+                {
+                    entries.remove(class_index);
+                }
+            }
+
+            self.classes.remove(class_index);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        player::level_history::LevelHistory,
+        tests::{
+            ClassRow, GameData, class_feats_2da, classes_2da, feat_ids, fixture, sorcerer, two_da,
+            wizard, write_and_read, write_gff,
+        },
+    };
+
+    #[test]
+    fn level_up() {
+        let bic = fixture!("player.bic");
+        let classes = [
+            (
+                Class::Sorcerer,
+                ClassRow {
+                    feats: "CLS_FEAT_SORC",
+                    ..sorcerer()
+                },
+            ),
+            (
+                Class::Wizard,
+                ClassRow {
+                    feats: "CLS_FEAT_WIZ",
+                    bonus_feats: "CLS_BFEAT_WIZ",
+                    ..wizard()
+                },
+            ),
+        ];
+        // The fixture's halfling subrace gets an extra skill point each level
+        let mut game = GameData::new()
+            .arcane_progression()
+            .subraces(&[(12, &[("ExtraSkillPointsPerLevel", "1")])])
+            .table("classes.2da", classes_2da(&classes))
+            .table(
+                "cls_feat_sorc.2da",
+                class_feats_2da(&[(46, 3, 1), (500, 3, 5)]),
+            )
+            .table(
+                "cls_feat_wiz.2da",
+                class_feats_2da(&[(46, 3, 1), (502, 3, 1)]),
+            )
+            .table("cls_bfeat_wiz.2da", two_da("Bonus", &[(0, "1")]))
+            .build();
+        let mut player = game.read_player(&bic);
+        let classes = ClassRecord::new(&mut game.reader).unwrap();
+        let races = RaceRecord::new(&game.tlk, &mut game.reader).unwrap();
+        let original = write_gff(&bic);
+
+        let hit_points = |player: &Player| {
+            let hp = &player.hit_points;
+            [&hp.base, &hp.current, &hp.max].map(|x| *x.as_ref().unwrap().get())
+        };
+        let saves = |player: &Player| {
+            let saves = &player.saves;
+            [&saves.fort, &saves.reflex, &saves.will].map(|x| *x.as_ref().unwrap().get())
+        };
+        let bab = |player: &Player| *player.base_attack_bonus.as_ref().unwrap().get();
+
+        assert!(matches!(
+            player.add_class_level(&classes, &races, Class::Wizard, Some(0)),
+            Err(Error::RuleViolation(_))
+        ));
+
+        // A new wizard class at level 5 with a bonus feat to choose
+        let feats = player
+            .add_class_level(&classes, &races, Class::Wizard, None)
+            .unwrap();
+        assert_eq!(feats, 1);
+        assert_eq!(player.classes.len(), 2);
+        assert_eq!(*player.classes[1].level.get(), 1);
+        assert_eq!(hit_points(&player), [20, 22, 35]);
+        assert_eq!(saves(&player), [4, 5, 7]);
+        assert_eq!(bab(&player), 2);
+        assert_eq!(player.unspent_skill_points(), Some(9));
+
+        let level = player.history.levels.last().unwrap();
+        assert_eq!(level.class, Class::Wizard);
+        assert_eq!(level.feat_ids().collect::<Vec<_>>(), [502]);
+        assert!(feat_ids(&player).contains(&502));
+
+        // Level 6 gives a general feat
+        let feats = player
+            .add_class_level(&classes, &races, Class::Sorcerer, None)
+            .unwrap();
+        assert_eq!(feats, 1);
+        assert_eq!(*player.classes[0].level.get(), 5);
+        assert_eq!(bab(&player), 3);
+        assert!(feat_ids(&player).contains(&500));
+        assert_eq!(player.unspent_skill_points(), Some(16));
+
+        player.remove_latest_level(&classes).unwrap();
+        player.remove_latest_level(&classes).unwrap();
+        assert_eq!(write_gff(&bic), original);
+
+        // Level 4 raised charisma, take it again raising constitution
+        player.remove_latest_level(&classes).unwrap();
+        assert_eq!(*player.attributes.cha.get(), 18);
+        assert_eq!(hit_points(&player), [12, 8, 21]);
+        assert_eq!(saves(&player), [4, 5, 4]);
+        assert_eq!(player.unspent_skill_points(), Some(0));
+
+        assert!(matches!(
+            player.add_class_level(&classes, &races, Class::Sorcerer, None),
+            Err(Error::RuleViolation(_))
+        ));
+        let feats = player
+            .add_class_level(&classes, &races, Class::Sorcerer, Some(2))
+            .unwrap();
+        assert_eq!(feats, 0);
+        assert_eq!(*player.attributes.con.get(), 17);
+        assert_eq!(hit_points(&player), [16, 15, 28]);
+        assert_eq!(player.history.levels[3].ability_increase, Some(2));
+
+        let bic = write_and_read(&bic);
+        let history =
+            LevelHistory::from_field(bic.root.find_direct("LvlStatList").unwrap()).unwrap();
+        assert_eq!(history.levels.len(), 4);
+        assert_eq!(history.levels[3].ability_increase, Some(2));
+    }
+
+    #[test]
+    fn failed_level_up_changes_nothing() {
+        let bic = fixture!("player.bic");
+        let wizard = ClassRow {
+            label: "Wizard",
+            hit_die: 4,
+            skill_point_base: 2,
+            ..Default::default()
+        };
+        let mut game = GameData::new()
+            .table("classes.2da", classes_2da(&[(Class::Wizard, wizard)]))
+            .build();
+        let mut player = game.read_player(&bic);
+        let classes = ClassRecord::new(&mut game.reader).unwrap();
+        let races = RaceRecord::default();
+
+        // A new class with a history that can't take another entry
+        let history = player.history.list_ref.as_ref().unwrap();
+        history.write().unwrap().field = Field::Byte(0);
+        let before = write_gff(&bic);
+
+        let result = player.add_class_level(&classes, &races, Class::Wizard, None);
+        assert!(matches!(result, Err(Error::ParseError(_))));
+        assert_eq!(player.classes.len(), 1);
+        assert_eq!(player.history.levels.len(), 4);
+        assert_eq!(write_gff(&bic), before);
+    }
+}
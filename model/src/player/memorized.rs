@@ -0,0 +1,396 @@
+use crate::{
+    class::ClassRecord,
+    domain::DomainRecord,
+    error::Error,
+    ids::spell::Spell,
+    player::{Player, ability_modifier, spell_entry::SpellEntry},
+};
+use nwn_lib::files::gff::{field::Field, r#struct::StructField};
+
+/// `SpellMetaMagicN2` flags of the metamagic feats
+pub const METAMAGIC_NAMES: [(u32, &str); 8] = [
+    (0x01, "Empower"),
+    (0x02, "Extend"),
+    (0x04, "Maximize"),
+    (0x08, "Quicken"),
+    (0x10, "Silent"),
+    (0x20, "Still"),
+    (0x40, "Persistent"),
+    (0x80, "Permanent"),
+];
+
+/// Names of the metamagic feats set in `flags`
+pub fn metamagic_names(flags: u32) -> impl Iterator<Item = &'static str> {
+    METAMAGIC_NAMES
+        .into_iter()
+        .filter(move |(flag, _)| flags & flag != 0)
+        .map(|(_, name)| name)
+}
+
+/// What an entry of a `MemorizedList*` prepares, see [`SpellEntry`] for all of its fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemorizedSpell {
+    pub spell: Spell,
+    /// `Ready`, cleared when the spell is cast until the next rest
+    pub ready: bool,
+    /// `SpellMetaMagicN2`, or the older byte sized `SpellMetaMagic`
+    pub metamagic: u32,
+    /// `SpellDomain`, set for spells prepared in the domain slot
+    pub domain: bool,
+}
+impl MemorizedSpell {
+    pub fn new(spell: Spell) -> Self {
+        Self {
+            spell,
+            ready: true,
+            metamagic: 0,
+            domain: false,
+        }
+    }
+}
+
+/// Spells of one level a class has prepared, `MemorizedList0` to `MemorizedList9`
+#[derive(Debug, Clone)]
+pub struct MemorizedList {
+    pub list_ref: StructField,
+    pub spells: Vec<MemorizedSpell>,
+    /// Every field of the entries of `spells`
+    pub entries: Vec<SpellEntry>,
+    /// Entry new spells copy when the list is empty, taken from another list of the class
+    pub template: Option<SpellEntry>,
+}
+impl MemorizedList {
+    pub fn new(list_field: StructField) -> Result<Self, Error> {
+        let lock = list_field.read()?;
+        let entries = lock
+            .field
+            .expect_list()?
+            .iter()
+            .map(SpellEntry::from_struct)
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(lock);
+
+        Ok(Self {
+            list_ref: list_field,
+            spells: entries.iter().map(SpellEntry::memorized_spell).collect(),
+            entries,
+            template: None,
+        })
+    }
+
+    /// Entry for `spell` shaped like the list's other entries
+    fn new_entry(&self, spell: MemorizedSpell) -> SpellEntry {
+        match self.entries.first().or(self.template.as_ref()) {
+            Some(template) => {
+                let mut entry = SpellEntry::from_template(template, spell.spell);
+                entry.apply(spell);
+                entry
+            }
+            None => SpellEntry::memorized(spell),
+        }
+    }
+
+    pub fn add_spell(&mut self, spell: MemorizedSpell) -> Result<(), Error> {
+        let entry = self.new_entry(spell);
+
+        let mut field_lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut field_lock.field else {
+            return Err(Error::ParseError("MemorizedList isn't a list".into()));
+        };
+        lst.push(entry.to_struct());
+        drop(field_lock);
+
+        self.spells.push(entry.memorized_spell());
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub fn remove_spell(&mut self, index: usize) -> Result<(), Error> {
+        let mut field_lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut field_lock.field else {
+            return Err(Error::ParseError("MemorizedList isn't a list".into()));
+        };
+        if index >= lst.len() || index >= self.entries.len() {
+            return Err(Error::MissingField(format!("MemorizedList entry {index}")));
+        }
+        lst.remove(index);
+        drop(field_lock);
+
+        self.spells.remove(index);
+        self.entries.remove(index);
+        Ok(())
+    }
+
+    /// Sets `Ready` of the spell at `index`, keeping the other fields of its entry
+    pub fn set_ready(&mut self, index: usize, ready: bool) -> Result<(), Error> {
+        let entry = self
+            .entries
+            .get_mut(index)
+            .ok_or_else(|| Error::MissingField(format!("MemorizedList entry {index}")))?;
+        entry.set_ready(ready);
+
+        let mut field_lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut field_lock.field else {
+            return Err(Error::ParseError("MemorizedList isn't a list".into()));
+        };
+        if let Some(s) = lst.get_mut(index) {
+            *s = entry.to_struct();
+        }
+        drop(field_lock);
+
+        self.spells[index] = entry.memorized_spell();
+        Ok(())
+    }
+}
+
+/// Spells of one level a class has prepared next to the slots it has
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SpellSlots {
+    pub prepared: usize,
+    /// `cls_spgn_*` slots with bonus spells for a high casting ability and the domain slot
+    pub slots: u8,
+    /// Slots of `slots` only domain spells can be prepared in
+    pub domain_slots: u8,
+}
+impl SpellSlots {
+    pub fn is_full(&self) -> bool {
+        self.prepared >= self.slots.into()
+    }
+
+    pub fn over_limit(&self) -> bool {
+        self.prepared > self.slots.into()
+    }
+}
+
+/// Bonus spells of `spell_level` for a casting ability modifier of `modifier`
+pub fn bonus_spells(modifier: i8, spell_level: usize) -> u8 {
+    match (spell_level, i16::from(modifier)) {
+        (0, _) => 0,
+        (level, modifier) if modifier >= level as i16 => ((modifier - level as i16) / 4 + 1) as u8,
+        _ => 0,
+    }
+}
+
+impl Player {
+    /// Spells of `spell_level` the class at `class_index` has prepared and the slots it has.
+    /// Bonus and domain slots only count for spell levels the class can cast.
+    pub fn spell_slots(
+        &self,
+        record: &ClassRecord,
+        class_index: usize,
+        spell_level: usize,
+    ) -> Option<SpellSlots> {
+        let class = self.classes.get(class_index)?;
+        let info = record.classes.get(class.class.get())?;
+        let level = (*class.level.get()).max(0) as u16;
+        let base = *info.spells_per_day_at(level)?.get(spell_level)?;
+
+        let prepared = class
+            .memorized_list
+            .get(spell_level)
+            .and_then(|x| x.as_ref())
+            .map(|x| x.spells.len())
+            .unwrap_or(0);
+        if base == 0 {
+            return Some(SpellSlots {
+                prepared,
+                ..Default::default()
+            });
+        }
+
+        let bonus = info
+            .spell_ability
+            .and_then(|x| self.attributes.get(x))
+            .map(|x| bonus_spells(ability_modifier(*x.get()), spell_level))
+            .unwrap_or(0);
+        let domain_slots = u8::from(info.has_domains && spell_level > 0);
+
+        Some(SpellSlots {
+            prepared,
+            slots: base + bonus + domain_slots,
+            domain_slots,
+        })
+    }
+
+    /// Prepares `spell` in the class at `class_index`, adding its `MemorizedList*`
+    /// if the class has no spells of that level prepared yet. Spells prepared in
+    /// the domain slot have to be a spell of that level of one of the class's domains
+    pub fn memorize_spell(
+        &mut self,
+        class_index: usize,
+        spell_level: usize,
+        spell: MemorizedSpell,
+        domains: &DomainRecord,
+    ) -> Result<(), Error> {
+        if spell_level > 9 {
+            return Err(Error::RuleViolation(format!(
+                "There are no level {spell_level} spells"
+            )));
+        }
+        if class_index >= self.classes.len() {
+            return Err(Error::MissingField(format!("Class {class_index}")));
+        }
+
+        if spell.domain {
+            let is_domain_spell = self.classes[class_index]
+                .domains
+                .iter()
+                .flatten()
+                .filter_map(|x| domains.domains.get(x.get()))
+                .any(|x| x.spell_at(spell_level) == Some(spell.spell));
+            if !is_domain_spell {
+                return Err(Error::RuleViolation(format!(
+                    "{} isn't a level {spell_level} spell of the class's domains",
+                    spell.spell
+                )));
+            }
+        }
+
+        if self.classes[class_index].memorized_list[spell_level].is_none() {
+            let field = self.add_class_field(
+                class_index,
+                &format!("MemorizedList{spell_level}"),
+                Field::List(vec![]),
+            )?;
+
+            let class = &mut self.classes[class_index];
+            let mut list = MemorizedList::new(field)?;
+            list.template = class.memorized_template();
+            class.memorized_list[spell_level] = Some(list);
+            class.is_caster = true;
+        }
+
+        match &mut self.classes[class_index].memorized_list[spell_level] {
+            Some(list) => list.add_spell(spell),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::Domain,
+        ids::class::Class,
+        tests::{ClassRow, GameData, classes_2da, fixture, spell_levels_2da, write_and_read},
+    };
+
+    #[test]
+    fn memorized_spells() {
+        let ros = fixture!("zhjaeve.ros");
+        let cleric = ClassRow {
+            label: "Cleric",
+            hit_die: 8,
+            spells_per_day: "CLS_SPGN_CLER",
+            spell_ability: "WIS",
+            has_domains: true,
+            ..Default::default()
+        };
+        let mut game = GameData::new()
+            .table("classes.2da", classes_2da(&[(Class::Cleric, cleric)]))
+            .table(
+                "cls_spgn_cler.2da",
+                spell_levels_2da(&[(1, &[6, 5, 4, 4, 3, 2])]),
+            )
+            .build();
+        let mut player = game.read_player(&ros);
+        let classes = ClassRecord::new(&mut game.reader).unwrap();
+        // Zhjaeve's second domain has Bless as its level 3 spell
+        let mut animal = Domain::default();
+        animal.spells[2] = Some(Spell::Bless);
+        let domains = DomainRecord {
+            domains: [(0, Domain::default()), (1, animal)].into(),
+        };
+
+        let cleric = &player.classes[0];
+        assert_eq!(*cleric.class.get(), Class::Cleric);
+        assert!(cleric.is_caster);
+
+        let memorized = |player: &Player, spell_level: usize| {
+            player.classes[0].memorized_list[spell_level]
+                .as_ref()
+                .map(|x| x.spells.clone())
+        };
+        let prepared = memorized(&player, 2).unwrap();
+        assert_eq!(
+            prepared.iter().map(|x| x.spell.0).collect::<Vec<_>>(),
+            [1, 34, 83, 433, 94]
+        );
+        assert!(
+            prepared
+                .iter()
+                .all(|x| x.ready && x.metamagic == 0 && !x.domain)
+        );
+        assert_eq!(memorized(&player, 3), None);
+
+        // Wisdom 17 gives a bonus spell of levels 1 to 3, clerics get a domain slot
+        let slots = |player: &Player, spell_level| player.spell_slots(&classes, 0, spell_level);
+        assert_eq!(
+            slots(&player, 0),
+            Some(SpellSlots {
+                prepared: 0,
+                slots: 6,
+                domain_slots: 0
+            })
+        );
+        assert_eq!(
+            slots(&player, 2),
+            Some(SpellSlots {
+                prepared: 5,
+                slots: 6,
+                domain_slots: 1
+            })
+        );
+        assert_eq!(slots(&player, 4).map(|x| x.slots), Some(4));
+        assert_eq!(slots(&player, 6).map(|x| x.slots), Some(0));
+        assert_eq!(bonus_spells(3, 4), 0);
+        assert_eq!(bonus_spells(5, 1), 2);
+
+        // The domain slot only takes the domains' spells of its level
+        let extended = MemorizedSpell {
+            spell: Spell::Bless,
+            ready: true,
+            metamagic: 0x02,
+            domain: true,
+        };
+        let err = player.memorize_spell(0, 2, extended, &domains);
+        assert!(matches!(err, Err(Error::RuleViolation(_))));
+        assert_eq!(memorized(&player, 2).map(|x| x.len()), Some(5));
+        player.memorize_spell(0, 3, extended, &domains).unwrap();
+
+        let list = player.classes[0].memorized_list[2].as_mut().unwrap();
+        list.set_ready(1, false).unwrap();
+        list.remove_spell(0).unwrap();
+        assert!(matches!(list.remove_spell(4), Err(Error::MissingField(_))));
+
+        let player = game.read_player(&write_and_read(&ros));
+        assert_eq!(memorized(&player, 3), Some(vec![extended]));
+        let labels = player.classes[0].memorized_list[3]
+            .as_ref()
+            .unwrap()
+            .entries[0]
+            .fields
+            .iter()
+            .map(|x| x.label.as_str().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            ["Spell", "Ready", "SpellMetaMagicN2", "SpellDomain"]
+        );
+        assert_eq!(
+            metamagic_names(extended.metamagic).collect::<Vec<_>>(),
+            ["Extend"]
+        );
+
+        let prepared = memorized(&player, 2).unwrap();
+        assert_eq!(
+            prepared
+                .iter()
+                .map(|x| (x.spell.0, x.ready))
+                .collect::<Vec<_>>(),
+            [(34, false), (83, true), (433, true), (94, true)]
+        );
+        assert_eq!(slots(&player, 3).map(|x| x.prepared), Some(1));
+    }
+}
@@ -0,0 +1,589 @@
+pub mod class_options;
+pub mod feat_list;
+pub mod inventory;
+pub mod legality;
+pub mod level_history;
+pub mod level_up;
+pub mod memorized;
+pub mod player_class;
+pub mod prereqs;
+pub mod skills;
+pub mod spell_entry;
+pub mod stats;
+
+use crate::{
+    Tlk,
+    error::Error,
+    field_ref::FieldRef,
+    player::{
+        feat_list::FeatList,
+        inventory::{Inventory, ItemList},
+        level_history::LevelHistory,
+        skills::{SkillRanks, Skills},
+    },
+    two_d_array,
+};
+use nwn_lib::files::gff::{
+    Gff,
+    field::{Field, U32Char},
+    r#struct::{Struct, StructField},
+};
+pub use player_class::PlayerClass;
+
+macro_rules! make_builder {
+    (struct $name: ident { $($field: ident : $t: ty),+ $(,)* }) => {
+        #[derive(Debug, Default)]
+        pub struct $name {
+            $(pub $field : Option<$t>),+
+        }
+        impl $name {
+            $(
+                pub fn $field (&mut self, x: $t) {
+                    self.$field = Some(x);
+                }
+            )+
+        }
+    };
+}
+
+common::open_enum! {
+    pub enum Gender: u8 {
+        Male = 0,
+        Female = 1,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Attributes {
+    pub str: FieldRef<u8>,
+    pub dex: FieldRef<u8>,
+    pub con: FieldRef<u8>,
+    pub int: FieldRef<u8>,
+    pub wis: FieldRef<u8>,
+    pub cha: FieldRef<u8>,
+}
+/// Ability names in the order of [`Attributes::get`] and `LvlStatAbility`
+pub const ABILITY_NAMES: [&str; 6] = ["Str", "Dex", "Con", "Int", "Wis", "Cha"];
+
+impl Attributes {
+    /// Ability by index, `0..6` for Str to Cha as in `LvlStatAbility`
+    pub fn get(&self, ability: u8) -> Option<&FieldRef<u8>> {
+        [
+            &self.str, &self.dex, &self.con, &self.int, &self.wis, &self.cha,
+        ]
+        .get(ability as usize)
+        .copied()
+    }
+
+    pub fn get_mut(&mut self, ability: u8) -> Option<&mut FieldRef<u8>> {
+        let abilities = [
+            &mut self.str,
+            &mut self.dex,
+            &mut self.con,
+            &mut self.int,
+            &mut self.wis,
+            &mut self.cha,
+        ];
+        abilities.into_iter().nth(ability as usize)
+    }
+}
+
+/// Modifier of an ability score, e.g. `+2` for 14
+pub fn ability_modifier(score: u8) -> i8 {
+    (i16::from(score) - 10).div_euclid(2) as i8
+}
+
+#[derive(Debug, Clone)]
+pub struct Race {
+    pub race: String,
+    pub subrace: Option<String>,
+    /// Row in `racialtypes.2da`
+    pub id: u8,
+    /// Row in `racialsubtypes.2da`
+    pub subrace_id: Option<u8>,
+}
+impl std::fmt::Display for Race {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.subrace.as_deref() {
+            Some(subrace) => f.write_str(subrace),
+            None => f.write_str(&self.race),
+        }
+    }
+}
+
+fn get_race_name_from_id(
+    tlk: &Tlk,
+    reader: &mut two_d_array::FileReader2DA,
+    field: &Field,
+) -> Result<String, Error> {
+    let file_name = "racialtypes.2da";
+    let table = reader.read(file_name)?;
+    let race_id = field.expect_byte()?;
+    let name_idx = table
+        .find_column_index("Name")
+        .ok_or(Error::MissingField(format!(
+            "Missing 'Name' field in {file_name}"
+        )))?;
+
+    let s_ref = table.data[(name_idx, race_id as usize)]
+        .clone()
+        .ok_or(Error::MissingField(format!(
+            "Missing race name in {file_name} for {race_id}"
+        )))?;
+
+    let x = tlk
+        .get_from_str_ref(
+            s_ref
+                .parse()
+                .map_err(|e: std::num::ParseIntError| Error::MissingField(e.to_string()))?,
+        )
+        .map_err(Error::LibError)
+        .and_then(|x| x.ok_or(Error::MissingField("Missing race name str_ref".into())))?;
+
+    Ok(x.to_string())
+}
+
+fn get_subrace_name_from_id(
+    tlk: &Tlk,
+    reader: &mut two_d_array::FileReader2DA,
+    field: &Field,
+) -> Result<String, Error> {
+    let file_name = "racialsubtypes.2da";
+    let table = reader.read(file_name)?;
+    let subrace_id = field.expect_byte()?;
+
+    let name_idx = table
+        .find_column_index("Name")
+        .ok_or(Error::MissingField(format!(
+            "Missing 'Name' field in {file_name}"
+        )))?;
+
+    let s_ref = table.data[(name_idx, subrace_id as usize)]
+        .clone()
+        .ok_or(Error::MissingField(format!(
+            "Missing race name in {file_name} for {subrace_id}"
+        )))?;
+
+    let x = tlk
+        .get_from_str_ref(
+            s_ref
+                .parse()
+                .map_err(|e: std::num::ParseIntError| Error::MissingField(e.to_string()))?,
+        )
+        .map_err(Error::LibError)
+        .and_then(|x| x.ok_or(Error::MissingField("Missing race name str_ref".into())))?;
+
+    Ok(x.to_string())
+}
+
+/// Text of an `ExoLocString`, from the tlk when it only stores a str_ref like
+/// companion names and most item names do
+pub(crate) fn read_loc_string(tlk: &Tlk, field: &Field) -> Result<String, Error> {
+    let s = field.expect_exolocstring()?;
+    let text = s
+        .substrings
+        .iter()
+        .map(|sub| &sub.data)
+        .fold(String::new(), |acc, x| acc + x);
+
+    if text.is_empty()
+        && let Some(text) = tlk.get_from_str_ref(s.str_ref).ok().flatten()
+    {
+        return Ok(text.to_string());
+    }
+
+    Ok(text)
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct HitPoints {
+    /// `HitPoints`, the sum of the hit dice rolled
+    pub base: Option<FieldRef<i16>>,
+    pub current: Option<FieldRef<i16>>,
+    pub max: Option<FieldRef<i16>>,
+}
+
+/// `FortSaveThrow`, `RefSaveThrow` and `WillSaveThrow`
+#[derive(Debug, Default, Clone)]
+pub struct SavingThrows {
+    pub fort: Option<FieldRef<i8>>,
+    pub reflex: Option<FieldRef<i8>>,
+    pub will: Option<FieldRef<i8>>,
+}
+
+/// Saves are stored as a `Char`, negative values are sign extended
+fn expect_save(field: &Field) -> Result<i8, nwn_lib::error::Error> {
+    field.expect_char().map(|x| x.0 as i8)
+}
+
+pub(crate) fn save_field(save: &i8) -> Field {
+    Field::Char(U32Char(i32::from(*save) as u32))
+}
+
+/// Sum of the levels in `classes`
+pub fn total_level(classes: &[PlayerClass]) -> u16 {
+    classes
+        .iter()
+        .map(|x| i32::from(*x.level.get()))
+        .sum::<i32>()
+        .clamp(0, u16::MAX.into()) as u16
+}
+
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    pub good_evil: FieldRef<u8>,
+    pub lawful_chaotic: FieldRef<u8>,
+}
+impl std::fmt::Display for Alignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let good_evil = self.good_evil.get();
+        let lawful_chaotic = self.lawful_chaotic.get();
+
+        let good_evil = match good_evil {
+            70..=100 => Some("Good"),
+            31..=69 => Some("Neutral"),
+            0..=30 => Some("Evil"),
+            _ => None,
+        };
+
+        let lawful_chaotic = match lawful_chaotic {
+            70..=100 => Some("Lawful"),
+            31..=69 => Some("Neutral"),
+            0..=30 => Some("Chaotic"),
+            _ => None,
+        };
+
+        match (good_evil, lawful_chaotic) {
+            (Some(ge), Some(lc)) => write!(f, "{lc} {ge}"),
+            (Some(ge), None) => write!(f, "{ge}"),
+            (None, Some(lc)) => write!(f, "{lc}"),
+            (None, None) => write!(f, "Unknown"),
+        }
+    }
+}
+
+make_builder! {
+    struct PlayerBuilder {
+        first_name: FieldRef<String>,
+        last_name: FieldRef<String>,
+        gender: FieldRef<Gender>,
+        race: FieldRef<String>,
+        race_id: u8,
+        subrace: FieldRef<String>,
+        subrace_id: u8,
+        classes: Vec<player_class::PlayerClass>,
+        class_list: StructField,
+        str: FieldRef<u8>,
+        dex: FieldRef<u8>,
+        con: FieldRef<u8>,
+        int: FieldRef<u8>,
+        wis: FieldRef<u8>,
+        cha: FieldRef<u8>,
+        good_evil: FieldRef<u8>,
+        lawful_chaotic: FieldRef<u8>,
+        feats: FeatList,
+        skill_ranks: SkillRanks,
+        skill_points: FieldRef<u16>,
+        history: LevelHistory,
+        hit_points: FieldRef<i16>,
+        current_hit_points: FieldRef<i16>,
+        max_hit_points: FieldRef<i16>,
+        base_attack_bonus: FieldRef<u8>,
+        fort_save: FieldRef<i8>,
+        reflex_save: FieldRef<i8>,
+        will_save: FieldRef<i8>,
+        armor_class: FieldRef<i16>,
+        natural_armor: FieldRef<u8>,
+        roster_tag: String,
+        portrait: FieldRef<String>,
+        equipped: ItemList,
+        carried: ItemList,
+    }
+}
+
+impl PlayerBuilder {
+    fn build(self) -> Result<Player, Error> {
+        macro_rules! unwrap_field {
+            ($field: ident) => {
+                self.$field
+                    .ok_or($crate::error::Error::MissingField(format!(
+                        "Missing field {} in player builder",
+                        stringify!($field)
+                    )))?
+            };
+        }
+
+        Ok(Player {
+            first_name: unwrap_field!(first_name),
+            last_name: unwrap_field!(last_name),
+            race: Race {
+                race: unwrap_field!(race).value,
+                subrace: self.subrace.map(|x| x.value),
+                id: unwrap_field!(race_id),
+                subrace_id: self.subrace_id,
+            },
+            classes: unwrap_field!(classes),
+            class_list: self.class_list,
+            gender: unwrap_field!(gender).value,
+            attributes: Attributes {
+                str: unwrap_field!(str),
+                dex: unwrap_field!(dex),
+                con: unwrap_field!(con),
+                int: unwrap_field!(int),
+                wis: unwrap_field!(wis),
+                cha: unwrap_field!(cha),
+            },
+            alignment: Alignment {
+                good_evil: unwrap_field!(good_evil),
+                lawful_chaotic: unwrap_field!(lawful_chaotic),
+            },
+            feats: unwrap_field!(feats),
+            skills: Skills {
+                ranks: self.skill_ranks.unwrap_or_default(),
+                skill_points: self.skill_points,
+            },
+            history: self.history.unwrap_or_default(),
+            hit_points: HitPoints {
+                base: self.hit_points,
+                current: self.current_hit_points,
+                max: self.max_hit_points,
+            },
+            base_attack_bonus: self.base_attack_bonus,
+            saves: SavingThrows {
+                fort: self.fort_save,
+                reflex: self.reflex_save,
+                will: self.will_save,
+            },
+            armor_class: self.armor_class,
+            natural_armor: self.natural_armor,
+            roster_tag: self.roster_tag.filter(|x| !x.is_empty()),
+            portrait: self.portrait,
+            inventory: Inventory {
+                equipped: self.equipped.unwrap_or_default(),
+                carried: self.carried.unwrap_or_default(),
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub first_name: FieldRef<String>,
+    pub last_name: FieldRef<String>,
+    pub race: Race,
+    pub gender: Gender,
+    pub classes: Vec<PlayerClass>,
+    /// The `ClassList` field `classes` is read from
+    pub class_list: Option<StructField>,
+    pub attributes: Attributes,
+    pub alignment: Alignment,
+    pub feats: FeatList,
+    pub skills: Skills,
+    pub history: LevelHistory,
+    pub hit_points: HitPoints,
+    pub base_attack_bonus: Option<FieldRef<u8>>,
+    pub saves: SavingThrows,
+    /// `ArmorClass`, including equipment
+    pub armor_class: Option<FieldRef<i16>>,
+    /// `NaturalAC`
+    pub natural_armor: Option<FieldRef<u8>>,
+    /// Roster name for companions loaded from a `.ros` file or the player list
+    pub roster_tag: Option<String>,
+    /// `Portrait` ResRef
+    pub portrait: Option<FieldRef<String>>,
+    pub inventory: Inventory,
+}
+
+impl Player {
+    pub fn new(
+        tlk: &Tlk,
+        data_reader: &mut two_d_array::FileReader2DA,
+        player_struct: &Struct,
+    ) -> Result<Self, Error> {
+        let read_name = |field: &Field| read_loc_string(tlk, field);
+
+        let mut player_builder = PlayerBuilder::default();
+
+        for field in &player_struct.fields {
+            let lock = field.read()?;
+            let label = &lock.label;
+
+            macro_rules! read_field {
+                ($builder_fn:ident, $expect_fn:expr) => {{ player_builder.$builder_fn(FieldRef::new(field.clone(), $expect_fn)?) }};
+            }
+
+            match label.as_str() {
+                "FirstName" => read_field!(first_name, read_name),
+                "LastName" => read_field!(last_name, read_name),
+                "Race" => {
+                    player_builder.race_id(lock.field.expect_byte()?);
+                    read_field!(race, |f| get_race_name_from_id(tlk, data_reader, f))
+                }
+                "Gender" => read_field!(gender, |f| { Field::expect_byte(f).map(Gender) }),
+                "Subrace" => {
+                    player_builder.subrace_id(lock.field.expect_byte()?);
+                    read_field!(subrace, |f| get_subrace_name_from_id(tlk, data_reader, f))
+                }
+                "Str" => read_field!(str, Field::expect_byte),
+                "Dex" => read_field!(dex, Field::expect_byte),
+                "Con" => read_field!(con, Field::expect_byte),
+                "Int" => read_field!(int, Field::expect_byte),
+                "Wis" => read_field!(wis, Field::expect_byte),
+                "Cha" => read_field!(cha, Field::expect_byte),
+                "GoodEvil" => read_field!(good_evil, Field::expect_byte),
+                "LawfulChaotic" => read_field!(lawful_chaotic, Field::expect_byte),
+                "Portrait" => read_field!(portrait, |f| f.expect_resref().map(|x| x.0.clone())),
+                "RosterTag" => {
+                    let tag = lock.field.expect_exostring()?.0.clone();
+                    player_builder.roster_tag(tag);
+                }
+                "SkillPoints" => read_field!(skill_points, Field::expect_word),
+                "SkillList" => player_builder.skill_ranks(SkillRanks::from_field(&lock.field)?),
+                "LvlStatList" => player_builder.history(LevelHistory::from_field(field.clone())?),
+                "HitPoints" => read_field!(hit_points, Field::expect_short),
+                "CurrentHitPoints" => read_field!(current_hit_points, Field::expect_short),
+                "MaxHitPoints" => read_field!(max_hit_points, Field::expect_short),
+                "BaseAttackBonus" => read_field!(base_attack_bonus, Field::expect_byte),
+                "FortSaveThrow" => read_field!(fort_save, expect_save),
+                "RefSaveThrow" => read_field!(reflex_save, expect_save),
+                "WillSaveThrow" => read_field!(will_save, expect_save),
+                "ArmorClass" => read_field!(armor_class, Field::expect_short),
+                "NaturalAC" => read_field!(natural_armor, Field::expect_byte),
+                "ClassList" => {
+                    let lock = field.read()?;
+                    let list = lock.field.expect_list()?;
+
+                    let classes = list
+                        .iter()
+                        .map(PlayerClass::new)
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    player_builder.classes(classes);
+                    player_builder.class_list(field.clone());
+                }
+                "FeatList" => {
+                    let feats = FeatList::from_field(field.clone())?;
+                    player_builder.feats(feats);
+                }
+                "Equip_ItemList" => player_builder.equipped(ItemList::new(tlk, field.clone())?),
+                "ItemList" => player_builder.carried(ItemList::new(tlk, field.clone())?),
+
+                _ => {}
+            }
+        }
+
+        player_builder.build()
+    }
+
+    /// Reads every player in the `Mod_PlayerList` of a `playerlist.ifo`
+    pub fn from_player_list(
+        tlk: &Tlk,
+        data_reader: &mut two_d_array::FileReader2DA,
+        player_list: &Gff,
+    ) -> Result<Vec<Self>, Error> {
+        let player_list = player_list
+            .root
+            .bfs_iter()
+            .find(|x| x.has_label("Mod_PlayerList"))
+            .ok_or_else(|| Error::MissingField("Mod_PlayerList in playerlist.ifo".into()))?;
+
+        let lock = player_list.read()?;
+        let player_list = lock.field.expect_list()?;
+
+        player_list
+            .iter()
+            .map(|x| Self::new(tlk, data_reader, x))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ids::{class::Class, spell::Spell},
+        tests::{GameData, feat_ids, fixture, write_and_read},
+    };
+
+    #[test]
+    fn read_player_list() {
+        let mut game = GameData::new().build();
+        let player_list = fixture!("playerlist.ifo");
+        let players = Player::from_player_list(&game.tlk, &mut game.reader, &player_list).unwrap();
+
+        assert_eq!(players.len(), 1);
+        let player = &players[0];
+
+        assert_eq!(player.first_name.get(), "Merrin");
+        assert_eq!(player.last_name.get(), "Tallow");
+        assert_eq!(player.gender, Gender::Female);
+        assert_eq!(player.race.race, "Race 3");
+        assert_eq!(player.race.to_string(), "Subrace 12");
+        assert_eq!(*player.attributes.str.get(), 10);
+        assert_eq!(player.feats.list_ref.get().len(), 15);
+
+        assert_eq!(player.classes.len(), 1);
+        let class = &player.classes[0];
+        assert_eq!(*class.class.get(), Class::Sorcerer);
+        assert_eq!(*class.level.get(), 4);
+        assert!(class.is_caster);
+
+        let known = class
+            .spell_known_list
+            .iter()
+            .map(|x| x.as_ref().map(|x| x.spells.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(known[..4], [Some(6), Some(5), Some(2), None]);
+    }
+
+    #[test]
+    fn read_bic() {
+        let mut game = GameData::new().build();
+        let player_list = fixture!("playerlist.ifo");
+        let players = Player::from_player_list(&game.tlk, &mut game.reader, &player_list).unwrap();
+        let player = game.read_player(&fixture!("player.bic"));
+
+        assert_eq!(player.first_name.get(), players[0].first_name.get());
+        assert_eq!(
+            feat_ids(&player),
+            [
+                46, 173, 189, 237, 247, 249, 250, 258, 303, 375, 1114, 1699, 1728, 1764, 1765
+            ]
+        );
+    }
+
+    #[test]
+    fn edit_bic() {
+        let bic = fixture!("player.bic");
+        let mut player = GameData::new().build().read_player(&bic);
+
+        player.feats.add_feat(1);
+        player.feats.remove_feat(0);
+        player.classes[0].spell_known_list[0]
+            .as_mut()
+            .unwrap()
+            .add_spell(Spell::AcidFog)
+            .unwrap();
+
+        let bic = write_and_read(&bic);
+
+        let class_list = bic.root.find_direct("ClassList").unwrap();
+        let lock = class_list.read().unwrap();
+        let class = PlayerClass::new(&lock.field.expect_list().unwrap()[0]).unwrap();
+        let feats = FeatList::from_field(bic.root.find_direct("FeatList").unwrap()).unwrap();
+
+        let feats = feats
+            .list_ref
+            .get()
+            .iter()
+            .map(|x| *x.get())
+            .collect::<Vec<_>>();
+        assert_eq!(feats.first(), Some(&173));
+        assert_eq!(feats.last(), Some(&1));
+        assert_eq!(feats.len(), 15);
+
+        let cantrips = &class.spell_known_list[0].as_ref().unwrap().spells;
+        assert_eq!(cantrips.len(), 7);
+        assert_eq!(cantrips.last(), Some(&Spell::AcidFog));
+    }
+}
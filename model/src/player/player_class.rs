@@ -0,0 +1,389 @@
+use crate::{
+    class::ClassRecord,
+    error::Error,
+    field_ref::FieldRef,
+    ids::{class::Class, spell::Spell},
+    player::{Player, memorized::MemorizedList, spell_entry::SpellEntry},
+};
+use nwn_lib::files::gff::{
+    field::{Field, LabeledField},
+    label::Label,
+    r#struct::{Struct, StructField},
+};
+use std::fmt::Display;
+
+fn opt_field<T>(
+    x: Option<T>,
+    field_name: impl Display,
+    class_name: impl Display,
+) -> Result<T, Error> {
+    x.ok_or_else(|| Error::MissingField(format!("{} in {}", field_name, class_name)))
+}
+
+#[derive(Debug, Clone)]
+pub struct SpellKnownList {
+    pub list_ref: StructField,
+    pub spells: Vec<Spell>,
+    /// Every field of the entries of `spells`
+    pub entries: Vec<SpellEntry>,
+    /// Entry new spells copy when the list is empty, taken from another list
+    pub template: Option<SpellEntry>,
+}
+impl SpellKnownList {
+    pub fn new(list_field: StructField) -> Result<Self, Error> {
+        let lock = list_field.read()?;
+        let list = lock.field.expect_list()?;
+
+        let entries = list
+            .iter()
+            .map(SpellEntry::from_struct)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        drop(lock);
+
+        Ok(Self {
+            list_ref: list_field,
+            spells: entries.iter().map(SpellEntry::spell).collect(),
+            entries,
+            template: None,
+        })
+    }
+
+    /// Entry for `spell` shaped like the list's other entries
+    fn create_spell_entry(&self, spell: Spell) -> SpellEntry {
+        match self.entries.first().or(self.template.as_ref()) {
+            Some(template) => SpellEntry::from_template(template, spell),
+            None => SpellEntry::known(spell),
+        }
+    }
+
+    pub fn add_spell(&mut self, spell: Spell) -> Result<(), Error> {
+        let entry = self.create_spell_entry(spell);
+
+        let mut field_lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut field_lock.field else {
+            return Err(Error::ParseError("KnownList isn't a list".into()));
+        };
+        lst.push(entry.to_struct());
+        drop(field_lock);
+
+        self.spells.push(spell);
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Replaces the spell at `index`, the entry's other fields are kept
+    pub fn swap_spell(&mut self, index: usize, new_id: Spell) -> Result<(), Error> {
+        let mut field_lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut field_lock.field else {
+            return Err(Error::ParseError("KnownList isn't a list".into()));
+        };
+        let (Some(s), Some(entry)) = (lst.get_mut(index), self.entries.get_mut(index)) else {
+            return Err(Error::MissingField(format!("KnownList entry {index}")));
+        };
+        entry.set_spell(new_id);
+        *s = entry.to_struct();
+        drop(field_lock);
+
+        self.spells[index] = new_id;
+        Ok(())
+    }
+
+    pub fn remove_spell(&mut self, index: usize) -> Result<(), Error> {
+        let mut field_lock = self.list_ref.write()?;
+        let Field::List(lst) = &mut field_lock.field else {
+            return Err(Error::ParseError("KnownList isn't a list".into()));
+        };
+        if index >= lst.len() || index >= self.entries.len() {
+            return Err(Error::MissingField(format!("KnownList entry {index}")));
+        }
+        lst.remove(index);
+        drop(field_lock);
+
+        self.spells.remove(index);
+        self.entries.remove(index);
+        Ok(())
+    }
+}
+
+/// Spells a class knows of one spell level next to what its tables allow
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SpellLevelUsage {
+    pub known: usize,
+    /// From `cls_spkn_*`, `None` for classes that learn any number of spells
+    pub allowed: Option<u8>,
+    /// From `cls_spgn_*`
+    pub per_day: Option<u8>,
+}
+impl SpellLevelUsage {
+    pub fn over_limit(&self) -> bool {
+        self.allowed.is_some_and(|x| self.known > x.into())
+    }
+
+    /// Whether learning another spell would go past the limit
+    pub fn is_full(&self) -> bool {
+        self.allowed.is_some_and(|x| self.known >= x.into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayerClass {
+    pub class: FieldRef<Class>,
+    pub level: FieldRef<i16>,
+
+    pub is_caster: bool,
+    pub spell_known_list: [Option<SpellKnownList>; 10],
+    /// Prepared spells of classes that memorize them
+    pub memorized_list: [Option<MemorizedList>; 10],
+    /// `Domain1` and `Domain2`, rows of `domains.2da`
+    pub domains: [Option<FieldRef<u8>>; 2],
+    /// `School`, the row of `spellschools.2da` a wizard specializes in
+    pub school: Option<FieldRef<u8>>,
+}
+impl PlayerClass {
+    pub fn new(s: &Struct) -> Result<Self, Error> {
+        let mut class = None;
+        let mut level = None;
+        let mut is_caster = false;
+
+        let mut known_list = [const { None }; 10];
+        let mut memorized_list = [const { None }; 10];
+        let mut domains = [const { None }; 2];
+        let mut school = None;
+
+        for f in &s.fields {
+            let field_lock = f.read()?;
+            match field_lock.label.as_str() {
+                "Class" => {
+                    let field_ref = FieldRef::new(f.clone(), |f| f.expect_int().map(Class))?;
+                    class = Some(field_ref);
+                }
+
+                "ClassLevel" => {
+                    level = Some(FieldRef::new(f.clone(), Field::expect_short)?);
+                }
+
+                "Domain1" => domains[0] = Some(FieldRef::new(f.clone(), Field::expect_byte)?),
+                "Domain2" => domains[1] = Some(FieldRef::new(f.clone(), Field::expect_byte)?),
+                "School" => school = Some(FieldRef::new(f.clone(), Field::expect_byte)?),
+
+                label @ ("KnownList0" | "KnownList1" | "KnownList2" | "KnownList3"
+                | "KnownList4" | "KnownList5" | "KnownList6" | "KnownList7"
+                | "KnownList8" | "KnownList9") => {
+                    is_caster = true;
+
+                    let spell_level: usize = label[9..]
+                        .parse()
+                        .map_err(|e: std::num::ParseIntError| Error::ParseError(e.to_string()))?;
+                    drop(field_lock);
+
+                    known_list[spell_level] = Some(SpellKnownList::new(f.clone())?);
+                }
+
+                label @ ("MemorizedList0" | "MemorizedList1" | "MemorizedList2"
+                | "MemorizedList3" | "MemorizedList4" | "MemorizedList5"
+                | "MemorizedList6" | "MemorizedList7" | "MemorizedList8"
+                | "MemorizedList9") => {
+                    is_caster = true;
+
+                    let spell_level: usize = label[13..]
+                        .parse()
+                        .map_err(|e: std::num::ParseIntError| Error::ParseError(e.to_string()))?;
+                    drop(field_lock);
+
+                    memorized_list[spell_level] = Some(MemorizedList::new(f.clone())?);
+                }
+
+                // Prepared casters without anything memorized still track their casts
+                "SpellsPerDayList" => is_caster = true,
+
+                _ => {}
+            }
+        }
+
+        macro_rules! opt {
+            ($x:expr, $field_name:expr) => {
+                opt_field($x, $field_name, "PlayerClass")
+            };
+        }
+
+        let mut class = Self {
+            class: opt!(class, "Class")?,
+            level: opt!(level, "ClassLevel")?,
+            is_caster,
+            spell_known_list: known_list,
+            memorized_list,
+            domains,
+            school,
+        };
+
+        // Empty lists copy their entries from another list so added spells match the game's
+        let known_template = class.known_template();
+        let memorized_template = class.memorized_template();
+        for list in class.spell_known_list.iter_mut().flatten() {
+            list.template = known_template.clone();
+        }
+        for list in class.memorized_list.iter_mut().flatten() {
+            list.template = memorized_template.clone();
+        }
+
+        Ok(class)
+    }
+
+    /// First entry of the class's known spells
+    pub fn known_template(&self) -> Option<SpellEntry> {
+        self.spell_known_list
+            .iter()
+            .flatten()
+            .find_map(|x| x.entries.first().cloned())
+    }
+
+    /// First entry of the class's prepared spells
+    pub fn memorized_template(&self) -> Option<SpellEntry> {
+        self.memorized_list
+            .iter()
+            .flatten()
+            .find_map(|x| x.entries.first().cloned())
+    }
+
+    /// Spells known of `spell_level` and the class's limits at its current level
+    pub fn spell_usage(&self, record: &ClassRecord, spell_level: usize) -> SpellLevelUsage {
+        let info = record.classes.get(self.class.get());
+        let level = (*self.level.get()).max(0) as u16;
+        let at = |x: Option<[u8; 10]>| x.and_then(|x| x.get(spell_level).copied());
+
+        SpellLevelUsage {
+            known: self
+                .spell_known_list
+                .get(spell_level)
+                .and_then(|x| x.as_ref())
+                .map(|x| x.spells.len())
+                .unwrap_or(0),
+            allowed: at(info.and_then(|x| x.spells_known_at(level))),
+            per_day: at(info.and_then(|x| x.spells_per_day_at(level))),
+        }
+    }
+
+    /// Errors if another spell of `spell_level` would go past the spells the class can know
+    pub fn check_spell_limit(&self, record: &ClassRecord, spell_level: usize) -> Result<(), Error> {
+        let usage = self.spell_usage(record, spell_level);
+        match usage.allowed {
+            Some(allowed) if usage.is_full() => Err(Error::RuleViolation(format!(
+                "{} already knows {} of {allowed} level {spell_level} spells",
+                self.class.get(),
+                usage.known
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Player {
+    /// Adds `label` to the `ClassList` entry of the class at `class_index`
+    pub(super) fn add_class_field(
+        &self,
+        class_index: usize,
+        label: &str,
+        field: Field,
+    ) -> Result<StructField, Error> {
+        let list = self
+            .class_list
+            .as_ref()
+            .ok_or_else(|| Error::MissingField("ClassList".into()))?;
+
+        let field = StructField::new(LabeledField::new(Label::from_string(label), field));
+
+        let mut lock = list.write()?;
+        let entry = match &mut lock.field {
+            Field::List(entries) => entries.get_mut(class_index),
+            _ => None,
+        }
+        .ok_or_else(|| Error::MissingField(format!("ClassList entry {class_index}")))?;
+        entry.fields.push(field.clone());
+
+        Ok(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ClassRow, GameData, classes_2da, fixture, spell_levels_2da};
+
+    #[test]
+    fn spell_limits() {
+        let bic = fixture!("player.bic");
+        let sorcerer = ClassRow {
+            label: "Sorcerer",
+            hit_die: 4,
+            spells_known: "CLS_SPKN_SORC",
+            spells_per_day: "CLS_SPGN_SORC",
+            arcane: true,
+            spell_ability: "CHA",
+            ..Default::default()
+        };
+        let wizard = ClassRow {
+            label: "Wizard",
+            hit_die: 4,
+            arcane: true,
+            spell_ability: "INT",
+            ..Default::default()
+        };
+        // Rows for level 4, the fixture sorcerer's
+        let mut game = GameData::new()
+            .table(
+                "classes.2da",
+                classes_2da(&[(Class::Sorcerer, sorcerer), (Class::Wizard, wizard)]),
+            )
+            .table("cls_spkn_sorc.2da", spell_levels_2da(&[(3, &[6, 3, 1])]))
+            .table("cls_spgn_sorc.2da", spell_levels_2da(&[(3, &[6, 6, 3])]))
+            .build();
+        let mut player = game.read_player(&bic);
+        let classes = ClassRecord::new(&mut game.reader).unwrap();
+        let sorcerer = &player.classes[0];
+
+        let usage = |spell_level| sorcerer.spell_usage(&classes, spell_level);
+        assert_eq!(
+            usage(0),
+            SpellLevelUsage {
+                known: 6,
+                allowed: Some(6),
+                per_day: Some(6)
+            }
+        );
+        assert!(usage(0).is_full() && !usage(0).over_limit());
+        assert!(usage(1).over_limit());
+        assert_eq!((usage(2).known, usage(2).allowed), (2, Some(1)));
+        // Spell levels the tables mark `****` can't have any spells
+        assert_eq!(
+            usage(3),
+            SpellLevelUsage {
+                known: 0,
+                allowed: Some(0),
+                per_day: Some(0)
+            }
+        );
+
+        for spell_level in [0, 1, 3] {
+            assert!(matches!(
+                sorcerer.check_spell_limit(&classes, spell_level),
+                Err(Error::RuleViolation(_))
+            ));
+        }
+
+        // Wizards learn any number of spells
+        player.classes[0]
+            .class
+            .set(Class::Wizard, |x| Field::Int(x.0));
+        let wizard = &player.classes[0];
+        assert_eq!(
+            wizard.spell_usage(&classes, 1),
+            SpellLevelUsage {
+                known: 5,
+                allowed: None,
+                per_day: None
+            }
+        );
+        assert!(wizard.check_spell_limit(&classes, 1).is_ok());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{
+    class::ClassRecord,
+    feat::{FeatId, FeatPrereqs, FeatRecord},
+    ids::class::Class,
+    player::{ABILITY_NAMES, Player, total_level},
+    skill::{SkillId, SkillRecord},
+};
+
+/// A `feat.2da` requirement the character doesn't meet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prereq {
+    Feat(FeatId),
+    /// One of these feats
+    AnyFeat(Vec<FeatId>),
+    /// Ability score, `0..6` for Str to Cha
+    Ability {
+        ability: u8,
+        min: u8,
+    },
+    AttackBonus(u8),
+    /// Character level, or the level of `class` if it's set
+    Level {
+        level: u16,
+        class: Option<Class>,
+    },
+    Skill {
+        skill: SkillId,
+        ranks: u8,
+    },
+    /// None of the character's classes can take the feat
+    Class,
+}
+impl Prereq {
+    /// Short description, like "Str 13" or "Spellcraft 4 ranks"
+    pub fn describe(&self, feats: &FeatRecord, skills: &SkillRecord) -> String {
+        match self {
+            Self::Feat(id) => feats.name(*id),
+            Self::AnyFeat(ids) => {
+                let names = ids.iter().map(|x| feats.name(*x)).collect::<Vec<_>>();
+                format!("one of {}", names.join(", "))
+            }
+            Self::Ability { ability, min } => {
+                let name = ABILITY_NAMES.get(*ability as usize).copied().unwrap_or("?");
+                format!("{name} {min}")
+            }
+            Self::AttackBonus(bonus) => format!("base attack bonus +{bonus}"),
+            Self::Level { level, class: None } => format!("character level {level}"),
+            Self::Level {
+                level,
+                class: Some(class),
+            } => format!("{class} level {level}"),
+            Self::Skill { skill, ranks } => format!("{} {ranks} ranks", skills.name(*skill)),
+            Self::Class => "a class that can take it".to_string(),
+        }
+    }
+}
+
+impl Player {
+    /// Whether one of the character's classes has `feat` on its feat table, feats
+    /// no class lists (racial and background feats) aren't restricted
+    fn class_can_take(&self, classes: &ClassRecord, feat: u16) -> bool {
+        let listed_by = |class: &Class| {
+            classes
+                .classes
+                .get(class)
+                .is_some_and(|x| x.class_feats.contains(&feat))
+        };
+
+        !classes.classes.keys().any(listed_by)
+            || self.classes.iter().any(|x| listed_by(x.class.get()))
+    }
+
+    /// Requirements of `feat` the character doesn't meet, empty if it can take the feat
+    pub fn unmet_prereqs(
+        &self,
+        classes: &ClassRecord,
+        feat: FeatId,
+        prereqs: &FeatPrereqs,
+    ) -> Vec<Prereq> {
+        let has_feat = |feat: FeatId| u16::try_from(feat).is_ok_and(|x| self.has_feat(x));
+        let mut unmet = vec![];
+
+        unmet.extend(
+            prereqs
+                .feats
+                .iter()
+                .filter(|x| !has_feat(**x))
+                .map(|x| Prereq::Feat(*x)),
+        );
+
+        if !prereqs.any_feats.is_empty() && !prereqs.any_feats.iter().any(|x| has_feat(*x)) {
+            unmet.push(Prereq::AnyFeat(prereqs.any_feats.clone()));
+        }
+
+        for (ability, min) in (0..).zip(prereqs.min_abilities) {
+            let score = self.attributes.get(ability).map(|x| *x.get()).unwrap_or(0);
+            if score < min {
+                unmet.push(Prereq::Ability { ability, min });
+            }
+        }
+
+        if self.base_attack_bonus(classes) < prereqs.min_attack_bonus {
+            unmet.push(Prereq::AttackBonus(prereqs.min_attack_bonus));
+        }
+
+        if let Some((level, class)) = prereqs.min_level {
+            let current = match class {
+                Some(class) => self.class_level(class),
+                None => total_level(&self.classes),
+            };
+            if current < level {
+                unmet.push(Prereq::Level { level, class });
+            }
+        }
+
+        unmet.extend(
+            prereqs
+                .skills
+                .iter()
+                .filter(|(skill, ranks)| self.skills.ranks.get(*skill) < *ranks)
+                .map(|(skill, ranks)| Prereq::Skill {
+                    skill: *skill,
+                    ranks: *ranks,
+                }),
+        );
+
+        if !prereqs.all_classes_can_use
+            && u16::try_from(feat).is_ok_and(|x| !self.class_can_take(classes, x))
+        {
+            unmet.push(Prereq::Class);
+        }
+
+        unmet
+    }
+
+    /// Feats the character has without meeting their requirements, with what's
+    /// missing. Feats its classes grant automatically are left out
+    pub fn illegal_feats(
+        &self,
+        feats: &FeatRecord,
+        classes: &ClassRecord,
+    ) -> Vec<(FeatId, Vec<Prereq>)> {
+        let granted = |feat: u16| {
+            self.classes.iter().any(|x| {
+                let level = (*x.level.get()).max(0) as u16;
+                classes.classes.get(x.class.get()).is_some_and(|info| {
+                    info.granted_feats
+                        .iter()
+                        .any(|(f, l)| *f == feat && (1..=level).contains(l))
+                })
+            })
+        };
+
+        self.feats
+            .list_ref
+            .get()
+            .iter()
+            .map(|x| *x.get())
+            .filter(|x| !granted(*x))
+            .filter_map(|id| {
+                let feat = feats.feats.get(&id.into())?;
+                let unmet = self.unmet_prereqs(classes, id.into(), &feat.prereqs);
+                (!unmet.is_empty()).then_some((id.into(), unmet))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        ClassRow, GameData, class_feats_2da, classes_2da, fixture, make_feat, sorcerer, wizard,
+    };
+
+    #[test]
+    fn feat_prereqs() {
+        let bic = fixture!("player.bic");
+        let sorcerer = ClassRow {
+            feats: "CLS_FEAT_SORC",
+            ..sorcerer()
+        };
+        let wizard = ClassRow {
+            feats: "CLS_FEAT_WIZ",
+            ..wizard()
+        };
+        let mut game = GameData::new()
+            .arcane_progression()
+            .table(
+                "classes.2da",
+                classes_2da(&[(Class::Sorcerer, sorcerer), (Class::Wizard, wizard)]),
+            )
+            .table(
+                "cls_feat_sorc.2da",
+                class_feats_2da(&[(46, 3, 1), (501, 0, -1)]),
+            )
+            .table("cls_feat_wiz.2da", class_feats_2da(&[(502, 3, 1)]))
+            .build();
+        let mut player = game.read_player(&bic);
+        let classes = ClassRecord::new(&mut game.reader).unwrap();
+
+        // The player is a level 4 sorcerer with attack bonus 2, Str under 13, Dex 14,
+        // 7 ranks of skill 1, 3 of skill 12 and feats 46 and 173
+        let prereqs = FeatPrereqs {
+            feats: vec![46, 9999],
+            any_feats: vec![1, 2],
+            min_abilities: [13, 14, 0, 0, 0, 0],
+            min_attack_bonus: 3,
+            min_level: Some((5, None)),
+            skills: vec![(1, 7), (12, 4)],
+            all_classes_can_use: true,
+        };
+        assert_eq!(
+            player.unmet_prereqs(&classes, 600, &prereqs),
+            [
+                Prereq::Feat(9999),
+                Prereq::AnyFeat(vec![1, 2]),
+                Prereq::Ability {
+                    ability: 0,
+                    min: 13
+                },
+                Prereq::AttackBonus(3),
+                Prereq::Level {
+                    level: 5,
+                    class: None
+                },
+                Prereq::Skill {
+                    skill: 12,
+                    ranks: 4
+                },
+            ]
+        );
+
+        let sorcerer_level = FeatPrereqs {
+            min_level: Some((4, Some(Class::Sorcerer))),
+            any_feats: vec![2, 173],
+            ..Default::default()
+        };
+        assert_eq!(player.unmet_prereqs(&classes, 600, &sorcerer_level), []);
+
+        // 501 is on the sorcerer's feat table, 502 only on the wizard's and 700 on none
+        let class_only = FeatPrereqs::default();
+        assert_eq!(player.unmet_prereqs(&classes, 501, &class_only), []);
+        assert_eq!(
+            player.unmet_prereqs(&classes, 502, &class_only),
+            [Prereq::Class]
+        );
+        assert_eq!(player.unmet_prereqs(&classes, 700, &class_only), []);
+
+        // 46 is granted at sorcerer level 1 so its requirements aren't checked
+        let strength = FeatPrereqs {
+            min_abilities: [13, 0, 0, 0, 0, 0],
+            all_classes_can_use: true,
+            ..Default::default()
+        };
+        let feats = FeatRecord {
+            feats: [
+                make_feat(46, "FEAT_46", strength.clone()),
+                make_feat(173, "FEAT_173", strength),
+                make_feat(189, "FEAT_189", FeatPrereqs::default()),
+                make_feat(502, "FEAT_502", class_only),
+            ]
+            .into(),
+        };
+        assert_eq!(
+            player.illegal_feats(&feats, &classes),
+            [(
+                173,
+                vec![Prereq::Ability {
+                    ability: 0,
+                    min: 13
+                }]
+            )]
+        );
+
+        player.feats.add_feat(502);
+        assert_eq!(
+            player.illegal_feats(&feats, &classes),
+            [
+                (
+                    173,
+                    vec![Prereq::Ability {
+                        ability: 0,
+                        min: 13
+                    }]
+                ),
+                (502, vec![Prereq::Class])
+            ]
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{
+    error::Error,
+    field_ref::FieldRef,
+    player::{Player, total_level},
+    skill::{SkillId, SkillRecord},
+};
+use nwn_lib::files::gff::{field::Field, r#struct::Struct};
+
+/// Ranks of a `SkillList`, indexed by skill id
+#[derive(Debug, Default, Clone)]
+pub struct SkillRanks {
+    pub ranks: Vec<FieldRef<u8>>,
+}
+impl SkillRanks {
+    pub fn from_field(list: &Field) -> Result<Self, Error> {
+        let ranks = list
+            .expect_list()?
+            .iter()
+            .map(|s| {
+                let rank = s
+                    .find_direct("Rank")
+                    .ok_or_else(|| Error::MissingField("Rank in SkillList entry".into()))?;
+                FieldRef::new(rank, Field::expect_byte)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { ranks })
+    }
+
+    pub fn get(&self, skill: SkillId) -> u8 {
+        self.ranks.get(skill).map(|x| *x.get()).unwrap_or(0)
+    }
+
+    fn has_skill(&self, skill: SkillId) -> bool {
+        skill < self.ranks.len()
+    }
+
+    pub(super) fn set(&mut self, skill: SkillId, rank: u8) {
+        if let Some(x) = self.ranks.get_mut(skill) {
+            x.set(rank, |x| Field::Byte(*x));
+        }
+    }
+}
+
+/// Skills bought with one level, from a `LvlStatList` entry
+#[derive(Debug, Default, Clone)]
+pub struct LevelSkills {
+    /// Points left unspent after this level
+    pub skill_points: Option<FieldRef<u16>>,
+    /// Ranks gained at this level
+    pub ranks: SkillRanks,
+}
+impl LevelSkills {
+    pub fn new(s: &Struct) -> Result<Self, Error> {
+        let skill_points = s
+            .find_direct("SkillPoints")
+            .map(|x| FieldRef::new(x, Field::expect_word))
+            .transpose()?;
+
+        let ranks = match s.find_direct("SkillList") {
+            Some(list) => list.read_field(SkillRanks::from_field)?,
+            None => SkillRanks::default(),
+        };
+
+        Ok(Self {
+            skill_points,
+            ranks,
+        })
+    }
+}
+
+fn add_points(points: &mut Option<FieldRef<u16>>, delta: i32) {
+    if let Some(points) = points {
+        let value = (i32::from(*points.get()) + delta).clamp(0, u16::MAX.into()) as u16;
+        points.set(value, |x| Field::Word(*x));
+    }
+}
+
+/// A creature's `SkillList` and `SkillPoints`, the ranks bought at each level are in
+/// its [`LevelHistory`](super::level_history::LevelHistory)
+#[derive(Debug, Default, Clone)]
+pub struct Skills {
+    pub ranks: SkillRanks,
+    pub skill_points: Option<FieldRef<u16>>,
+}
+
+impl Player {
+    /// Ranks in `skill` gained at each level of the history
+    pub fn skill_ranks_by_level(&self, skill: SkillId) -> impl Iterator<Item = u8> + '_ {
+        self.history
+            .levels
+            .iter()
+            .map(move |x| x.skills.ranks.get(skill))
+    }
+
+    /// Unspent skill points, `None` if the creature doesn't track them
+    pub fn unspent_skill_points(&self) -> Option<u16> {
+        self.skills
+            .skill_points
+            .as_ref()
+            .or_else(|| self.history.levels.last()?.skills.skill_points.as_ref())
+            .map(|x| *x.get())
+    }
+
+    /// Sets the unspent points of the creature and its most recent level
+    pub fn set_unspent_skill_points(&mut self, points: u16) {
+        let level_points = self
+            .history
+            .levels
+            .last_mut()
+            .and_then(|x| x.skills.skill_points.as_mut());
+
+        for x in self.skills.skill_points.iter_mut().chain(level_points) {
+            x.set(points, |x| Field::Word(*x));
+        }
+    }
+
+    fn add_unspent_skill_points(&mut self, delta: i32) {
+        add_points(&mut self.skills.skill_points, delta);
+
+        if let Some(level) = self.history.levels.last_mut() {
+            add_points(&mut level.skills.skill_points, delta);
+        }
+    }
+
+    /// Sets the rank in `skill` up to the maximum for the character's classes.
+    ///
+    /// New ranks are bought with unspent points at the most recent level,
+    /// removed ranks are taken from the latest levels first and refunded
+    pub fn set_skill_rank(
+        &mut self,
+        record: &SkillRecord,
+        skill: SkillId,
+        rank: u8,
+    ) -> Result<(), Error> {
+        let class_ids = self
+            .classes
+            .iter()
+            .map(|x| *x.class.get())
+            .collect::<Vec<_>>();
+
+        let max = record.max_ranks(&class_ids, total_level(&self.classes), skill);
+        if rank > max {
+            return Err(Error::RuleViolation(format!(
+                "{rank} ranks is over the maximum of {max}"
+            )));
+        }
+
+        if !self.skills.ranks.has_skill(skill) {
+            return Err(Error::MissingField(format!("Skill {skill} in SkillList")));
+        }
+
+        let latest_class = self
+            .history
+            .levels
+            .last()
+            .map(|x| x.class)
+            .or_else(|| class_ids.last().copied())
+            .unwrap_or_default();
+
+        let current = self.skills.ranks.get(skill);
+
+        if rank > current {
+            let added = rank - current;
+            let cost = record.rank_cost(latest_class, skill) * u16::from(added);
+
+            if let Some(unspent) = self.unspent_skill_points()
+                && unspent < cost
+            {
+                return Err(Error::RuleViolation(format!(
+                    "{added} more ranks cost {cost} skill points but only {unspent} are unspent"
+                )));
+            }
+
+            if let Some(level) = self.history.levels.last_mut() {
+                let ranks = &mut level.skills.ranks;
+                if !ranks.has_skill(skill) {
+                    return Err(Error::MissingField(format!(
+                        "Skill {skill} in the latest LvlStatList entry"
+                    )));
+                }
+
+                let level_ranks = ranks.get(skill).checked_add(added).ok_or_else(|| {
+                    Error::RuleViolation(format!(
+                        "Skill {skill} can't have {added} more ranks in the latest level"
+                    ))
+                })?;
+                ranks.set(skill, level_ranks);
+            }
+
+            self.add_unspent_skill_points(-i32::from(cost));
+        } else {
+            let mut removed = current - rank;
+            let mut refund = 0;
+
+            for level in self.history.levels.iter_mut().rev() {
+                let ranks = &mut level.skills.ranks;
+                let gained = ranks.get(skill);
+                let n = gained.min(removed);
+
+                if n > 0 {
+                    ranks.set(skill, gained - n);
+                    refund += record.rank_cost(level.class, skill) * u16::from(n);
+                    removed -= n;
+                }
+            }
+
+            // Ranks missing from the history are refunded at the latest class's cost
+            refund += record.rank_cost(latest_class, skill) * u16::from(removed);
+
+            self.add_unspent_skill_points(refund.into());
+        }
+
+        self.skills.ranks.set(skill, rank);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ids::class::Class,
+        player::level_history::LevelHistory,
+        tests::{GameData, fixture, make_skill_record, write_and_read},
+    };
+
+    #[test]
+    fn edit_skills() {
+        let bic = fixture!("player.bic");
+        let mut player = GameData::new().build().read_player(&bic);
+        // 0 and 12 are cross-class for sorcerers and 5 is unavailable
+        let record = make_skill_record(
+            &[(0, true), (1, true), (5, false), (7, true), (12, true)],
+            &[(Class::Sorcerer, &[1, 7])],
+        );
+
+        let by_level =
+            |player: &Player, skill| player.skill_ranks_by_level(skill).collect::<Vec<_>>();
+
+        assert_eq!(player.skills.ranks.get(1), 7);
+        assert_eq!(by_level(&player, 1), [4, 1, 1, 1]);
+        assert_eq!(player.unspent_skill_points(), Some(2));
+
+        // Cross-class, unavailable and class skill maximums at level 4
+        for (skill, rank) in [(12, 4), (5, 1), (7, 8)] {
+            assert!(matches!(
+                player.set_skill_rank(&record, skill, rank),
+                Err(Error::RuleViolation(_))
+            ));
+        }
+
+        // Ranks the latest level can't hold leave the points unspent
+        let gained = player.history.levels[3].skills.ranks.get(0);
+        player.history.levels[3].skills.ranks.set(0, u8::MAX);
+        assert!(matches!(
+            player.set_skill_rank(&record, 0, 1),
+            Err(Error::RuleViolation(_))
+        ));
+        assert_eq!(player.skills.ranks.get(0), 0);
+        assert_eq!(player.unspent_skill_points(), Some(2));
+        player.history.levels[3].skills.ranks.set(0, gained);
+
+        player.set_skill_rank(&record, 1, 5).unwrap();
+        assert_eq!(by_level(&player, 1), [4, 1, 0, 0]);
+        assert_eq!(player.unspent_skill_points(), Some(4));
+
+        player.set_skill_rank(&record, 0, 2).unwrap();
+        assert_eq!(by_level(&player, 0), [0, 0, 0, 2]);
+        assert_eq!(player.unspent_skill_points(), Some(0));
+
+        assert!(matches!(
+            player.set_skill_rank(&record, 1, 6),
+            Err(Error::RuleViolation(_))
+        ));
+
+        let bic = write_and_read(&bic);
+
+        let ranks = bic
+            .root
+            .find_direct("SkillList")
+            .unwrap()
+            .read_field(SkillRanks::from_field)
+            .unwrap();
+        let history =
+            LevelHistory::from_field(bic.root.find_direct("LvlStatList").unwrap()).unwrap();
+        let points = bic
+            .root
+            .find_direct("SkillPoints")
+            .unwrap()
+            .read_field(Field::expect_word)
+            .unwrap();
+
+        let last_level = &history.levels[3].skills;
+        assert_eq!((ranks.get(0), ranks.get(1)), (2, 5));
+        assert_eq!(last_level.ranks.get(0), 2);
+        assert_eq!(*last_level.skill_points.as_ref().unwrap().get(), 0);
+        assert_eq!(points, 0);
+    }
+}
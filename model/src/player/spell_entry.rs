@@ -0,0 +1,319 @@
+use crate::{error::Error, ids::spell::Spell, player::memorized::MemorizedSpell};
+use nwn_lib::files::gff::{
+    field::{Field, LabeledField, U32Char},
+    label::Label,
+    r#struct::{Struct, StructField},
+};
+
+/// Struct id of the game's spell list entries
+const SPELL_STRUCT_ID: u32 = 3;
+
+/// Integer value of `field`, `None` for fields that aren't integers
+pub(super) fn as_number(field: &Field) -> Option<u32> {
+    match field {
+        Field::Byte(x) => Some((*x).into()),
+        Field::Char(x) => Some(x.0),
+        Field::Word(x) => Some((*x).into()),
+        Field::Short(x) => Some(*x as u32),
+        Field::DWord(x) => Some(*x),
+        Field::Int(x) => Some(*x as u32),
+        _ => None,
+    }
+}
+
+/// `value` stored as the same type as `field`
+pub(super) fn with_number(field: &Field, value: u32) -> Option<Field> {
+    Some(match field {
+        Field::Byte(_) => Field::Byte(value as u8),
+        Field::Char(_) => Field::Char(U32Char(value)),
+        Field::Word(_) => Field::Word(value as u16),
+        Field::Short(_) => Field::Short(value as i16),
+        Field::DWord(_) => Field::DWord(value),
+        Field::Int(_) => Field::Int(value as i32),
+        _ => return None,
+    })
+}
+
+/// An entry of a `KnownList*` or `MemorizedList*` with every field the game stored,
+/// like `SpellFlags`, `SpellMetaMagic` or caster level data the editor doesn't use
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellEntry {
+    pub struct_id: u32,
+    pub fields: Vec<LabeledField>,
+}
+impl SpellEntry {
+    pub fn from_struct(s: &Struct) -> Result<Self, Error> {
+        let fields = s
+            .fields
+            .iter()
+            .map(|f| {
+                let lock = f.read()?;
+                Ok(LabeledField::new(
+                    lock.label.clone(),
+                    lock.field.deep_clone(),
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let entry = Self {
+            struct_id: s.id,
+            fields,
+        };
+        match entry.get("Spell") {
+            Some(Field::Word(_)) => Ok(entry),
+            Some(x) => Err(Error::ParseError(format!("Spell entry with a {x:?} spell"))),
+            None => Err(Error::MissingField("Spell in spell list entry".into())),
+        }
+    }
+
+    /// Entry of a `KnownList*` when there's no other entry to copy
+    pub fn known(Spell(spell): Spell) -> Self {
+        Self {
+            struct_id: SPELL_STRUCT_ID,
+            fields: vec![LabeledField::new(
+                Label::from_string("Spell"),
+                Field::Word(spell),
+            )],
+        }
+    }
+
+    /// Entry of a `MemorizedList*` when there's no other entry to copy
+    pub fn memorized(spell: MemorizedSpell) -> Self {
+        let mut entry = Self::known(spell.spell);
+        entry.fields.extend([
+            LabeledField::new(Label::from_string("Ready"), Field::Byte(1)),
+            LabeledField::new(Label::from_string("SpellMetaMagicN2"), Field::DWord(0)),
+        ]);
+        entry.apply(spell);
+        entry
+    }
+
+    /// Entry for `spell` with the struct id, fields and field types of `template`.
+    /// What belongs to the prepared spell, metamagic, the domain slot and `Ready`,
+    /// is reset while flags and caster level data are kept like the template's.
+    pub fn from_template(template: &Self, spell: Spell) -> Self {
+        let mut entry = template.clone();
+        entry.set_spell(spell);
+        for label in ["SpellMetaMagic", "SpellMetaMagicN2", "SpellDomain"] {
+            entry.set_number(label, 0);
+        }
+        entry.set_number("Ready", 1);
+        entry
+    }
+
+    pub fn to_struct(&self) -> Struct {
+        Struct {
+            id: self.struct_id,
+            original_data_or_data_offset: u32::MAX,
+            fields: self
+                .fields
+                .iter()
+                .map(|x| StructField::new(x.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, label: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|x| x.label.as_str() == label)
+            .map(|x| &x.field)
+    }
+
+    fn number(&self, label: &str) -> Option<u32> {
+        self.get(label).and_then(as_number)
+    }
+
+    /// Writes `value` to `label` keeping the field's type, returns whether the entry has it
+    pub fn set_number(&mut self, label: &str, value: u32) -> bool {
+        let field = self.fields.iter_mut().find(|x| x.label.as_str() == label);
+        match field.and_then(|x| Some((with_number(&x.field, value)?, x))) {
+            Some((new, x)) => {
+                x.field = new;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets `label`, adding it as `default`'s type if the entry doesn't have it
+    fn set_or_add(&mut self, label: &str, value: u32, default: Field) {
+        if !self.set_number(label, value) {
+            let field = with_number(&default, value).unwrap_or(default);
+            self.fields
+                .push(LabeledField::new(Label::from_string(label), field));
+        }
+    }
+
+    pub fn spell(&self) -> Spell {
+        Spell(self.number("Spell").unwrap_or_default() as u16)
+    }
+
+    pub fn set_spell(&mut self, spell: Spell) {
+        self.set_number("Spell", spell.0.into());
+    }
+
+    /// `SpellFlags`
+    pub fn flags(&self) -> Option<u8> {
+        self.number("SpellFlags").map(|x| x as u8)
+    }
+
+    /// `SpellMetaMagicN2`, or the older byte sized `SpellMetaMagic`
+    pub fn metamagic(&self) -> u32 {
+        self.number("SpellMetaMagicN2")
+            .or_else(|| self.number("SpellMetaMagic"))
+            .unwrap_or(0)
+    }
+
+    /// Writes the metamagic to the fields the entry has, `SpellMetaMagicN2` if it has neither
+    pub fn set_metamagic(&mut self, metamagic: u32) {
+        let n2 = self.set_number("SpellMetaMagicN2", metamagic);
+        let byte = self.set_number("SpellMetaMagic", metamagic);
+        if !n2 && !byte && metamagic != 0 {
+            self.set_or_add("SpellMetaMagicN2", metamagic, Field::DWord(0));
+        }
+    }
+
+    /// `Ready`, cleared when the spell is cast until the next rest
+    pub fn ready(&self) -> bool {
+        self.number("Ready").is_some_and(|x| x != 0)
+    }
+
+    pub fn set_ready(&mut self, ready: bool) {
+        self.set_or_add("Ready", ready.into(), Field::Byte(0));
+    }
+
+    /// `SpellDomain`, set for spells prepared in the domain slot
+    pub fn domain(&self) -> bool {
+        self.number("SpellDomain").is_some_and(|x| x != 0)
+    }
+
+    pub fn set_domain(&mut self, domain: bool) {
+        if !self.set_number("SpellDomain", domain.into()) && domain {
+            self.set_or_add("SpellDomain", 1, Field::Byte(0));
+        }
+    }
+
+    /// What the entry holds as a prepared spell
+    pub fn memorized_spell(&self) -> MemorizedSpell {
+        MemorizedSpell {
+            spell: self.spell(),
+            ready: self.ready(),
+            metamagic: self.metamagic(),
+            domain: self.domain(),
+        }
+    }
+
+    /// Sets the prepared spell's fields, leaving the others as they are
+    pub fn apply(&mut self, spell: MemorizedSpell) {
+        self.set_spell(spell.spell);
+        self.set_ready(spell.ready);
+        self.set_metamagic(spell.metamagic);
+        self.set_domain(spell.domain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        player::player_class::{PlayerClass, SpellKnownList},
+        tests::{fixture, write_and_read},
+    };
+
+    #[test]
+    fn spell_entries() {
+        let bic = fixture!("player.bic");
+        let class_list = bic.root.find_direct("ClassList").unwrap();
+        let lock = class_list.read().unwrap();
+        let class_struct = &lock.field.expect_list().unwrap()[0];
+
+        // Fields some versions of the game write next to `Spell`
+        for f in &class_struct.fields {
+            let mut known = f.write().unwrap();
+            if !known.label.as_str().starts_with("KnownList") {
+                continue;
+            }
+            let Field::List(entries) = &mut known.field else {
+                panic!("Known list isn't a list");
+            };
+            for entry in entries {
+                let fields = [
+                    ("SpellFlags", Field::Byte(1)),
+                    ("SpellMetaMagic", Field::Byte(2)),
+                    ("SpellCasterLevel", Field::Byte(4)),
+                ];
+                entry.fields.extend(fields.map(|(label, field)| {
+                    StructField::new(LabeledField::new(Label::from_string(label), field))
+                }));
+            }
+        }
+
+        let mut class = PlayerClass::new(class_struct).unwrap();
+        drop(lock);
+
+        let labels = |entry: &SpellEntry| {
+            entry
+                .fields
+                .iter()
+                .map(|x| x.label.as_str().to_string())
+                .collect::<Vec<_>>()
+        };
+        let known = class.spell_known_list[1].as_mut().unwrap();
+        let first = known.entries[0].clone();
+        assert_eq!(
+            labels(&first),
+            ["Spell", "SpellFlags", "SpellMetaMagic", "SpellCasterLevel"]
+        );
+        assert_eq!((first.flags(), first.metamagic()), (Some(1), 2));
+
+        // Added spells copy the other entries without their metamagic
+        known.add_spell(Spell::AcidFog).unwrap();
+        let added = known.entries.last().unwrap().clone();
+        assert_eq!(labels(&added), labels(&first));
+        assert_eq!(added.struct_id, first.struct_id);
+        assert_eq!(added.spell(), Spell::AcidFog);
+        assert_eq!((added.flags(), added.metamagic()), (Some(1), 0));
+        assert_eq!(added.get("SpellCasterLevel"), Some(&Field::Byte(4)));
+
+        known.swap_spell(0, Spell::Aid).unwrap();
+        assert_eq!(known.entries[0].spell(), Spell::Aid);
+        assert_eq!(known.entries[0].metamagic(), 2);
+
+        // Indices past the list leave it as it is
+        let count = known.spells.len();
+        assert!(known.swap_spell(count, Spell::Bless).is_err());
+        assert!(known.remove_spell(count).is_err());
+        assert_eq!(known.entries.len(), count);
+
+        // Empty lists use the class's other entries, or the game's minimal entry without any
+        let empty_list = || {
+            StructField::new(LabeledField::new(
+                Label::from_string("KnownList3"),
+                Field::List(vec![]),
+            ))
+        };
+        let mut empty = SpellKnownList::new(empty_list()).unwrap();
+        empty.template = class.known_template();
+        empty.add_spell(Spell::Aid).unwrap();
+        assert_eq!(labels(&empty.entries[0]), labels(&first));
+
+        let mut empty = SpellKnownList::new(empty_list()).unwrap();
+        empty.add_spell(Spell::Aid).unwrap();
+        assert_eq!(empty.entries, [SpellEntry::known(Spell::Aid)]);
+        assert_eq!(empty.entries[0].struct_id, 3);
+
+        let bic = write_and_read(&bic);
+        let class_list = bic.root.find_direct("ClassList").unwrap();
+        let lock = class_list.read().unwrap();
+        let reread = PlayerClass::new(&lock.field.expect_list().unwrap()[0]).unwrap();
+        assert_eq!(
+            reread.spell_known_list[1].as_ref().unwrap().entries,
+            class.spell_known_list[1].as_ref().unwrap().entries
+        );
+        assert_eq!(
+            reread.spell_known_list[1].as_ref().unwrap().spells,
+            class.spell_known_list[1].as_ref().unwrap().spells
+        );
+    }
+}
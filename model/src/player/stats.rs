@@ -0,0 +1,254 @@
+use crate::{
+    class::{ClassRecord, Saves},
+    feat::FeatRecord,
+    player::{ABILITY_NAMES, Player, ability_modifier, total_level},
+    skill::{SkillId, SkillRecord},
+};
+use std::collections::HashMap;
+
+/// `feat.2da` labels of the feats the stats count, ids differ between the games' tables
+const GREAT_FORTITUDE: &str = "FEAT_GREAT_FORTITUDE";
+const IRON_WILL: &str = "FEAT_IRON_WILL";
+const LIGHTNING_REFLEXES: &str = "FEAT_LIGHTNING_REFLEXES";
+const TOUGHNESS: &str = "FEAT_TOUGHNESS";
+/// Skill Focus labels end in the skill's label, like `FEAT_SKILL_FOCUS_MOVE_SILENTLY`
+const SKILL_FOCUS_PREFIX: &str = "FEAT_SKILL_FOCUS_";
+const SKILL_FOCUS_BONUS: i16 = 3;
+
+/// Label without case or underscores, `MOVE_SILENTLY` and `MoveSilently` are the same
+fn normalize_label(label: &str) -> String {
+    label
+        .chars()
+        .filter(|x| *x != '_')
+        .map(|x| x.to_ascii_lowercase())
+        .collect()
+}
+
+/// Statistics worked out from a character's classes, abilities and feats.
+///
+/// Feats count by their `feat.2da` label: Great Fortitude, Iron Will,
+/// Lightning Reflexes, Toughness and the Skill Focus feats whose label names
+/// a skill. Other feats, racial save and skill bonuses, equipment and
+/// temporary effects aren't counted
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DerivedStats {
+    /// Modifiers of Str to Cha
+    pub ability_modifiers: [i8; 6],
+    pub base_attack_bonus: u8,
+    /// Class saves with ability modifiers and feats
+    pub saves: Saves,
+    pub max_hit_points: i16,
+    /// 10 with the dexterity modifier and natural armor
+    pub armor_class: i16,
+    /// Ranks with the key ability modifier and feats
+    pub skill_totals: HashMap<SkillId, i16>,
+}
+
+impl Player {
+    fn ability_modifiers(&self) -> [i8; 6] {
+        std::array::from_fn(|i| {
+            let score = self.attributes.get(i as u8).map(|x| *x.get()).unwrap_or(10);
+            ability_modifier(score)
+        })
+    }
+
+    /// Stored `FortSaveThrow`, `RefSaveThrow` and `WillSaveThrow`, `None` if one is missing
+    pub fn stored_saves(&self) -> Option<Saves> {
+        Some(Saves {
+            fort: *self.saves.fort.as_ref()?.get(),
+            reflex: *self.saves.reflex.as_ref()?.get(),
+            will: *self.saves.will.as_ref()?.get(),
+        })
+    }
+
+    /// Sum of the class base attack bonuses at the character's class levels
+    pub fn base_attack_bonus(&self, classes: &ClassRecord) -> u8 {
+        self.classes
+            .iter()
+            .filter_map(|x| {
+                let info = classes.classes.get(x.class.get())?;
+                Some(info.attack_bonus_at((*x.level.get()).max(0) as u16))
+            })
+            .fold(0, u8::saturating_add)
+    }
+
+    /// `feat.2da` labels of the character's feats
+    fn feat_labels<'a>(&self, feats: &'a FeatRecord) -> Vec<&'a str> {
+        self.feats
+            .list_ref
+            .get()
+            .iter()
+            .filter_map(|x| feats.feats.get(&(*x.get()).into()))
+            .map(|x| x.label.as_str())
+            .collect()
+    }
+
+    /// Works out the statistics the game would from `classes.2da` and the `cls_*` tables
+    pub fn derived_stats(
+        &self,
+        classes: &ClassRecord,
+        skills: &SkillRecord,
+        feats: &FeatRecord,
+    ) -> DerivedStats {
+        let modifiers = self.ability_modifiers();
+        let [_, dex, con, _, wis, _] = modifiers.map(i16::from);
+        let labels = self.feat_labels(feats);
+        let feat_bonus =
+            |label: &str, bonus: i16| match labels.iter().any(|x| x.eq_ignore_ascii_case(label)) {
+                true => bonus,
+                false => 0,
+            };
+
+        let class_levels = self.classes.iter().filter_map(|x| {
+            let info = classes.classes.get(x.class.get())?;
+            Some((info, (*x.level.get()).max(0) as u16))
+        });
+
+        let mut class_saves = [0i16; 3];
+        for (info, level) in class_levels {
+            let saves = info.saves_at(level);
+            for (total, save) in class_saves
+                .iter_mut()
+                .zip([saves.fort, saves.reflex, saves.will])
+            {
+                *total += i16::from(save);
+            }
+        }
+
+        let [fort, reflex, will] = class_saves;
+        let save = |x: i16| x.clamp(i8::MIN.into(), i8::MAX.into()) as i8;
+        let saves = Saves {
+            fort: save(fort + con + feat_bonus(GREAT_FORTITUDE, 2)),
+            reflex: save(reflex + dex + feat_bonus(LIGHTNING_REFLEXES, 2)),
+            will: save(will + wis + feat_bonus(IRON_WILL, 2)),
+        };
+
+        // Rolls from the history, or the class hit die for levels it doesn't cover
+        let hit_dice = match self.history.levels.is_empty() {
+            false => self
+                .history
+                .levels
+                .iter()
+                .map(|x| i16::from(*x.hit_die.get()))
+                .sum::<i16>(),
+            true => self
+                .classes
+                .iter()
+                .filter_map(|x| {
+                    let info = classes.classes.get(x.class.get())?;
+                    Some(i16::from(info.hit_die) * *x.level.get())
+                })
+                .sum(),
+        };
+        let level = total_level(&self.classes) as i16;
+        let max_hit_points = hit_dice + (con + feat_bonus(TOUGHNESS, 1)) * level;
+
+        let natural_armor = self.natural_armor.as_ref().map(|x| *x.get()).unwrap_or(0);
+        let armor_class = 10 + dex + i16::from(natural_armor);
+
+        DerivedStats {
+            ability_modifiers: modifiers,
+            base_attack_bonus: self.base_attack_bonus(classes),
+            saves,
+            max_hit_points,
+            armor_class,
+            skill_totals: self.skill_totals(skills, feats),
+        }
+    }
+
+    /// Ranks of each skill with the key ability modifier and Skill Focus
+    pub fn skill_totals(&self, skills: &SkillRecord, feats: &FeatRecord) -> HashMap<SkillId, i16> {
+        let modifiers = self.ability_modifiers();
+        let focused = self
+            .feat_labels(feats)
+            .into_iter()
+            .filter_map(|x| {
+                let prefix = x.get(..SKILL_FOCUS_PREFIX.len())?;
+                prefix
+                    .eq_ignore_ascii_case(SKILL_FOCUS_PREFIX)
+                    .then(|| normalize_label(&x[SKILL_FOCUS_PREFIX.len()..]))
+            })
+            .collect::<Vec<_>>();
+
+        skills
+            .skills
+            .iter()
+            .map(|(id, skill)| {
+                let ability = ABILITY_NAMES
+                    .iter()
+                    .position(|x| x.eq_ignore_ascii_case(&skill.key_ability))
+                    .map(|i| i16::from(modifiers[i]))
+                    .unwrap_or(0);
+
+                let label = normalize_label(&skill.label);
+                let focus =
+                    focused.iter().filter(|x| **x == label).count() as i16 * SKILL_FOCUS_BONUS;
+
+                let rank = i16::from(self.skills.ranks.get(*id));
+                (*id, rank + ability + focus)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        class::ClassRecord,
+        feat::FeatPrereqs,
+        ids::class::Class,
+        player::Field,
+        tests::{GameData, classes_2da, fixture, make_feat, make_skill_record, sorcerer},
+    };
+
+    #[test]
+    fn derived_stats() {
+        let bic = fixture!("player.bic");
+        let mut game = GameData::new()
+            .arcane_progression()
+            .table("classes.2da", classes_2da(&[(Class::Sorcerer, sorcerer())]))
+            .build();
+        let mut player = game.read_player(&bic);
+        let classes = ClassRecord::new(&mut game.reader).unwrap();
+        let skills = make_skill_record(
+            &[(0, true), (1, true), (12, true), (16, true)],
+            &[(Class::Sorcerer, &[1, 16])],
+        );
+        // Bonuses follow the labels, the player has feats 173, 189 and 303 but not 14
+        let feats = FeatRecord {
+            feats: [
+                make_feat(14, "FEAT_IRON_WILL", FeatPrereqs::default()),
+                make_feat(173, "FEAT_SKILL_FOCUS_SKILL_1", FeatPrereqs::default()),
+                make_feat(189, "FEAT_SKILL_FOCUS_SKILL16", FeatPrereqs::default()),
+                make_feat(303, "FEAT_GREAT_FORTITUDE", FeatPrereqs::default()),
+            ]
+            .into(),
+        };
+
+        let stats = player.derived_stats(&classes, &skills, &feats);
+        assert_eq!(stats.ability_modifiers, [0, 2, 3, 4, 0, 4]);
+        assert_eq!(stats.base_attack_bonus, 2);
+        assert_eq!(
+            stats.saves,
+            Saves {
+                fort: 6,
+                reflex: 3,
+                will: 4
+            }
+        );
+        assert_eq!(stats.max_hit_points, 28);
+        assert_eq!(stats.armor_class, 12);
+        assert_eq!(
+            [0, 1, 12, 16].map(|x| stats.skill_totals[&x]),
+            [4, 14, 7, 14]
+        );
+
+        // Stored values don't follow ability changes
+        player.attributes.con.set(18, |x| Field::Byte(*x));
+        let stats = player.derived_stats(&classes, &skills, &feats);
+        assert_eq!((stats.max_hit_points, stats.saves.fort), (32, 7));
+        assert_eq!(*player.hit_points.max.as_ref().unwrap().get(), 28);
+        assert_eq!(player.stored_saves().unwrap().fort, 4);
+    }
+}
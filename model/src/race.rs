@@ -0,0 +1,122 @@
+use crate::{Tlk, error::Error, tlk_string_ref::TlkStringRef, two_d_array::FileReader2DA};
+use std::collections::HashMap;
+
+/// Ability adjustments and first level bonuses of a race or subrace
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RaceInfo {
+    pub label: String,
+    /// `Name` from the tlk, the label if it has none
+    pub name: String,
+    /// `StrAdjust` to `ChaAdjust` in Str to Cha order
+    pub ability_adjustments: [i8; 6],
+    /// Feats chosen at level 1 on top of the general feat, like humans' Quick to Master
+    pub extra_feats_at_first_level: u8,
+    /// Skill points gained each level on top of the class's, like humans' extra point
+    pub extra_skill_points_per_level: u8,
+    /// Feat of rangers with the race as a favored enemy, from `FavoredEnemyFeat`
+    pub favored_enemy_feat: Option<u16>,
+}
+
+/// Rows of `racialtypes.2da` and `racialsubtypes.2da`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RaceRecord {
+    pub races: HashMap<u8, RaceInfo>,
+    pub subraces: HashMap<u8, RaceInfo>,
+}
+impl RaceRecord {
+    pub fn new(tlk: &Tlk, reader: &mut FileReader2DA) -> Result<Self, Error> {
+        Ok(Self {
+            races: read_races(tlk, reader, "racialtypes.2da")?,
+            subraces: read_races(tlk, reader, "racialsubtypes.2da")?,
+        })
+    }
+
+    /// The subrace's row if there is one, the game applies its adjustments instead of the race's
+    pub fn get(&self, race: u8, subrace: Option<u8>) -> Option<&RaceInfo> {
+        subrace
+            .and_then(|x| self.subraces.get(&x))
+            .or_else(|| self.races.get(&race))
+    }
+
+    /// Race with `FavoredEnemyFeat` `feat`
+    pub fn favored_enemy(&self, feat: u16) -> Option<u8> {
+        self.races
+            .iter()
+            .find(|(_, x)| x.favored_enemy_feat == Some(feat))
+            .map(|(id, _)| *id)
+    }
+
+    pub fn name(&self, race: u8) -> String {
+        self.races
+            .get(&race)
+            .map(|x| x.name.clone())
+            .unwrap_or_else(|| format!("Race {race}"))
+    }
+}
+
+fn read_races(
+    tlk: &Tlk,
+    reader: &mut FileReader2DA,
+    file_name: &'static str,
+) -> Result<HashMap<u8, RaceInfo>, Error> {
+    let table = reader.read(file_name)?;
+
+    let [
+        label_idx,
+        str_idx,
+        dex_idx,
+        con_idx,
+        int_idx,
+        wis_idx,
+        cha_idx,
+    ] = table
+        .find_column_indices([
+            "Label",
+            "StrAdjust",
+            "DexAdjust",
+            "ConAdjust",
+            "IntAdjust",
+            "WisAdjust",
+            "ChaAdjust",
+        ])
+        .map_err(|e| Error::MissingTableColumn {
+            file: file_name,
+            column: e,
+        })?;
+    let name_idx = table.find_column_index("Name");
+    let extra_feats_idx = table.find_column_index("ExtraFeatsAtFirstLevel");
+    let extra_skill_points_idx = table.find_column_index("ExtraSkillPointsPerLevel");
+    let favored_enemy_idx = table.find_column_index("FavoredEnemyFeat");
+
+    Ok(table
+        .data
+        .row_iter()
+        .enumerate()
+        .filter_map(|(i, row)| {
+            let get = |idx: usize| row.get(idx).and_then(|x| x.as_deref());
+            let adjustment = |idx: usize| get(idx).and_then(|x| x.parse().ok()).unwrap_or(0);
+            let count = |idx: Option<usize>| idx.and_then(get).and_then(|x| x.parse::<u8>().ok());
+
+            let label = get(label_idx)?.to_string();
+            let name = name_idx
+                .and_then(get)
+                .and_then(|x| x.parse().ok())
+                .and_then(|x| TlkStringRef::from_id(tlk, x).ok())
+                .map(|x| x.data)
+                .filter(|x| !x.is_empty())
+                .unwrap_or_else(|| label.clone());
+
+            let info = RaceInfo {
+                label,
+                name,
+                ability_adjustments: [str_idx, dex_idx, con_idx, int_idx, wis_idx, cha_idx]
+                    .map(adjustment),
+                extra_feats_at_first_level: count(extra_feats_idx).unwrap_or(0),
+                extra_skill_points_per_level: count(extra_skill_points_idx).unwrap_or(0),
+                favored_enemy_feat: favored_enemy_idx.and_then(get).and_then(|x| x.parse().ok()),
+            };
+
+            Some((u8::try_from(i).ok()?, info))
+        })
+        .collect())
+}
@@ -0,0 +1,122 @@
+use crate::error::Error;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+pub type Tlk = nwn_lib::files::tlk::Tlk<BufReader<File>>;
+
+pub type IconName = String;
+pub type IconPath = PathBuf;
+
+pub fn join_path(base: &Path, paths: &[&str]) -> PathBuf {
+    let paths = paths.join(std::path::MAIN_SEPARATOR_STR);
+    base.join(paths)
+}
+
+/// Every file under `path`, following symlinks. Entries below `path` that can't be read
+/// are skipped.
+pub fn read_dir_recursive(path: &Path) -> Result<impl Iterator<Item = PathBuf>, Error> {
+    use std::collections::{HashSet, VecDeque};
+
+    let mut stack = path
+        .read_dir()?
+        .filter_map(|x| x.ok().map(|x| x.path()))
+        .collect::<VecDeque<_>>();
+    // Symlinked folders can lead back to a folder already read
+    let mut visited = path.canonicalize().into_iter().collect::<HashSet<_>>();
+
+    Ok(std::iter::from_fn(move || {
+        while let Some(path) = stack.pop_front() {
+            let Ok(metadata) = path.metadata() else {
+                continue;
+            };
+            if metadata.is_file() {
+                return Some(path);
+            }
+            if metadata.is_dir()
+                && let Ok(dir) = path.canonicalize()
+                && visited.insert(dir)
+                && let Ok(entries) = path.read_dir()
+            {
+                for entry in entries.filter_map(Result::ok) {
+                    stack.push_front(entry.path());
+                }
+            }
+        }
+
+        None
+    }))
+}
+
+/// Every file under `game_dir` by file stem
+pub fn get_icon_paths(game_dir: &Path) -> Result<HashMap<IconName, IconPath>, Error> {
+    Ok(read_dir_recursive(game_dir)?
+        .filter_map(|x| {
+            let name = x
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(|x| x.to_string())?;
+            Some((name, x))
+        })
+        .collect())
+}
+
+/// Opens `dialog.tlk` in the game directory
+pub fn get_tlk_file(game_dir: &Path) -> Result<Tlk, Error> {
+    let mut read_dir = game_dir.read_dir()?;
+
+    let file_path = read_dir.find_map(|x| {
+        if let Ok(dir) = x
+            && let Ok(m) = dir.metadata()
+            && m.is_file()
+            && dir.file_name().eq_ignore_ascii_case("dialog.tlk")
+        {
+            return Some(dir.path());
+        }
+
+        None
+    });
+
+    match file_path {
+        Some(p) => {
+            let f = File::open(p)?;
+            Tlk::read(BufReader::new(f)).map_err(Error::LibError)
+        }
+        None => Err(Error::MissingDialogFile(game_dir.into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn read_dir_recursive_follows_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = std::env::temp_dir().join(format!(
+            "nwn2-charedit-model-{}-resources",
+            std::process::id()
+        ));
+        let data = dir.join("data");
+        std::fs::create_dir_all(data.join("ui")).unwrap();
+        std::fs::write(data.join("ui").join("icon.dds"), []).unwrap();
+
+        let game_dir = dir.join("game");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        symlink(&data, game_dir.join("data")).unwrap();
+        // Broken links and links back to a folder already read
+        symlink(dir.join("missing"), game_dir.join("missing")).unwrap();
+        symlink(&game_dir, data.join("game")).unwrap();
+
+        let files = read_dir_recursive(&game_dir).unwrap().collect::<Vec<_>>();
+        assert_eq!(files, [game_dir.join("data").join("ui").join("icon.dds")]);
+        assert!(read_dir_recursive(&dir.join("missing")).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
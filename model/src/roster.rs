@@ -0,0 +1,117 @@
+use crate::{error::Error, field_ref::FieldRef};
+use nwn_lib::files::gff::{
+    Gff,
+    field::Field,
+    r#struct::{Struct, StructField},
+};
+
+fn bool_field(field: &Field) -> Result<bool, nwn_lib::error::Error> {
+    field.expect_int().map(|x| x != 0)
+}
+
+fn find_field(s: &Struct, label: &'static str) -> Result<StructField, Error> {
+    s.find_direct(label)
+        .ok_or_else(|| Error::MissingField(format!("{label} in roster.rst")))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RosterFlag {
+    Available,
+    Selectable,
+    CampaignNpc,
+    Required,
+}
+
+/// Entry in `RosMembers`
+#[derive(Debug, Clone)]
+pub struct RosterMember {
+    /// Roster name, matches the `.ros` file name and the creature's `RosterTag`
+    pub name: String,
+    pub char_name: String,
+    pub available: FieldRef<bool>,
+    pub selectable: FieldRef<bool>,
+    pub campaign_npc: FieldRef<bool>,
+    pub required: FieldRef<bool>,
+    /// Set when the companion has an entry in `Mod_PlayerList`
+    pub in_party: bool,
+}
+impl RosterMember {
+    fn new(s: &Struct, party_tags: &[String]) -> Result<Self, Error> {
+        let read_string = |label| -> Result<String, Error> {
+            find_field(s, label)?
+                .read_field(|f| f.expect_exostring().map(|x| x.0.clone()))
+                .map_err(Error::LibError)
+        };
+
+        let name = read_string("RosName")?;
+        let char_name = read_string("RosCharName")?;
+
+        let flag = |label| FieldRef::new(find_field(s, label)?, bool_field);
+
+        Ok(Self {
+            in_party: party_tags.iter().any(|x| x.eq_ignore_ascii_case(&name)),
+            name,
+            char_name,
+            available: flag("RosAvailable")?,
+            selectable: flag("RosSelectable")?,
+            campaign_npc: flag("RosCampaignNPC")?,
+            required: flag("RosRequired")?,
+        })
+    }
+
+    pub fn flag(&self, flag: RosterFlag) -> bool {
+        match flag {
+            RosterFlag::Available => *self.available.get(),
+            RosterFlag::Selectable => *self.selectable.get(),
+            RosterFlag::CampaignNpc => *self.campaign_npc.get(),
+            RosterFlag::Required => *self.required.get(),
+        }
+    }
+
+    pub fn set_flag(&mut self, flag: RosterFlag, value: bool) {
+        let field_ref = match flag {
+            RosterFlag::Available => &mut self.available,
+            RosterFlag::Selectable => &mut self.selectable,
+            RosterFlag::CampaignNpc => &mut self.campaign_npc,
+            RosterFlag::Required => &mut self.required,
+        };
+
+        field_ref.set(value, |x| Field::Int(*x as i32));
+    }
+}
+
+/// Companion roster from `roster.rst`
+#[derive(Debug, Clone)]
+pub struct Roster {
+    pub party_limit: FieldRef<i32>,
+    pub members: Vec<RosterMember>,
+}
+impl Roster {
+    /// `party_tags` are the `RosterTag`s of companions in `Mod_PlayerList`
+    pub fn new(roster: &Gff, party_tags: &[String]) -> Result<Self, Error> {
+        let party_limit = FieldRef::new(
+            find_field(&roster.root, "RosPartyLimit")?,
+            Field::expect_int,
+        )?;
+
+        let members = find_field(&roster.root, "RosMembers")?;
+        let lock = members.read()?;
+        let members = lock
+            .field
+            .expect_list()?
+            .iter()
+            .map(|s| RosterMember::new(s, party_tags))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            party_limit,
+            members,
+        })
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.members
+            .iter()
+            .position(|m| m.name.eq_ignore_ascii_case(name))
+    }
+}
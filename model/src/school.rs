@@ -0,0 +1,66 @@
+use crate::{Tlk, error::Error, tlk_string_ref::TlkStringRef, two_d_array::FileReader2DA};
+use std::collections::HashMap;
+
+/// A wizard's school of specialization, a row of `spellschools.2da`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SpellSchool {
+    pub label: String,
+    /// `StringRef` from the tlk, the label if it has none
+    pub name: String,
+    /// School a specialist can't cast from, from `Opposition`
+    pub opposition: Option<u8>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SchoolRecord {
+    pub schools: HashMap<u8, SpellSchool>,
+}
+impl SchoolRecord {
+    pub fn new(tlk: &Tlk, reader: &mut FileReader2DA) -> Result<Self, Error> {
+        let file_name = "spellschools.2da";
+        let table = reader.read(file_name)?;
+
+        let [label_idx, name_idx] =
+            table
+                .find_column_indices(["Label", "StringRef"])
+                .map_err(|e| Error::MissingTableColumn {
+                    file: file_name,
+                    column: e,
+                })?;
+        let opposition_idx = table.find_column_index("Opposition");
+
+        let schools = table
+            .data
+            .row_iter()
+            .enumerate()
+            .filter_map(|(i, row)| {
+                let get = |idx: usize| row.get(idx).and_then(|x| x.as_deref());
+
+                let label = get(label_idx)?.to_string();
+                let name = get(name_idx)
+                    .and_then(|x| x.parse().ok())
+                    .and_then(|x| TlkStringRef::from_id(tlk, x).ok())
+                    .map(|x| x.data)
+                    .filter(|x| !x.is_empty())
+                    .unwrap_or_else(|| label.clone());
+
+                let school = SpellSchool {
+                    label,
+                    name,
+                    opposition: opposition_idx.and_then(get).and_then(|x| x.parse().ok()),
+                };
+
+                Some((u8::try_from(i).ok()?, school))
+            })
+            .collect();
+
+        Ok(Self { schools })
+    }
+
+    pub fn name(&self, school: u8) -> String {
+        self.schools
+            .get(&school)
+            .map(|x| x.name.clone())
+            .unwrap_or_else(|| format!("School {school}"))
+    }
+}
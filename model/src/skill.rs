@@ -0,0 +1,212 @@
+use crate::{
+    Tlk,
+    error::Error,
+    icon::Icon,
+    ids::class::Class,
+    resources::{IconName, IconPath},
+    tlk_string_ref::TlkStringRef,
+    two_d_array::FileReader2DA,
+};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skill {
+    pub label: String,
+    pub name: TlkStringRef,
+    pub desc: Option<TlkStringRef>,
+    pub icon: Option<Icon>,
+    /// Ability label from `skills.2da`, e.g. `DEX`
+    pub key_ability: String,
+    /// Whether classes without it as a class skill can take ranks
+    pub all_classes_can_use: bool,
+}
+
+pub type SkillId = usize;
+
+/// How a class can take ranks in a skill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillStatus {
+    ClassSkill,
+    CrossClass,
+    Unavailable,
+}
+impl std::fmt::Display for SkillStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ClassSkill => "Class",
+            Self::CrossClass => "Cross-class",
+            Self::Unavailable => "Unavailable",
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SkillRecord {
+    pub skills: HashMap<SkillId, Skill>,
+    /// Class skills of each class, from the `cls_skill_*` table in `classes.2da`
+    pub class_skills: HashMap<Class, HashSet<SkillId>>,
+}
+impl SkillRecord {
+    pub fn new(
+        tlk: &Tlk,
+        reader: &mut FileReader2DA,
+        icon_paths: &HashMap<IconName, IconPath>,
+    ) -> Result<Self, Error> {
+        let file_name = "skills.2da";
+        let table = reader.read(file_name)?;
+
+        let [
+            label_idx,
+            name_idx,
+            desc_idx,
+            icon_idx,
+            ability_idx,
+            all_classes_idx,
+        ] = table
+            .find_column_indices([
+                "Label",
+                "Name",
+                "Description",
+                "Icon",
+                "KeyAbility",
+                "AllClassesCanUse",
+            ])
+            .map_err(|e| Error::MissingTableColumn {
+                file: file_name,
+                column: e,
+            })?;
+
+        let from_row = |row: &[Option<String>]| -> Option<Skill> {
+            let label = row.get(label_idx)?.clone()?;
+
+            let name_ref = row.get(name_idx)?.as_deref()?;
+            let name_ref = name_ref.parse().ok()?;
+
+            let desc_ref = row.get(desc_idx)?.as_deref()?;
+            let desc_ref = desc_ref.parse().ok();
+
+            let icon = row
+                .get(icon_idx)?
+                .as_deref()
+                .and_then(|name| icon_paths.get(name))
+                .and_then(|path| Icon::read(path));
+
+            Some(Skill {
+                label,
+                name: TlkStringRef::from_id(tlk, name_ref).ok()?,
+                desc: desc_ref.and_then(|r| TlkStringRef::from_id(tlk, r).ok()),
+                icon,
+                key_ability: row.get(ability_idx)?.clone().unwrap_or_default(),
+                all_classes_can_use: row.get(all_classes_idx)?.as_deref() == Some("1"),
+            })
+        };
+
+        let skills = table
+            .data
+            .row_iter()
+            .enumerate()
+            .filter_map(|(i, x)| from_row(x).map(|x| (i, x)))
+            .collect();
+
+        Ok(Self {
+            skills,
+            class_skills: Self::read_class_skills(reader)?,
+        })
+    }
+
+    fn read_class_skills(
+        reader: &mut FileReader2DA,
+    ) -> Result<HashMap<Class, HashSet<SkillId>>, Error> {
+        let file_name = "classes.2da";
+        let table = reader.read(file_name)?;
+
+        let skills_table_idx =
+            table
+                .find_column_index("SkillsTable")
+                .ok_or(Error::MissingTableColumn {
+                    file: file_name,
+                    column: "SkillsTable",
+                })?;
+
+        let skill_tables = table
+            .get_column_data(skills_table_idx)
+            .enumerate()
+            .filter_map(|(i, x)| Some((Class(i as i32), x?.to_ascii_lowercase())))
+            .collect::<Vec<_>>();
+
+        let mut class_skills = HashMap::new();
+
+        for (class, table_name) in skill_tables {
+            // Some rows name tables that were never shipped
+            let Ok(table) = reader.read(&format!("{table_name}.2da")) else {
+                continue;
+            };
+
+            let Ok([index_idx, class_skill_idx]) =
+                table.find_column_indices(["SkillIndex", "ClassSkill"])
+            else {
+                continue;
+            };
+
+            let skills = table
+                .data
+                .row_iter()
+                .filter(|row| row.get(class_skill_idx).and_then(|x| x.as_deref()) == Some("1"))
+                .filter_map(|row| row.get(index_idx)?.as_deref()?.parse().ok())
+                .collect();
+
+            class_skills.insert(class, skills);
+        }
+
+        Ok(class_skills)
+    }
+
+    /// Name of `skill`, or its id if the table doesn't have it
+    pub fn name(&self, skill: SkillId) -> String {
+        self.skills
+            .get(&skill)
+            .map(|x| x.name.data.clone())
+            .unwrap_or_else(|| format!("Skill {skill}"))
+    }
+
+    pub fn status(&self, class: Class, skill: SkillId) -> SkillStatus {
+        let is_class_skill = self
+            .class_skills
+            .get(&class)
+            .is_some_and(|x| x.contains(&skill));
+
+        match (is_class_skill, self.skills.get(&skill)) {
+            (true, _) => SkillStatus::ClassSkill,
+            (false, Some(x)) if x.all_classes_can_use => SkillStatus::CrossClass,
+            (false, _) => SkillStatus::Unavailable,
+        }
+    }
+
+    /// Skill points a rank costs when bought with a level of `class`
+    pub fn rank_cost(&self, class: Class, skill: SkillId) -> u16 {
+        match self.status(class, skill) {
+            SkillStatus::ClassSkill => 1,
+            SkillStatus::CrossClass | SkillStatus::Unavailable => 2,
+        }
+    }
+
+    /// Highest rank a character of `total_level` with `classes` can have in `skill`
+    pub fn max_ranks(&self, classes: &[Class], total_level: u16, skill: SkillId) -> u8 {
+        let best = classes
+            .iter()
+            .map(|x| self.status(*x, skill))
+            .min_by_key(|x| match x {
+                SkillStatus::ClassSkill => 0,
+                SkillStatus::CrossClass => 1,
+                SkillStatus::Unavailable => 2,
+            });
+
+        let max = match best {
+            Some(SkillStatus::ClassSkill) => total_level + 3,
+            Some(SkillStatus::CrossClass) => (total_level + 3) / 2,
+            Some(SkillStatus::Unavailable) | None => 0,
+        };
+
+        max.min(u8::MAX.into()) as u8
+    }
+}
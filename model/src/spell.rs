@@ -1,13 +1,12 @@
 use std::{collections::HashMap, path::Path};
 
-use iced::widget::image::Handle;
-
 use crate::{
     Tlk,
     error::Error,
+    icon::Icon,
     ids::class::Class,
+    resources::{IconName, IconPath, join_path},
     tlk_string_ref::TlkStringRef,
-    ui::settings::{IconName, IconPath},
 };
 
 type SpellLevel = Option<u8>;
@@ -23,13 +22,28 @@ pub struct SpellLevels {
     pub warlock: SpellLevel,
     pub innate: SpellLevel,
 }
+impl SpellLevels {
+    /// Level of the spell on the spell list of `class`, `None` if it isn't on it
+    pub fn for_class(&self, class: Class) -> SpellLevel {
+        match class {
+            Class::Bard => self.bard,
+            Class::Cleric => self.cleric,
+            Class::Druid => self.druid,
+            Class::Paladin => self.paladin,
+            Class::Ranger => self.ranger,
+            Class::Wizard | Class::Sorcerer => self.wiz_sorc,
+            Class::Warlock => self.warlock,
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Spell {
     pub label: String,
     pub name: TlkStringRef,
     pub desc: Option<TlkStringRef>,
-    pub icon: Option<Handle>,
+    pub icon: Option<Icon>,
     pub spell_levels: SpellLevels,
 }
 
@@ -47,7 +61,7 @@ impl SpellRecord {
     ) -> Result<Self, Error> {
         let file_name = "spells.2da";
 
-        let file_path = super::join_path(
+        let file_path = join_path(
             game_dir,
             &["campaigns", "westgate_campaign", "2da", file_name],
         );
@@ -104,19 +118,7 @@ impl SpellRecord {
                 .get(icon_idx)?
                 .as_deref()
                 .and_then(|name| icon_paths.get(name))
-                .and_then(|path| {
-                    let f = std::fs::File::open(path).ok()?;
-                    let reader = std::io::BufReader::new(f);
-                    dds::Dds::read(reader).ok()
-                })
-                .map(|dds| {
-                    let pixels = Vec::from_iter(
-                        dds.pixels
-                            .into_iter()
-                            .flat_map(|dds::Rgba { r, g, b, a }| [r, g, b, a]),
-                    );
-                    Handle::from_rgba(dds.header.width, dds.header.height, pixels)
-                });
+                .and_then(|path| Icon::read(path));
 
             let get_spell_level = |idx: usize| {
                 row.get(idx)
@@ -178,28 +180,6 @@ impl SpellRecord {
         }
     }
 
-    fn for_spells_for_class(&self, class: Class, f: impl FnMut(&Spell)) {
-        macro_rules! for_class {
-            ($class:ident) => {
-                self.spells
-                    .values()
-                    .filter(|x| x.spell_levels.$class.is_some())
-                    .for_each(f)
-            };
-        }
-
-        match class {
-            Class::Bard => for_class!(bard),
-            Class::Cleric => for_class!(cleric),
-            Class::Druid => for_class!(druid),
-            Class::Paladin => for_class!(paladin),
-            Class::Ranger => for_class!(ranger),
-            Class::Wizard | Class::Sorcerer => for_class!(wiz_sorc),
-            Class::Warlock => for_class!(warlock),
-            _ => (),
-        }
-    }
-
     pub fn get_spells_per_class_level<'a>(
         &'a self,
         class: Class,
@@ -0,0 +1,405 @@
+//! Generated game directories and save fixtures shared by the model's tests
+
+use crate::{
+    Tlk,
+    feat::{Feat, FeatId, FeatPrereqs},
+    ids::class::Class,
+    player::Player,
+    resources::get_tlk_file,
+    skill::{Skill, SkillId, SkillRecord},
+    tlk_string_ref::TlkStringRef,
+    two_d_array::FileReader2DA,
+};
+use nwn_lib::files::gff::Gff;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Cursor, Write},
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use zip::write::SimpleFileOptions;
+
+/// Rows of the generated `racialtypes.2da`, named `Race <row>` in the tlk
+pub const RACE_COUNT: usize = 32;
+/// Rows of the generated `racialsubtypes.2da`, named `Subrace <row>` after the races
+pub const SUBRACE_COUNT: usize = 64;
+
+/// Reads a save from `lib/src/tests/files`
+macro_rules! fixture {
+    ($file_name: literal) => {
+        $crate::tests::read_gff(include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../lib/src/tests/files/",
+            $file_name
+        )))
+    };
+}
+pub(crate) use fixture;
+
+pub fn read_gff(data: &[u8]) -> Gff {
+    Gff::read_without_tlk(Cursor::new(data)).unwrap()
+}
+
+pub fn write_gff(gff: &Gff) -> Vec<u8> {
+    let mut data = Cursor::new(vec![]);
+    gff.write(&mut data).unwrap();
+    data.into_inner()
+}
+
+pub fn write_and_read(gff: &Gff) -> Gff {
+    read_gff(&write_gff(gff))
+}
+
+/// 2DA with tab separated `columns`, rows missing from `rows` are `****` up to the last one
+pub fn two_da(columns: &str, rows: &[(usize, impl AsRef<str>)]) -> String {
+    let empty = vec!["****"; columns.split('\t').count()].join("\t");
+    let rows = rows
+        .iter()
+        .map(|(i, row)| (*i, row.as_ref()))
+        .collect::<BTreeMap<_, _>>();
+    let count = rows.keys().last().map_or(0, |x| x + 1);
+
+    (0..count).fold(format!("2DA V2.0\n\n\t{columns}\n"), |acc, i| {
+        let row = rows.get(&i).copied().unwrap_or(&empty);
+        acc + &format!("{i}\t{row}\n")
+    })
+}
+
+/// Values of some columns of a race table row
+pub type RaceRow<'a> = (usize, &'a [(&'a str, &'a str)]);
+
+/// Race table naming each of its `count` rows with the ability adjustment columns
+/// [`RaceRecord::new`](crate::race::RaceRecord::new) needs, other columns are only set on `rows`
+fn race_table(count: usize, first_str_ref: usize, label: &str, rows: &[RaceRow]) -> String {
+    let mut columns = vec![
+        "StrAdjust",
+        "DexAdjust",
+        "ConAdjust",
+        "IntAdjust",
+        "WisAdjust",
+        "ChaAdjust",
+    ];
+    for (column, _) in rows.iter().flat_map(|(_, x)| x.iter()) {
+        if !columns.contains(column) {
+            columns.push(column);
+        }
+    }
+
+    let rows = (0..count)
+        .map(|i| {
+            let values = rows.iter().find(|(x, _)| *x == i).map_or(&[][..], |x| x.1);
+            let values = columns.iter().map(|column| {
+                values
+                    .iter()
+                    .find(|(x, _)| x == column)
+                    .map_or("****", |(_, value)| value)
+            });
+            let row = [format!("{label}{i}"), (first_str_ref + i).to_string()]
+                .into_iter()
+                .chain(values.map(str::to_string))
+                .collect::<Vec<_>>();
+            (i, row.join("\t"))
+        })
+        .collect::<Vec<_>>();
+
+    two_da(&format!("Label\tName\t{}", columns.join("\t")), &rows)
+}
+
+pub fn feat_ids(player: &Player) -> Vec<u16> {
+    player
+        .feats
+        .list_ref
+        .get()
+        .iter()
+        .map(|x| *x.get())
+        .collect()
+}
+
+/// A player class row of `classes.2da`, empty table names are `****`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ClassRow<'a> {
+    pub label: &'a str,
+    pub hit_die: u8,
+    pub skill_point_base: u8,
+    pub attack_bonus: &'a str,
+    pub saves: &'a str,
+    pub feats: &'a str,
+    pub bonus_feats: &'a str,
+    pub spells_known: &'a str,
+    pub spells_per_day: &'a str,
+    pub prereqs: &'a str,
+    /// `AlignRestrict`, `AlignRstrctType` and `InvertRestrict`
+    pub alignment: (u8, u8, bool),
+    pub arcane: bool,
+    pub spell_ability: &'a str,
+    pub has_domains: bool,
+}
+
+/// `classes.2da` with the columns [`ClassRecord::new`](crate::class::ClassRecord::new)
+/// reads, rows before the last class are monster classes without a hit die
+pub fn classes_2da(rows: &[(Class, ClassRow)]) -> String {
+    let columns = "Label\tPlayerClass\tHitDie\tSkillPointBase\tMaxLevel\tAttackBonusTable\t\
+         SavingThrowTable\tFeatsTable\tAlignRestrict\tAlignRstrctType\tInvertRestrict\tHasArcane\t\
+         BonusFeatsTable\tSpellKnownTable\tSpellGainTable\tPreReqTable\tSpellAbil\tHasDomains";
+    let name = |x: &str| match x {
+        "" => "****".to_string(),
+        x => x.to_string(),
+    };
+
+    let rows = rows
+        .iter()
+        .map(|(class, x)| {
+            let (alignments, axes, invert) = x.alignment;
+            let row = [
+                name(x.label),
+                "1".into(),
+                x.hit_die.to_string(),
+                x.skill_point_base.to_string(),
+                "****".into(),
+                name(x.attack_bonus),
+                name(x.saves),
+                name(x.feats),
+                format!("{alignments:#04x}"),
+                format!("{axes:#04x}"),
+                u8::from(invert).to_string(),
+                u8::from(x.arcane).to_string(),
+                name(x.bonus_feats),
+                name(x.spells_known),
+                name(x.spells_per_day),
+                name(x.prereqs),
+                name(x.spell_ability),
+                u8::from(x.has_domains).to_string(),
+            ];
+            (class.0 as usize, row.join("\t"))
+        })
+        .collect::<Vec<_>>();
+
+    two_da(columns, &rows)
+}
+
+/// Sorcerer row of `classes.2da` using the tables of [`GameData::arcane_progression`]
+pub fn sorcerer() -> ClassRow<'static> {
+    ClassRow {
+        label: "Sorcerer",
+        hit_die: 4,
+        skill_point_base: 2,
+        attack_bonus: "CLS_ATK_2",
+        saves: "CLS_SAVTHR_WIZ",
+        ..Default::default()
+    }
+}
+
+/// Wizard row of `classes.2da` like [`sorcerer`]
+pub fn wizard() -> ClassRow<'static> {
+    ClassRow {
+        label: "Wizard",
+        ..sorcerer()
+    }
+}
+
+/// `cls_feat_*.2da` with rows of `(feat, List, GrantedOnLevel)`
+pub fn class_feats_2da(rows: &[(FeatId, u8, i8)]) -> String {
+    let rows = rows
+        .iter()
+        .enumerate()
+        .map(|(i, (feat, list, level))| (i, format!("FEAT_{feat}\t{feat}\t{list}\t{level}\t0")))
+        .collect::<Vec<_>>();
+    two_da("FeatLabel\tFeatIndex\tList\tGrantedOnLevel\tOnMenu", &rows)
+}
+
+/// `cls_spkn_*.2da` or `cls_spgn_*.2da` with the spells of each spell level at rows of
+/// class levels, spell levels past the given ones are `****`
+pub fn spell_levels_2da(rows: &[(usize, &[u8])]) -> String {
+    let columns = (0..10).map(|i| format!("\tSpellLevel{i}"));
+    let rows = rows
+        .iter()
+        .map(|(i, spells)| {
+            let spells = (0..10).map(|x| spells.get(x).map_or("****".into(), u8::to_string));
+            let row = std::iter::once((i + 1).to_string())
+                .chain(spells)
+                .collect::<Vec<_>>();
+            (*i, row.join("\t"))
+        })
+        .collect::<Vec<_>>();
+    two_da(&format!("Level{}", columns.collect::<String>()), &rows)
+}
+
+/// Feat `id` named `Feat <id>` in the tlk
+pub fn make_feat(id: FeatId, label: &str, prereqs: FeatPrereqs) -> (FeatId, Feat) {
+    let feat = Feat {
+        label: label.into(),
+        name: TlkStringRef {
+            id: id as u32,
+            data: format!("Feat {id}"),
+        },
+        desc: None,
+        icon: None,
+        prereqs,
+    };
+    (id, feat)
+}
+
+/// Skills `(id, all_classes_can_use)` with the class skills of each class
+pub fn make_skill_record(
+    skills: &[(SkillId, bool)],
+    class_skills: &[(Class, &[SkillId])],
+) -> SkillRecord {
+    let skills = skills
+        .iter()
+        .map(|&(i, all_classes_can_use)| {
+            let skill = Skill {
+                label: format!("Skill{i}"),
+                name: TlkStringRef {
+                    id: i as u32,
+                    data: format!("Skill {i}"),
+                },
+                desc: None,
+                icon: None,
+                key_ability: "INT".into(),
+                all_classes_can_use,
+            };
+            (i, skill)
+        })
+        .collect();
+    let class_skills = class_skills
+        .iter()
+        .map(|(class, skills)| (*class, skills.iter().copied().collect()))
+        .collect::<HashMap<_, _>>();
+
+    SkillRecord {
+        skills,
+        class_skills,
+    }
+}
+
+/// `dialog.tlk` and `data/2da.zip` of a game directory written by [`GameData::build`]
+pub struct GameData {
+    strings: Vec<String>,
+    tables: BTreeMap<&'static str, String>,
+}
+impl GameData {
+    /// Race and subrace tables with only names, the tables [`Player::new`] reads
+    pub fn new() -> Self {
+        let strings = (0..RACE_COUNT)
+            .map(|i| format!("Race {i}"))
+            .chain((0..SUBRACE_COUNT).map(|i| format!("Subrace {i}")))
+            .collect();
+
+        let mut data = Self {
+            strings,
+            tables: BTreeMap::new(),
+        };
+        data.races(&[]).subraces(&[]);
+        data
+    }
+
+    /// Adds `data` to the tlk, returns its str ref
+    pub fn string(&mut self, data: &str) -> usize {
+        self.strings.push(data.into());
+        self.strings.len() - 1
+    }
+
+    pub fn table(&mut self, file_name: &'static str, table: impl Into<String>) -> &mut Self {
+        self.tables.insert(file_name, table.into());
+        self
+    }
+
+    /// `cls_atk_2.2da` and `cls_savthr_wiz.2da` of [`sorcerer`] and [`wizard`] for levels
+    /// 1 to 5 and 1 to 4, the tables stop after them
+    pub fn arcane_progression(&mut self) -> &mut Self {
+        let attack = two_da("BAB", &[(0, "0"), (1, "1"), (2, "1"), (3, "2"), (4, "3")]);
+        let saves = two_da(
+            "Level\tFortSave\tRefSave\tWillSave",
+            &[
+                (0, "1\t0\t0\t2"),
+                (1, "2\t0\t0\t3"),
+                (2, "3\t1\t1\t3"),
+                (3, "4\t1\t1\t4"),
+            ],
+        );
+        self.table("cls_atk_2.2da", attack)
+            .table("cls_savthr_wiz.2da", saves)
+    }
+
+    /// `racialtypes.2da` with the column values of `rows`, labelled `RACE_<row>`
+    pub fn races(&mut self, rows: &[RaceRow]) -> &mut Self {
+        let table = race_table(RACE_COUNT, 0, "RACE_", rows);
+        self.table("racialtypes.2da", table)
+    }
+
+    /// `racialsubtypes.2da` like [`Self::races`], labelled `SUBRACE_<row>`
+    pub fn subraces(&mut self, rows: &[RaceRow]) -> &mut Self {
+        let table = race_table(SUBRACE_COUNT, RACE_COUNT, "SUBRACE_", rows);
+        self.table("racialsubtypes.2da", table)
+    }
+
+    /// Writes the game directory to a new temporary directory
+    pub fn build(&self) -> TestGame {
+        static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "nwn2-charedit-model-{}-{}",
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(dir.join("data")).unwrap();
+
+        std::fs::write(dir.join("dialog.tlk"), self.tlk()).unwrap();
+
+        let zip_file = std::fs::File::create(dir.join("data").join("2da.zip")).unwrap();
+        let mut zip = zip::ZipWriter::new(zip_file);
+        for (file_name, table) in &self.tables {
+            zip.start_file(format!("2DA/{file_name}"), SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(table.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        TestGame {
+            tlk: get_tlk_file(&dir).unwrap(),
+            reader: FileReader2DA::new(&dir).unwrap(),
+            dir,
+        }
+    }
+
+    fn tlk(&self) -> Vec<u8> {
+        let count = self.strings.len() as u32;
+        let mut data = Vec::new();
+        data.extend_from_slice(b"TLK V3.0");
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&count.to_le_bytes());
+        data.extend_from_slice(&(20 + 40 * count).to_le_bytes());
+
+        let mut offset = 0u32;
+        for s in &self.strings {
+            data.extend_from_slice(&1u32.to_le_bytes());
+            data.extend_from_slice(&[0; 24]);
+            data.extend_from_slice(&offset.to_le_bytes());
+            data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            data.extend_from_slice(&0f32.to_le_bytes());
+            offset += s.len() as u32;
+        }
+
+        self.strings
+            .iter()
+            .for_each(|s| data.extend_from_slice(s.as_bytes()));
+        data
+    }
+}
+
+/// Generated game directory, removed when dropped
+pub struct TestGame {
+    dir: PathBuf,
+    pub tlk: Tlk,
+    pub reader: FileReader2DA,
+}
+impl TestGame {
+    pub fn read_player(&mut self, gff: &Gff) -> Player {
+        Player::new(&self.tlk, &mut self.reader, &gff.root).unwrap()
+    }
+}
+impl Drop for TestGame {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
@@ -50,7 +50,11 @@ impl FileReader2DA {
 
     pub fn read(&mut self, file_name: &str) -> Result<DataTable, Error> {
         let path = format!("{}/{}", self.file.name, file_name);
-        let entry = self.file.archive.by_path(&path).unwrap();
+        let entry = self
+            .file
+            .archive
+            .by_path(&path)
+            .map_err(|e| Error::ParseError(format!("Failed to read {path}: {e}")))?;
 
         nwn_lib::files::two_da::parse(entry).map_err(Error::LibError)
     }
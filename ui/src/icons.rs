@@ -0,0 +1,62 @@
+use iced::widget::image::Handle;
+use nwn_model::{
+    feat::{FeatId, FeatRecord},
+    icon::Icon,
+    item::{BaseItemId, BaseItemRecord},
+    skill::{SkillId, SkillRecord},
+    spell::{SpellId, SpellRecord},
+};
+use std::collections::HashMap;
+
+fn to_handle(icon: &Icon) -> Handle {
+    Handle::from_rgba(icon.width, icon.height, icon.pixels.clone())
+}
+
+/// Image handles for feat, spell, skill and base item icons. Each handle gets a new id when it's
+/// created, so they're made once here rather than every frame
+#[derive(Debug, Default)]
+pub struct IconCache {
+    pub feats: HashMap<FeatId, Handle>,
+    pub spells: HashMap<SpellId, Handle>,
+    pub skills: HashMap<SkillId, Handle>,
+    pub base_items: HashMap<BaseItemId, Handle>,
+}
+impl IconCache {
+    pub fn new(
+        feat_record: &FeatRecord,
+        spell_record: &SpellRecord,
+        skill_record: &SkillRecord,
+        base_item_record: &BaseItemRecord,
+    ) -> Self {
+        let feats = feat_record
+            .feats
+            .iter()
+            .filter_map(|(id, feat)| Some((*id, to_handle(feat.icon.as_ref()?))))
+            .collect();
+
+        let spells = spell_record
+            .spells
+            .iter()
+            .filter_map(|(id, spell)| Some((*id, to_handle(spell.icon.as_ref()?))))
+            .collect();
+
+        let skills = skill_record
+            .skills
+            .iter()
+            .filter_map(|(id, skill)| Some((*id, to_handle(skill.icon.as_ref()?))))
+            .collect();
+
+        let base_items = base_item_record
+            .items
+            .iter()
+            .filter_map(|(id, item)| Some((*id, to_handle(item.icon.as_ref()?))))
+            .collect();
+
+        Self {
+            feats,
+            spells,
+            skills,
+            base_items,
+        }
+    }
+}
@@ -1,60 +1,31 @@
 #![allow(unstable_name_collisions)]
 
 mod error;
-mod feat;
-mod field_ref;
-mod ids;
-mod player;
-mod spell;
-mod tlk_string_ref;
-mod two_d_array;
+mod icons;
+mod portrait;
 mod ui;
 
-use crate::{
-    error::Error, player::Player, two_d_array::FileReader2DA, ui::settings::GameResources,
-};
+use crate::error::Error;
 use iced::{
     Length, Task,
     widget::{button, column, horizontal_space, row, text},
 };
-use nwn_lib::files::gff::Gff;
-use std::{
-    fs::File,
-    io::{BufReader, Read},
-    path::{Path, PathBuf},
+use nwn_lib::save::SaveGame;
+use nwn_model::{
+    Tlk, error::Error as ModelError, player::Player, roster::Roster, two_d_array::FileReader2DA,
 };
+use std::path::{Path, PathBuf};
+
+fn open_file(path: &Path) -> Result<SaveGame, Error> {
+    let save_dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().ok_or_else(|| {
+            ModelError::ParseError(format!("Invalid save path: {}", path.display()))
+        })?
+    };
 
-pub(crate) fn join_path(base: &Path, paths: &[&str]) -> PathBuf {
-    let paths = paths.join(std::path::MAIN_SEPARATOR_STR);
-    base.join(paths)
-}
-
-fn open_file(path: &Path) -> Result<Gff, Error> {
-    let ext = path.extension().and_then(|x| x.to_str());
-
-    match ext {
-        Some("zip") => {
-            let file = File::open(path).unwrap();
-            let mut reader = zip::read::ZipArchive::new(file).unwrap();
-            let save = {
-                let mut save = reader
-                    .by_name("playerlist.ifo")
-                    .expect("missing playerlist.ifo");
-                let mut buf = Vec::with_capacity(save.size() as usize);
-                save.read_to_end(&mut buf).unwrap();
-                std::io::Cursor::new(buf)
-            };
-
-            Gff::read_without_tlk(save).map_err(|e| e.into())
-        }
-        Some("ifo") => {
-            let file = File::open(path).unwrap();
-            Gff::read_without_tlk(file).map_err(|e| e.into())
-        }
-
-        Some(e) => panic!("unexpected file ext: {e}"),
-        None => panic!("unknown file type"),
-    }
+    SaveGame::open(save_dir).map_err(|e| e.into())
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -67,6 +38,9 @@ enum Message {
     FileSelector(ui::SelectFileMessage),
     SaveFile,
     SaveWindow(ui::SaveMessage),
+    OpenGlobals,
+    Globals(ui::GlobalsMessage),
+    Portraits(ui::PortraitMessage),
     CloseFile,
 }
 
@@ -103,38 +77,79 @@ fn menu_button(text: &str) -> iced::widget::Button<'_, Message> {
     button(text).style(style)
 }
 
-pub type Tlk = nwn_lib::files::tlk::Tlk<BufReader<File>>;
-
 #[derive(Debug)]
 pub struct SaveFile {
-    pub file: Gff,
-    pub path: PathBuf,
-    pub save_dir: PathBuf,
+    pub save: SaveGame,
 }
 impl SaveFile {
+    pub fn save_dir(&self) -> &Path {
+        &self.save.path
+    }
+
     pub fn get_players(&self, tlk: &Tlk, reader_2da: &mut FileReader2DA) -> Vec<Player> {
         let player_list = self
-            .file
-            .root
-            .bfs_iter()
-            .find(|x| x.has_label("Mod_PlayerList"))
-            .expect("Couldn't find player list");
+            .save
+            .res_gff
+            .player_list()
+            .expect("Missing playerlist.ifo");
 
-        let lock = player_list.read().unwrap();
-        let player_list = lock.field.expect_list().unwrap();
+        Player::from_player_list(tlk, reader_2da, player_list).unwrap()
+    }
+
+    /// Companions stored as `.ros` files in `resgff.zip`.
+    ///
+    /// Companions in the party are skipped, their `Mod_PlayerList` entry is edited instead
+    /// and synced to the `.ros` when saving
+    pub fn get_companions(&self, tlk: &Tlk, reader_2da: &mut FileReader2DA) -> Vec<Player> {
+        let in_party = self.save.res_gff.companion_copies();
+
+        self.save
+            .res_gff
+            .roster_members()
+            .filter(|(name, _)| !in_party.iter().any(|x| x.ros_name == *name))
+            .filter_map(
+                |(name, gff)| match Player::new(tlk, reader_2da, &gff.root) {
+                    Ok(player) => Some(player),
+                    Err(e) => popup_opt!("Failed to read companion {name}: {e}"),
+                },
+            )
+            .collect()
+    }
 
-        player_list
+    /// Warns if a companion's `Mod_PlayerList` entry and `.ros` already disagree
+    pub fn check_companions(&self) {
+        let mismatches = self.save.res_gff.companion_mismatches();
+        if mismatches.is_empty() {
+            return;
+        }
+
+        let description = mismatches
             .iter()
-            .map(|x| Player::new(tlk, reader_2da, x))
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap()
+            .map(|m| format!("{}: {}", m.copies.tag, m.fields.join(", ")))
+            .fold(
+                "Companion data differs between playerlist.ifo and the roster files:\n".to_string(),
+                |acc, x| acc + "\n" + &x,
+            );
+
+        rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Warning)
+            .set_title("Companion data mismatch")
+            .set_description(description)
+            .show();
     }
 
-    pub fn save_changes<W>(&self, output: &mut W) -> Result<(), Error>
-    where
-        W: std::io::Write,
-    {
-        Ok(self.file.write(output)?)
+    pub fn get_roster(&self, players: &[Player]) -> Option<Roster> {
+        let roster = self.save.res_gff.roster()?;
+
+        let party_tags = players
+            .iter()
+            .filter_map(|p| p.roster_tag.clone())
+            .collect::<Vec<_>>();
+
+        match Roster::new(roster, &party_tags) {
+            Ok(roster) => Some(roster),
+            Err(e) => popup_opt!("Failed to read roster.rst: {e}"),
+        }
     }
 }
 
@@ -176,6 +191,8 @@ struct App {
     pub settings: ui::SettingsState,
     pub select_file: ui::SelectFileState,
     pub save_window: ui::SaveState,
+    pub globals: ui::GlobalsState,
+    pub portraits: ui::PortraitState,
 }
 impl App {
     fn title() -> &'static str {
@@ -186,6 +203,8 @@ impl App {
         self.settings.close();
         self.select_file.close();
         self.save_window.close();
+        self.globals.close();
+        self.portraits.close();
     }
 
     fn theme(&self) -> iced::Theme {
@@ -199,6 +218,8 @@ impl App {
             settings: ui::SettingsState::from_file_or_default(),
             select_file: ui::SelectFileState::default(),
             save_window: ui::SaveState::default(),
+            globals: ui::GlobalsState::default(),
+            portraits: ui::PortraitState::default(),
         };
 
         (this, Task::none())
@@ -208,32 +229,25 @@ impl App {
         match msg {
             Message::FileSelected(path) => match open_file(&path) {
                 Ok(save) => {
-                    match self.settings.game_resources.as_mut() {
-                        Some(g) => {
-                            let save_dir =
-                                path.parent().expect("Failed to get save dir").to_path_buf();
-                            let save_file = SaveFile {
-                                file: save,
-                                path,
-                                save_dir,
-                            };
-
-                            self.characters = ui::character::State::new(
-                                save_file.get_players(&g.tlk, &mut g.file_reader),
-                            );
-                            self.save_file = Some(save_file);
-                        }
-                        None => {
-                            return show_error_popup_task(
-                                "Couldn't find game resources, is Game Directory set?".to_string(),
-                            );
-                        }
-                    };
+                    if self.settings.game_resources.is_none() {
+                        return show_error_popup_task(
+                            "Couldn't find game resources, is Game Directory set?".to_string(),
+                        );
+                    }
+
+                    let save_file = SaveFile { save };
+                    save_file.check_companions();
+
+                    self.save_file = Some(save_file);
+                    self.load_characters();
                 }
                 Err(e) => show_error_popup(format!("Failed to open save file: {e}")),
             },
             Message::Settings(m @ ui::SettingsMessage::Save) => {
                 self.settings.update(m);
+                if let Some(g) = &self.settings.game_resources {
+                    self.characters.refresh(g.rules());
+                }
             }
             Message::Settings(m) => {
                 self.settings.update(m);
@@ -258,8 +272,31 @@ impl App {
             Message::FileSelector(m) => {
                 return self.select_file.update(m);
             }
+            Message::Character(ui::CharacterMessage::ExportBic) => self.export_bic(),
+            Message::Character(ui::CharacterMessage::ImportBic) => self.import_bic(),
+            Message::Character(ui::CharacterMessage::ChangePortrait) => {
+                if self.save_file.is_some() {
+                    self.close_windows();
+                }
+
+                if let Some(save_file) = &self.save_file
+                    && let Some(g) = &self.settings.game_resources
+                {
+                    let user_dir = save_file.save.portraits_dir();
+                    return self.portraits.open(&g.portrait_record, user_dir.as_deref());
+                }
+            }
             Message::Character(msg) => {
-                self.characters.update(msg);
+                let Some(g) = &self.settings.game_resources else {
+                    return Task::none();
+                };
+
+                let player_changed = matches!(msg, ui::CharacterMessage::PlayerSelected(_));
+                self.characters.update(msg, g.rules());
+
+                if player_changed {
+                    self.load_portrait();
+                }
             }
             Message::SaveFile => {
                 if self.save_file.is_some() {
@@ -270,10 +307,34 @@ impl App {
                 }
             }
             Message::SaveWindow(msg) => {
-                if let Some(ref save_file) = self.save_file {
+                if let Some(save_file) = self.save_file.as_mut() {
                     self.save_window.update(msg, save_file);
                 }
             }
+            Message::OpenGlobals => {
+                if self.save_file.is_some() {
+                    self.close_windows();
+                }
+                if let Some(save_file) = &self.save_file {
+                    self.globals
+                        .open(save_file, self.characters.roster.as_ref());
+                }
+            }
+            Message::Globals(msg) => {
+                if let Some(save_file) = self.save_file.as_mut() {
+                    self.globals.update(msg, save_file);
+                }
+            }
+            Message::Portraits(msg) => {
+                let user_dir = self.save_file.as_ref().and_then(|x| x.save.portraits_dir());
+
+                if let Some(res_ref) = self.portraits.update(msg, user_dir.as_deref()) {
+                    if !self.characters.set_portrait(res_ref) {
+                        show_error_popup("Character has no Portrait field");
+                    }
+                    self.load_portrait();
+                }
+            }
             Message::CloseFile => {
                 let settings = std::mem::take(&mut self.settings);
 
@@ -287,13 +348,119 @@ impl App {
         Task::none()
     }
 
+    fn load_characters(&mut self) {
+        let (Some(save_file), Some(g)) = (&self.save_file, self.settings.game_resources.as_mut())
+        else {
+            return;
+        };
+
+        let players = save_file.get_players(&g.tlk, &mut g.file_reader);
+        let roster = save_file.get_roster(&players);
+        let companions = save_file.get_companions(&g.tlk, &mut g.file_reader);
+
+        self.characters = ui::character::State::new(players, companions, roster);
+        self.characters.refresh(g.rules());
+        self.load_portrait();
+    }
+
+    fn load_portrait(&mut self) {
+        let (Some(save_file), Some(g)) = (&self.save_file, &self.settings.game_resources) else {
+            return;
+        };
+
+        let user_dir = save_file.save.portraits_dir();
+        let path = self
+            .characters
+            .players
+            .get(self.characters.selected_player)
+            .and_then(|p| p.portrait.as_ref())
+            .filter(|x| !x.get().is_empty())
+            .and_then(|x| g.portrait_record.find(x.get(), user_dir.as_deref()));
+
+        self.characters.portrait =
+            path.and_then(|path| match portrait::load_portrait(&path, None) {
+                Ok(x) => Some(x),
+                Err(e) => popup_opt!("Failed to load portrait: {e}"),
+            });
+    }
+
+    fn export_bic(&mut self) {
+        let (Some(save_file), Some(index)) = (
+            &self.save_file,
+            self.characters.selected_player_list_index(),
+        ) else {
+            return;
+        };
+
+        let bic = match save_file.save.res_gff.export_player(index) {
+            Ok(bic) => bic,
+            Err(e) => return show_error_popup(format!("Failed to export character: {e}")),
+        };
+
+        let file_name = self
+            .characters
+            .players
+            .get(index)
+            .map(|p| p.first_name.get().to_lowercase())
+            .filter(|x| !x.is_empty())
+            .unwrap_or("player".to_string());
+
+        let mut dialog = rfd::FileDialog::new()
+            .add_filter("BIC", &["bic"])
+            .set_file_name(format!("{file_name}.bic"));
+        if let Some(vault) = save_file.save.local_vault_dir() {
+            dialog = dialog.set_directory(vault);
+        }
+
+        if let Some(path) = dialog.save_file()
+            && let Err(e) = nwn_lib::save::write_bic(&bic, &path)
+        {
+            show_error_popup(format!("Failed to write {}: {e}", path.display()));
+        }
+    }
+
+    fn import_bic(&mut self) {
+        let (Some(save_file), Some(index)) = (
+            self.save_file.as_mut(),
+            self.characters.selected_player_list_index(),
+        ) else {
+            return;
+        };
+
+        let mut dialog = rfd::FileDialog::new().add_filter("BIC", &["bic"]);
+        if let Some(vault) = save_file.save.local_vault_dir() {
+            dialog = dialog.set_directory(vault);
+        }
+
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+
+        let result = nwn_lib::save::read_bic(&path)
+            .and_then(|bic| save_file.save.res_gff.import_player(index, &bic));
+
+        match result {
+            Ok(()) => {
+                self.load_characters();
+                if let Some(g) = &self.settings.game_resources {
+                    let msg = ui::CharacterMessage::PlayerSelected(index);
+                    self.characters.update(msg, g.rules());
+                }
+                self.load_portrait();
+            }
+            Err(e) => show_error_popup(format!("Failed to import {}: {e}", path.display())),
+        }
+    }
+
     fn menu(&self) -> Element<'_> {
         let open_file = menu_button("Open").on_press(Message::OpenFileSelector);
         let save =
             menu_button("Save").on_press_maybe(self.save_file.as_ref().map(|_| Message::SaveFile));
+        let globals = menu_button("Globals")
+            .on_press_maybe(self.save_file.as_ref().map(|_| Message::OpenGlobals));
         let settings = menu_button("Settings").on_press(Message::OpenSettings);
 
-        let mut menu_bar = row![open_file, save, settings].spacing(8);
+        let mut menu_bar = row![open_file, save, globals, settings].spacing(8);
 
         if self.save_file.is_some() {
             menu_bar = menu_bar
@@ -317,17 +484,17 @@ impl App {
             self.settings.view().map(Message::Settings)
         } else if self.save_window.active {
             self.save_window.view().map(Message::SaveWindow)
+        } else if self.globals.active {
+            self.globals.view().map(Message::Globals)
+        } else if self.portraits.active {
+            self.portraits.view().map(Message::Portraits)
         } else if self.select_file.active {
             self.select_file.view().map(Message::FileSelector)
         } else {
             match &self.settings.game_resources {
-                Some(GameResources {
-                    spell_record,
-                    feat_record,
-                    ..
-                }) => self
+                Some(g) => self
                     .characters
-                    .view(spell_record, feat_record)
+                    .view(g.rules(), &g.icons)
                     .map(Message::Character),
                 None => text("Game Directory not set correctly").into(),
             }
@@ -0,0 +1,180 @@
+use crate::error::Error;
+use iced::widget::image::Handle;
+use nwn_model::{
+    error::Error as ModelError,
+    resources::{IconName, IconPath},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// Game portraits are named `po_*`
+pub const PORTRAIT_PREFIX: &str = "po_";
+pub const PORTRAIT_EXTENSIONS: &[&str] = &["dds", "tga"];
+const MAX_RES_REF_LEN: usize = 32;
+
+fn has_portrait_extension(path: &Path) -> bool {
+    path.extension().and_then(|x| x.to_str()).is_some_and(|x| {
+        PORTRAIT_EXTENSIONS
+            .iter()
+            .any(|e| x.eq_ignore_ascii_case(e))
+    })
+}
+
+fn read_image_error(path: &Path, e: impl std::fmt::Display) -> Error {
+    ModelError::ParseError(format!("Failed to read {}: {e}", path.display())).into()
+}
+
+/// Loads a DDS or TGA portrait. With `size`, DDS files use the smallest
+/// mip level that is at least `size` pixels wide
+pub fn load_portrait(path: &Path, size: Option<u32>) -> Result<Handle, Error> {
+    let is_dds = path
+        .extension()
+        .is_some_and(|x| x.eq_ignore_ascii_case("dds"));
+
+    if is_dds {
+        let reader = BufReader::new(File::open(path)?);
+        let dds = dds::Dds::read(reader).map_err(|e| read_image_error(path, e))?;
+
+        let surface = size
+            .and_then(|size| dds.mip_for_size(size, size))
+            .or(dds.surfaces.first())
+            .ok_or_else(|| read_image_error(path, "no images"))?;
+
+        let pixels = surface
+            .pixels
+            .iter()
+            .flat_map(|dds::Rgba { r, g, b, a }| [*r, *g, *b, *a])
+            .collect::<Vec<_>>();
+
+        return Ok(Handle::from_rgba(surface.width, surface.height, pixels));
+    }
+
+    let image = image::open(path).map_err(|e| read_image_error(path, e))?;
+    let image = match size {
+        Some(size) => image.thumbnail(size, size),
+        None => image,
+    }
+    .to_rgba8();
+
+    Ok(Handle::from_rgba(
+        image.width(),
+        image.height(),
+        image.into_vec(),
+    ))
+}
+
+/// ResRef for an imported image, `po_` followed by the file name
+fn portrait_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|x| x.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let stem = stem
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect::<String>();
+
+    let stem = stem.strip_prefix(PORTRAIT_PREFIX).unwrap_or(&stem);
+
+    format!("{PORTRAIT_PREFIX}{stem}")
+        .chars()
+        .take(MAX_RES_REF_LEN)
+        .collect()
+}
+
+/// Converts `image` to a DDS in `dir`, returns its ResRef. Fails rather than replace a
+/// portrait of the same ResRef in `dir`.
+pub fn import_portrait(image: &Path, dir: &Path) -> Result<String, Error> {
+    let rgba = image::open(image)
+        .map_err(|e| read_image_error(image, e))?
+        .to_rgba8();
+
+    let (width, height) = rgba.dimensions();
+    let pixels = rgba
+        .pixels()
+        .map(|image::Rgba([r, g, b, a])| dds::Rgba {
+            r: *r,
+            g: *g,
+            b: *b,
+            a: *a,
+        })
+        .collect();
+
+    let dds = dds::Dds::new(width, height, pixels, dds::Format::Bc3, true)?;
+
+    let name = portrait_name(image);
+    let conflict = |path: &Path| -> Error {
+        ModelError::WriteError(format!("{} already exists", path.display())).into()
+    };
+
+    // The game finds portraits by ResRef, a TGA of the name is the same portrait
+    std::fs::create_dir_all(dir)?;
+    if let Some((_, existing)) = portrait_files(dir).find(|(x, _)| *x == name) {
+        return Err(conflict(&existing));
+    }
+
+    let path = dir.join(format!("{name}.dds"));
+    let file = File::create_new(&path).map_err(|e| match e.kind() {
+        ErrorKind::AlreadyExists => conflict(&path),
+        _ => e.into(),
+    })?;
+    dds.write(BufWriter::new(file))?;
+
+    Ok(name)
+}
+
+fn portrait_files(dir: &Path) -> impl Iterator<Item = (String, PathBuf)> {
+    dir.read_dir()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|x| x.path())
+        .filter(|x| x.is_file() && has_portrait_extension(x))
+        .filter_map(|x| Some((x.file_stem()?.to_str()?.to_lowercase(), x)))
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PortraitRecord {
+    /// Game portraits by lowercase ResRef
+    pub portraits: BTreeMap<String, PathBuf>,
+}
+impl PortraitRecord {
+    pub fn new(icon_paths: &HashMap<IconName, IconPath>) -> Self {
+        let portraits = icon_paths
+            .iter()
+            .filter(|(name, path)| {
+                name.to_lowercase().starts_with(PORTRAIT_PREFIX) && has_portrait_extension(path)
+            })
+            .map(|(name, path)| (name.to_lowercase(), path.clone()))
+            .collect();
+
+        Self { portraits }
+    }
+
+    /// Game portraits and those in the user's `portraits` folder,
+    /// user portraits replace game portraits with the same name
+    pub fn all(&self, user_dir: Option<&Path>) -> BTreeMap<String, PathBuf> {
+        let mut portraits = self.portraits.clone();
+        portraits.extend(user_dir.into_iter().flat_map(portrait_files));
+        portraits
+    }
+
+    pub fn find(&self, res_ref: &str, user_dir: Option<&Path>) -> Option<PathBuf> {
+        let res_ref = res_ref.to_lowercase();
+
+        user_dir
+            .into_iter()
+            .flat_map(portrait_files)
+            .find(|(name, _)| *name == res_ref)
+            .map(|(_, path)| path)
+            .or_else(|| self.portraits.get(&res_ref).cloned())
+    }
+}
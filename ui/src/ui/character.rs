@@ -1,11 +1,33 @@
+#![allow(unstable_name_collisions)]
+
+mod class_options_panel;
 mod feat_panel;
+mod history_panel;
+mod inventory_panel;
+mod skill_panel;
 mod spell_panel;
 
-use iced::widget::{column, text, vertical_space};
+use iced::widget::{
+    Column, Image, button, checkbox, column, combo_box, horizontal_rule, image::Handle, row,
+    scrollable, text, vertical_space,
+};
 use iced_aw::{TabLabel, grid, grid_row, tabs::Tabs};
-use nwn_lib::files::gff::field::Field;
+use itertools::Itertools;
+use nwn_lib::files::{gff::field::Field, res_ref::ResRef};
+
+use nwn_model::{
+    class::Saves,
+    field_ref::FieldRef,
+    player::{
+        Player,
+        inventory::next_object_id,
+        legality::{Area, Finding, Rules, Severity},
+        stats::DerivedStats,
+    },
+    roster::{Roster, RosterFlag, RosterMember},
+};
 
-use crate::{feat::FeatRecord, field_ref::FieldRef, player::Player, spell::SpellRecord};
+use crate::icons::IconCache;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Stat {
@@ -20,8 +42,27 @@ pub enum Stat {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Message {
     TabSelected(TabMode),
-    StatChanged { stat: Stat, new_value: u8 },
+    PlayerSelected(usize),
+    /// Handled by the app, needs the save file
+    ExportBic,
+    /// Handled by the app, needs the save file
+    ImportBic,
+    /// Handled by the app, opens the portrait gallery
+    ChangePortrait,
+    ValidatePressed,
+    RosterFlagChanged {
+        flag: RosterFlag,
+        value: bool,
+    },
+    StatChanged {
+        stat: Stat,
+        new_value: u8,
+    },
+    ClassOptionsPanel(class_options_panel::Message),
     FeatPanel(feat_panel::Message),
+    HistoryPanel(history_panel::Message),
+    InventoryPanel(inventory_panel::Message),
+    SkillPanel(skill_panel::Message),
     SpellPanel(spell_panel::Message),
 }
 
@@ -33,35 +74,189 @@ pub enum TabMode {
     Stats,
     Spells,
     Feats,
+    Skills,
+    History,
+    ClassOptions,
+    Inventory,
+    Roster,
+    Report,
+}
+
+#[derive(Debug, Default, Clone)]
+struct PlayerOption {
+    index: usize,
+    name: String,
+}
+impl std::fmt::Display for PlayerOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+fn make_spell_panel(player: &Player) -> Option<spell_panel::State> {
+    player
+        .classes
+        .iter()
+        .any(|x| x.is_caster)
+        .then(|| spell_panel::State::new(player))
 }
 
 #[derive(Default)]
 pub struct State {
     pub selected_player: usize,
+    /// `Mod_PlayerList` entries followed by companions
     pub players: Vec<Player>,
+    pub roster: Option<Roster>,
+    player_list_len: usize,
     pub tab_mode: TabMode,
+    /// Portrait of the selected player, loaded by the app
+    pub portrait: Option<Handle>,
 
+    player_options: combo_box::State<PlayerOption>,
+    class_options_panel: class_options_panel::State,
     feat_panel: feat_panel::State,
-    spell_panel: spell_panel::State,
+    history_panel: history_panel::State,
+    inventory_panel: inventory_panel::State,
+    skill_panel: skill_panel::State,
+    spell_panel: Option<spell_panel::State>,
+    /// Findings of the last validation of the selected player
+    report: Option<Vec<Finding>>,
+    /// Derived statistics of the selected player, see [`State::refresh`]
+    derived: DerivedStats,
 }
 impl State {
-    pub fn new(players: Vec<Player>) -> Self {
-        let spell_panel = spell_panel::State::new(&players[0]);
+    pub fn new(players: Vec<Player>, companions: Vec<Player>, roster: Option<Roster>) -> Self {
+        let player_list_len = players.len();
+        let players = players.into_iter().chain(companions).collect::<Vec<_>>();
+
+        let player_options = combo_box::State::new(
+            players
+                .iter()
+                .enumerate()
+                .map(|(index, player)| {
+                    let name = format!("{} {}", player.first_name.get(), player.last_name.get());
+                    PlayerOption {
+                        index,
+                        name: name.trim().to_string(),
+                    }
+                })
+                .collect(),
+        );
+
+        let spell_panel = players.first().and_then(make_spell_panel);
 
         Self {
             tab_mode: TabMode::Stats,
             selected_player: 0,
             players,
+            roster,
+            player_list_len,
+            portrait: None,
+            player_options,
+            class_options_panel: Default::default(),
             feat_panel: Default::default(),
+            history_panel: Default::default(),
+            inventory_panel: Default::default(),
+            skill_panel: Default::default(),
             spell_panel,
+            report: None,
+            derived: Default::default(),
         }
     }
 
-    pub fn update(&mut self, msg: Message) {
+    /// Recomputes the derived statistics of the selected player, after it or the rules change
+    pub fn refresh(&mut self, rules: Rules<'_>) {
+        self.derived = match self.players.get(self.selected_player) {
+            Some(player) => player.derived_stats(rules.classes, rules.skills, rules.feats),
+            None => Default::default(),
+        };
+    }
+
+    /// Index into `Mod_PlayerList` of the selected player
+    pub fn selected_player_list_index(&self) -> Option<usize> {
+        (self.selected_player < self.player_list_len).then_some(self.selected_player)
+    }
+
+    /// Sets the selected player's `Portrait`, returns `false` if they don't have one
+    pub fn set_portrait(&mut self, res_ref: String) -> bool {
+        let portrait = self
+            .players
+            .get_mut(self.selected_player)
+            .and_then(|p| p.portrait.as_mut());
+
+        match portrait {
+            Some(portrait) => {
+                portrait.set(res_ref, |x| Field::ResRef(ResRef(x.clone())));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn roster_member(&self, player: &Player) -> Option<&RosterMember> {
+        let roster = self.roster.as_ref()?;
+        let index = roster.find(player.roster_tag.as_deref()?)?;
+        roster.members.get(index)
+    }
+
+    /// Tab that edits what a finding is about
+    fn finding_tab(&self, area: Area, has_class_options: bool) -> TabMode {
+        match area {
+            Area::Abilities | Area::Alignment => TabMode::Stats,
+            Area::Classes => TabMode::History,
+            Area::ClassOptions if has_class_options => TabMode::ClassOptions,
+            Area::ClassOptions => TabMode::History,
+            Area::Skills => TabMode::Skills,
+            Area::Feats => TabMode::Feats,
+            Area::Spells if self.spell_panel.is_some() => TabMode::Spells,
+            Area::Spells => TabMode::History,
+            Area::Items => TabMode::Inventory,
+        }
+    }
+
+    pub fn update(&mut self, msg: Message, rules: Rules<'_>) {
+        self.apply(msg, rules);
+        self.refresh(rules);
+    }
+
+    fn apply(&mut self, msg: Message, rules: Rules<'_>) {
         match msg {
             Message::TabSelected(mode) => {
                 self.tab_mode = mode;
             }
+            Message::PlayerSelected(i) => {
+                let Some(player) = self.players.get(i) else {
+                    return;
+                };
+
+                self.selected_player = i;
+                self.tab_mode = TabMode::Stats;
+                self.feat_panel = Default::default();
+                self.history_panel = Default::default();
+                self.inventory_panel = Default::default();
+                self.spell_panel = make_spell_panel(player);
+                self.report = None;
+            }
+            Message::ExportBic | Message::ImportBic | Message::ChangePortrait => {}
+            Message::ValidatePressed => {
+                if let Some(player) = self.players.get(self.selected_player) {
+                    self.report = Some(player.validate(rules));
+                    self.tab_mode = TabMode::Report;
+                }
+            }
+            Message::RosterFlagChanged { flag, value } => {
+                let tag = self
+                    .players
+                    .get(self.selected_player)
+                    .and_then(|p| p.roster_tag.as_deref());
+
+                if let Some(tag) = tag
+                    && let Some(roster) = self.roster.as_mut()
+                    && let Some(index) = roster.find(tag)
+                {
+                    roster.members[index].set_flag(flag, value);
+                }
+            }
             Message::StatChanged { stat, new_value } => {
                 let player = self.players.get_mut(self.selected_player);
                 let player = match player {
@@ -82,12 +277,94 @@ impl State {
                     Stat::Charisma => set_stat(&mut player.attributes.cha),
                 }
             }
-            Message::FeatPanel(m) => self.feat_panel.update(&mut self.players[0], m),
-            Message::SpellPanel(m) => self.spell_panel.update(&mut self.players[0], m),
+            Message::ClassOptionsPanel(m) => {
+                if let Some(player) = self.players.get_mut(self.selected_player) {
+                    self.class_options_panel.update(player, rules, m);
+                }
+            }
+            Message::FeatPanel(m) => {
+                if let Some(player) = self.players.get_mut(self.selected_player) {
+                    self.feat_panel.update(player, m);
+                }
+            }
+            Message::HistoryPanel(m) => {
+                if let Some(player) = self.players.get_mut(self.selected_player) {
+                    let class_count = player.classes.len();
+                    self.history_panel.update(player, rules, m);
+
+                    // Spell panel class options are indices into `classes`
+                    if player.classes.len() != class_count {
+                        self.spell_panel = make_spell_panel(player);
+                    }
+                }
+            }
+            Message::InventoryPanel(m) => {
+                // Copies of items need ids no character of the save uses
+                let next_object_id = next_object_id(&self.players);
+                if let Some(player) = self.players.get_mut(self.selected_player) {
+                    self.inventory_panel
+                        .update(player, rules, next_object_id, m);
+                }
+            }
+            Message::SkillPanel(m) => {
+                if let Some(player) = self.players.get_mut(self.selected_player) {
+                    self.skill_panel.update(player, rules.skills, m);
+                }
+            }
+            Message::SpellPanel(m) => {
+                if let Some(player) = self.players.get_mut(self.selected_player)
+                    && let Some(spell_panel) = self.spell_panel.as_mut()
+                {
+                    spell_panel.update(player, rules, m);
+                }
+            }
         }
     }
 
-    fn view_stats(&self, player: &Player) -> Element<'_> {
+    /// Stored values next to the derived ones, mismatches in the danger color
+    fn view_derived_stats<'a>(player: &'a Player, derived: &DerivedStats) -> Element<'a> {
+        let stat_row = |name, stored: Option<i16>, derived: i16| {
+            let stored_text = match stored {
+                Some(x) => text(x.to_string()),
+                None => text("-"),
+            };
+            let derived_text = text(derived.to_string());
+
+            let derived_text = match stored {
+                Some(x) if x != derived => derived_text.style(text::danger),
+                _ => derived_text,
+            };
+
+            grid_row![text(name), stored_text, derived_text]
+        };
+
+        let stored_saves = player.stored_saves();
+        let save = |f: fn(&Saves) -> i8| stored_saves.as_ref().map(|x| f(x).into());
+        let hit_points = player.hit_points.max.as_ref().map(|x| *x.get());
+        let armor_class = player.armor_class.as_ref().map(|x| *x.get());
+
+        let bab = player.base_attack_bonus.as_ref().map(|x| (*x.get()).into());
+
+        let grid = grid![
+            grid_row![text(""), text("Stored"), text("Derived")],
+            stat_row("Base attack bonus", bab, derived.base_attack_bonus.into()),
+            stat_row("Fortitude", save(|x| x.fort), derived.saves.fort.into()),
+            stat_row("Reflex", save(|x| x.reflex), derived.saves.reflex.into()),
+            stat_row("Will", save(|x| x.will), derived.saves.will.into()),
+            stat_row("Max hit points", hit_points, derived.max_hit_points),
+            // Stored armor class counts equipment
+            grid_row![
+                text("Armor class"),
+                text(armor_class.map(|x| x.to_string()).unwrap_or("-".into())),
+                text(format!("{} without equipment", derived.armor_class)),
+            ],
+        ]
+        .column_spacing(16);
+
+        column![text("Derived statistics"), grid].spacing(8).into()
+    }
+
+    fn view_stats<'a>(&'a self, player: &'a Player) -> Element<'a> {
         let level = player
             .classes
             .iter()
@@ -103,6 +380,8 @@ impl State {
         let race = player.race.to_string();
         let name = format!("{} {}", player.first_name.get(), player.last_name.get());
 
+        let derived = &self.derived;
+
         let stat_row = |name, value, stat| {
             let input = iced_aw::number_input(value, ..=u8::MAX, move |x| Message::StatChanged {
                 stat,
@@ -110,7 +389,8 @@ impl State {
             })
             .ignore_buttons(true);
 
-            grid_row![text(name), input]
+            let modifier = derived.ability_modifiers[stat as usize];
+            grid_row![text(name), input, text(format!("{modifier:+}"))]
         };
 
         let strength = player.attributes.str.get();
@@ -130,22 +410,118 @@ impl State {
         ]
         .column_spacing(16);
 
-        column![
+        let portrait: Element = match &self.portrait {
+            Some(handle) => Image::<Handle>::new(handle.clone())
+                .width(128)
+                .height(128)
+                .into(),
+            None => text("No portrait").into(),
+        };
+        let portrait_name = player.portrait.as_ref().map(|x| x.get().as_str());
+
+        let portrait = column![
+            portrait,
+            text(portrait_name.unwrap_or_default()).size(12),
+            button("Change Portrait")
+                .on_press_maybe(portrait_name.map(|_| Message::ChangePortrait)),
+        ]
+        .spacing(4);
+
+        let info = column![
             text(name),
             text(format!("Level {level} {race}")),
             text(classes),
             vertical_space().height(32),
             stat_grid,
+            vertical_space().height(32),
+            Self::view_derived_stats(player, derived),
+        ];
+
+        row![info, portrait].spacing(32).padding(16).into()
+    }
+
+    fn view_report<'a>(&self, findings: &'a [Finding], has_class_options: bool) -> Element<'a> {
+        let errors = findings
+            .iter()
+            .filter(|x| x.severity == Severity::Error)
+            .count();
+        let warnings = findings.len() - errors;
+
+        let summary = match findings.is_empty() {
+            true => text("The character follows every rule the editor checks").style(text::success),
+            false => text(format!("{errors} errors, {warnings} warnings")),
+        };
+
+        let header = row![
+            summary,
+            button("Validate again").on_press(Message::ValidatePressed),
         ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let rows = findings.iter().map(|finding| {
+            let severity = match finding.severity {
+                Severity::Error => text("Error").style(text::danger),
+                Severity::Warning => text("Warning"),
+            };
+            let tab = self.finding_tab(finding.area(), has_class_options);
+
+            row![
+                severity.width(80),
+                text(&finding.message).width(iced::Length::Fill),
+                button(text(format!("Go to {tab:?}")).size(12))
+                    .padding([2, 8])
+                    .on_press(Message::TabSelected(tab)),
+            ]
+            .spacing(16)
+            .align_y(iced::Alignment::Center)
+            .into()
+        });
+
+        let rows =
+            Column::from_iter(rows.intersperse_with(|| horizontal_rule(1).into())).spacing(8);
+
+        column![header, scrollable(rows)]
+            .spacing(16)
+            .padding(16)
+            .into()
+    }
+
+    fn view_roster<'a>(&self, member: &'a RosterMember) -> Element<'a> {
+        let flag_checkbox = |label, flag| {
+            checkbox(label, member.flag(flag))
+                .on_toggle(move |value| Message::RosterFlagChanged { flag, value })
+        };
+
+        let in_party = if member.in_party {
+            "In party"
+        } else {
+            "Not in party"
+        };
+
+        column![
+            text(format!("Roster name: {}", member.name)),
+            text(in_party),
+            vertical_space().height(16),
+            flag_checkbox("Available", RosterFlag::Available),
+            flag_checkbox("Selectable", RosterFlag::Selectable),
+            flag_checkbox("Campaign NPC", RosterFlag::CampaignNpc),
+            flag_checkbox("Required", RosterFlag::Required),
+        ]
+        .spacing(8)
         .padding(16)
         .into()
     }
 
-    pub fn view<'a>(
-        &'a self,
-        spell_record: &'a SpellRecord,
-        feat_record: &'a FeatRecord,
-    ) -> Element<'a> {
+    pub fn view<'a>(&'a self, rules: Rules<'a>, icons: &'a IconCache) -> Element<'a> {
+        let Rules {
+            spells: spell_record,
+            feats: feat_record,
+            skills: skill_record,
+            classes: class_record,
+            ..
+        } = rules;
+
         let player = match self.players.get(self.selected_player) {
             Some(player) => player,
             None => return iced::widget::vertical_space().into(),
@@ -163,20 +539,83 @@ impl State {
                 TabMode::Feats,
                 TabLabel::Text("Feats".to_string()),
                 self.feat_panel
-                    .view(player, feat_record)
+                    .view(player, feat_record, class_record, skill_record, icons)
                     .map(Message::FeatPanel),
+            )
+            .push(
+                TabMode::Skills,
+                TabLabel::Text("Skills".to_string()),
+                self.skill_panel
+                    .view(player, skill_record, &self.derived.skill_totals, icons)
+                    .map(Message::SkillPanel),
+            )
+            .push(
+                TabMode::History,
+                TabLabel::Text("History".to_string()),
+                self.history_panel
+                    .view(player, rules, icons)
+                    .map(Message::HistoryPanel),
             );
 
-        if is_caster {
+        if is_caster && let Some(spell_panel) = &self.spell_panel {
             tabs = tabs.push(
                 TabMode::Spells,
                 TabLabel::Text("Spells".to_string()),
-                self.spell_panel
-                    .view(player, spell_record)
+                spell_panel
+                    .view(player, spell_record, class_record, icons)
                     .map(Message::SpellPanel),
             )
         }
 
-        tabs.set_active_tab(&self.tab_mode).into()
+        let has_class_options = class_options_panel::has_options(player, class_record);
+        if has_class_options {
+            tabs = tabs.push(
+                TabMode::ClassOptions,
+                TabLabel::Text("Class options".to_string()),
+                self.class_options_panel
+                    .view(player, rules)
+                    .map(Message::ClassOptionsPanel),
+            )
+        }
+
+        tabs = tabs.push(
+            TabMode::Inventory,
+            TabLabel::Text("Inventory".to_string()),
+            self.inventory_panel
+                .view(player, rules, icons)
+                .map(Message::InventoryPanel),
+        );
+
+        if let Some(member) = self.roster_member(player) {
+            tabs = tabs.push(
+                TabMode::Roster,
+                TabLabel::Text("Roster".to_string()),
+                self.view_roster(member),
+            )
+        }
+
+        if let Some(findings) = &self.report {
+            tabs = tabs.push(
+                TabMode::Report,
+                TabLabel::Text("Report".to_string()),
+                self.view_report(findings, has_class_options),
+            )
+        }
+
+        let selected = self.player_options.options().get(self.selected_player);
+        let player_select = combo_box(&self.player_options, "Select character", selected, |x| {
+            Message::PlayerSelected(x.index)
+        });
+
+        let bic_message = |msg| self.selected_player_list_index().map(|_| msg);
+        let header = row![
+            player_select,
+            button("Export BIC").on_press_maybe(bic_message(Message::ExportBic)),
+            button("Import BIC").on_press_maybe(bic_message(Message::ImportBic)),
+            button("Validate character").on_press(Message::ValidatePressed),
+        ]
+        .spacing(8);
+
+        column![header, tabs.set_active_tab(&self.tab_mode)].into()
     }
 }
@@ -0,0 +1,234 @@
+#![allow(unstable_name_collisions)]
+
+use iced::{
+    Alignment,
+    widget::{Column, column, horizontal_rule, pick_list, row, scrollable, text},
+};
+use itertools::Itertools;
+use nwn_model::{
+    class::ClassRecord,
+    ids::class::Class,
+    player::{Player, PlayerClass, legality::Rules},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    DomainChanged {
+        class_index: usize,
+        slot: usize,
+        domain: u8,
+    },
+    SchoolChanged {
+        class_index: usize,
+        school: u8,
+    },
+    FavoredEnemySwapped {
+        old: u8,
+        new: u8,
+    },
+}
+
+pub type Element<'a> = iced::Element<'a, Message>;
+
+/// A row of a 2DA in a pick list
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Choice {
+    id: u8,
+    name: String,
+}
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Rows of a 2DA sorted by name
+fn choices<'a>(rows: impl Iterator<Item = (&'a u8, &'a String)>) -> Vec<Choice> {
+    rows.map(|(id, name)| Choice {
+        id: *id,
+        name: name.clone(),
+    })
+    .sorted_by(|a, b| a.name.cmp(&b.name))
+    .collect()
+}
+
+fn has_domains(class: &PlayerClass, class_record: &ClassRecord) -> bool {
+    class.domains.iter().any(Option::is_some)
+        || class_record
+            .classes
+            .get(class.class.get())
+            .is_some_and(|x| x.has_domains)
+}
+
+fn has_school(class: &PlayerClass) -> bool {
+    class.school.is_some() || *class.class.get() == Class::Wizard
+}
+
+/// Whether any of the player's classes has choices the panel edits
+pub fn has_options(player: &Player, class_record: &ClassRecord) -> bool {
+    player.classes.iter().any(|x| {
+        has_domains(x, class_record)
+            || has_school(x)
+            || matches!(*x.class.get(), Class::Ranger | Class::Warlock)
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct State;
+impl State {
+    pub fn update(&mut self, player: &mut Player, rules: Rules<'_>, msg: Message) {
+        let result = match msg {
+            Message::DomainChanged {
+                class_index,
+                slot,
+                domain,
+            } => player.set_domain(class_index, slot, domain, rules.domains, rules.spells),
+            Message::SchoolChanged {
+                class_index,
+                school,
+            } => player.set_school(class_index, school, rules.schools),
+            Message::FavoredEnemySwapped { old, new } => {
+                player.swap_favored_enemy(old, new, rules.races)
+            }
+        };
+
+        if let Err(e) = result {
+            crate::show_error_popup(format!("Can't change class option: {e}"));
+        }
+    }
+
+    fn view_domains<'a>(class_index: usize, class: &PlayerClass, rules: Rules<'a>) -> Element<'a> {
+        let options = choices(rules.domains.domains.iter().map(|(id, x)| (id, &x.name)));
+
+        let lists = (0..2).map(|slot| {
+            let selected = class.domains[slot].as_ref().map(|x| Choice {
+                id: *x.get(),
+                name: rules.domains.name(*x.get()),
+            });
+
+            pick_list(options.clone(), selected, move |x| Message::DomainChanged {
+                class_index,
+                slot,
+                domain: x.id,
+            })
+            .placeholder("No domain")
+            .into()
+        });
+
+        row![text("Domains").width(120)]
+            .extend(lists)
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .into()
+    }
+
+    fn view_school<'a>(class_index: usize, class: &PlayerClass, rules: Rules<'a>) -> Element<'a> {
+        let options = choices(rules.schools.schools.iter().map(|(id, x)| (id, &x.name)));
+        let school = class.school.as_ref().map(|x| *x.get());
+
+        let selected = school.map(|id| Choice {
+            id,
+            name: rules.schools.name(id),
+        });
+        let list = pick_list(options, selected, move |x| Message::SchoolChanged {
+            class_index,
+            school: x.id,
+        })
+        .placeholder("No school");
+
+        let opposition = school
+            .and_then(|x| rules.schools.schools.get(&x))
+            .and_then(|x| x.opposition)
+            .map(|x| format!("Opposed school: {}", rules.schools.name(x)));
+
+        row![
+            text("School").width(120),
+            list,
+            text(opposition.unwrap_or_default())
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    fn view_favored_enemies<'a>(player: &Player, rules: Rules<'a>) -> Element<'a> {
+        let enemies = player.favored_enemies(rules.races);
+        let options = choices(
+            rules
+                .races
+                .races
+                .iter()
+                .filter(|(id, x)| x.favored_enemy_feat.is_some() && !enemies.contains(id))
+                .map(|(id, x)| (id, &x.name)),
+        );
+
+        let lists = enemies.iter().map(|&old| {
+            let selected = Choice {
+                id: old,
+                name: rules.races.name(old),
+            };
+
+            pick_list(options.clone(), Some(selected), move |x| {
+                Message::FavoredEnemySwapped { old, new: x.id }
+            })
+            .into()
+        });
+
+        let lists: Element = match enemies.is_empty() {
+            true => text("None").into(),
+            false => Column::from_iter(lists).spacing(4).into(),
+        };
+
+        row![text("Favored enemies").width(120), lists]
+            .spacing(8)
+            .into()
+    }
+
+    fn view_class<'a>(
+        player: &Player,
+        class_index: usize,
+        class: &PlayerClass,
+        rules: Rules<'a>,
+    ) -> Option<Element<'a>> {
+        let mut items = vec![];
+
+        if has_domains(class, rules.classes) {
+            items.push(Self::view_domains(class_index, class, rules));
+        }
+        if has_school(class) {
+            items.push(Self::view_school(class_index, class, rules));
+        }
+        match *class.class.get() {
+            Class::Ranger => items.push(Self::view_favored_enemies(player, rules)),
+            Class::Warlock => {
+                let grade = player
+                    .invocation_grade()
+                    .map(|x| x.to_string())
+                    .unwrap_or("None".into());
+                items.push(text(format!("Invocations up to the {grade} grade")).into())
+            }
+            _ => {}
+        }
+
+        if items.is_empty() {
+            return None;
+        }
+
+        let header = text(format!("{} {}", class.class.get(), class.level.get())).size(20);
+        Some(column![header].extend(items).spacing(8).into())
+    }
+
+    pub fn view<'a>(&self, player: &'a Player, rules: Rules<'a>) -> Element<'a> {
+        let classes = player
+            .classes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, class)| Self::view_class(player, i, class, rules));
+
+        let classes = Column::from_iter(classes.intersperse_with(|| horizontal_rule(1).into()))
+            .spacing(16)
+            .padding(16);
+
+        scrollable(classes).into()
+    }
+}
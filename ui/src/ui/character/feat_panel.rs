@@ -1,18 +1,23 @@
 #![allow(unstable_name_collisions)]
 
 use crate::{
-    feat::{Feat, FeatRecord},
-    player::Player,
+    icons::IconCache,
     ui::{HoverableEvent, HoverableState, hoverable, search_window},
 };
 use iced::{
     Length,
     widget::{
-        Column, Image, button, column, container, horizontal_rule, horizontal_space, row,
-        scrollable, text,
+        Column, Image, button, column, container, horizontal_rule, horizontal_space, image::Handle,
+        row, scrollable, text,
     },
 };
 use itertools::Itertools;
+use nwn_model::{
+    class::ClassRecord,
+    feat::{Feat, FeatRecord},
+    player::Player,
+    skill::SkillRecord,
+};
 
 fn bordered_container<'a>(content: impl Into<Element<'a>>) -> iced::widget::Container<'a, Message> {
     fn style(theme: &iced::Theme) -> container::Style {
@@ -74,8 +79,13 @@ impl State {
         }
     }
 
-    fn view_feat<'a>(&'a self, index: usize, feat: &'a Feat) -> Element<'a> {
-        let icon: Element<'_> = match &feat.icon {
+    fn view_feat<'a>(
+        &'a self,
+        index: usize,
+        feat: &'a Feat,
+        icon: Option<&'a Handle>,
+    ) -> Element<'a> {
+        let icon: Element<'_> = match icon {
             Some(icon) => Image::new(icon).into(),
             None => horizontal_space().width(40).into(),
         };
@@ -122,6 +132,7 @@ impl State {
         &'a self,
         player: &'a Player,
         feat_record: &'a FeatRecord,
+        icons: &'a IconCache,
     ) -> impl Into<Element<'a>> {
         let feats = {
             let feats = player.feats.list_ref.get();
@@ -130,10 +141,10 @@ impl State {
                 .map(|x| x.get())
                 .filter_map(|x| {
                     let id: usize = (*x).into();
-                    feat_record.feats.get(&id)
+                    Some((feat_record.feats.get(&id)?, icons.feats.get(&id)))
                 })
                 .enumerate()
-                .map(|(i, feat)| self.view_feat(i, feat))
+                .map(|(i, (feat, icon))| self.view_feat(i, feat, icon))
                 .intersperse_with(|| horizontal_rule(1).into());
             bordered_container(Column::from_iter(feats))
         };
@@ -142,13 +153,78 @@ impl State {
         column![feats, self.button_bar()].padding(8.0)
     }
 
-    pub fn view<'a>(&'a self, player: &'a Player, feat_record: &'a FeatRecord) -> Element<'a> {
+    /// Feats the character has without meeting their requirements
+    fn view_illegal_feats<'a>(
+        &self,
+        player: &'a Player,
+        feat_record: &'a FeatRecord,
+        class_record: &'a ClassRecord,
+        skill_record: &'a SkillRecord,
+    ) -> Option<Element<'a>> {
+        let illegal = player.illegal_feats(feat_record, class_record);
+        if illegal.is_empty() {
+            return None;
+        }
+
+        let lines = illegal.iter().map(|(id, unmet)| {
+            let name = feat_record
+                .feats
+                .get(id)
+                .map(|x| x.name.data.as_str())
+                .unwrap_or_default();
+            let unmet = unmet
+                .iter()
+                .map(|x| x.describe(feat_record, skill_record))
+                .join(", ");
+
+            text(format!("{name}: requires {unmet}"))
+                .style(text::danger)
+                .into()
+        });
+
+        let content = column![text("Feats held without their requirements")]
+            .extend(lines)
+            .spacing(4);
+
+        Some(
+            container(bordered_container(content.padding(8)).width(Length::Fill))
+                .padding([0, 32])
+                .into(),
+        )
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        player: &'a Player,
+        feat_record: &'a FeatRecord,
+        class_record: &'a ClassRecord,
+        skill_record: &'a SkillRecord,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
         if self.search_window.is_active() {
+            let kind = search_window::SearchKind::Feats {
+                feat_record,
+                check: Some(search_window::FeatCheck {
+                    player,
+                    class_record,
+                    skill_record,
+                }),
+            };
+
             self.search_window
-                .view(search_window::SearchKind::Feats(feat_record))
+                .view(kind, icons)
                 .map(Message::SearchWindow)
         } else {
-            self.view_feats(player, feat_record).into()
+            column![]
+                .push_maybe(self.view_illegal_feats(
+                    player,
+                    feat_record,
+                    class_record,
+                    skill_record,
+                ))
+                .push(self.view_feats(player, feat_record, icons))
+                .padding([16, 0])
+                .into()
         }
     }
 }
@@ -0,0 +1,411 @@
+use crate::{
+    icons::IconCache,
+    ui::{bordered, search_window},
+};
+use iced::{
+    Length,
+    widget::{Column, button, column, pick_list, row, scrollable, text},
+};
+use itertools::Itertools;
+use nwn_model::{
+    class::ClassRecord,
+    ids::{class::Class, spell::Spell as SpellId},
+    player::{
+        ABILITY_NAMES, Player,
+        legality::{FeatChoices, Rules},
+        level_history::LevelEntry,
+        level_up::raises_ability,
+        total_level,
+    },
+};
+
+/// Index into `ABILITY_NAMES`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbilityOption(u8);
+impl std::fmt::Display for AbilityOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(ABILITY_NAMES.get(self.0 as usize).copied().unwrap_or("?"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    NewClassSelected(Class),
+    NewAbilitySelected(AbilityOption),
+    AddLevelPressed,
+    RemoveLevelPressed,
+    HitDieChanged {
+        level: usize,
+        roll: u8,
+    },
+    SkillPointsChanged {
+        level: usize,
+        points: u16,
+    },
+    AddFeatPressed(usize),
+    RemoveFeatPressed {
+        level: usize,
+        index: usize,
+    },
+    AddSpellPressed {
+        level: usize,
+        spell_level: usize,
+    },
+    RemoveSpellPressed {
+        level: usize,
+        spell_level: usize,
+        index: usize,
+    },
+    SearchWindow(search_window::Message),
+}
+
+pub type Element<'a> = iced::Element<'a, Message>;
+
+/// What the search window is picking for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddTarget {
+    Feat { level: usize },
+    Spell { level: usize, spell_level: usize },
+}
+
+#[derive(Default)]
+pub struct State {
+    new_class: Option<Class>,
+    new_ability: Option<AbilityOption>,
+    add_target: Option<AddTarget>,
+    /// Feats a new level still gives to choose, the search window reopens until they're picked
+    feats_to_choose: u8,
+    search_window: search_window::State,
+}
+impl State {
+    fn open_search(&mut self, target: AddTarget) {
+        self.add_target = Some(target);
+        self.search_window.open(search_window::SearchMode::Add);
+    }
+
+    fn add_selected(&self, player: &mut Player, id: usize) -> Result<(), nwn_model::error::Error> {
+        match self.add_target {
+            Some(AddTarget::Feat { level }) => player.add_level_feat(level, id as u16),
+            Some(AddTarget::Spell { level, spell_level }) => {
+                player.add_level_spell(level, spell_level, SpellId(id as u16))
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn update(&mut self, player: &mut Player, rules: Rules<'_>, msg: Message) {
+        let result = match msg {
+            Message::NewClassSelected(class) => {
+                self.new_class = Some(class);
+                Ok(())
+            }
+            Message::NewAbilitySelected(ability) => {
+                self.new_ability = Some(ability);
+                Ok(())
+            }
+            Message::AddLevelPressed => match self.new_class {
+                Some(class) => {
+                    let ability = self.new_ability.map(|x| x.0);
+                    let result = player.add_class_level(rules.classes, rules.races, class, ability);
+                    result.map(|feats| {
+                        self.new_ability = None;
+                        self.feats_to_choose = feats;
+                        if feats > 0 {
+                            let level = player.history.levels.len() - 1;
+                            self.open_search(AddTarget::Feat { level });
+                        }
+                    })
+                }
+                None => Ok(()),
+            },
+            Message::RemoveLevelPressed => player.remove_latest_level(rules.classes),
+            Message::HitDieChanged { level, roll } => player.set_level_hit_die(level, roll),
+            Message::SkillPointsChanged { level, points } => {
+                player.set_level_skill_points(level, points)
+            }
+            Message::AddFeatPressed(level) => {
+                self.open_search(AddTarget::Feat { level });
+                Ok(())
+            }
+            Message::RemoveFeatPressed { level, index } => player.remove_level_feat(level, index),
+            Message::AddSpellPressed { level, spell_level } => {
+                self.open_search(AddTarget::Spell { level, spell_level });
+                Ok(())
+            }
+            Message::RemoveSpellPressed {
+                level,
+                spell_level,
+                index,
+            } => player.remove_level_spell(level, spell_level, index),
+            Message::SearchWindow(msg @ search_window::Message::Confirm) => {
+                let result = match self.search_window.selected_id {
+                    Some(id) => self.add_selected(player, id),
+                    None => Ok(()),
+                };
+
+                self.search_window.update(msg);
+                let target = self.add_target.take();
+
+                if result.is_ok()
+                    && let Some(AddTarget::Feat { level }) = target
+                    && self.feats_to_choose > 0
+                {
+                    self.feats_to_choose -= 1;
+                    if self.feats_to_choose > 0 {
+                        self.open_search(AddTarget::Feat { level });
+                    }
+                }
+                result
+            }
+            Message::SearchWindow(msg) => {
+                if msg == search_window::Message::Close {
+                    self.add_target = None;
+                    self.feats_to_choose = 0;
+                }
+
+                self.search_window.update(msg);
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            crate::show_error_popup(format!("Can't change level: {e}"));
+        }
+    }
+
+    fn view_level<'a>(
+        &self,
+        player: &'a Player,
+        level: usize,
+        entry: &'a LevelEntry,
+        feat_choices: Option<FeatChoices>,
+        rules: Rules<'a>,
+    ) -> Element<'a> {
+        let Rules {
+            feats: feat_record,
+            spells: spell_record,
+            skills: skill_record,
+            ..
+        } = rules;
+        let small_button = |label| button(text(label).size(12)).padding([2, 8]);
+
+        let mut title = format!("Level {}: {}", level + 1, entry.class);
+        if entry.epic_level {
+            title += " (epic)";
+        }
+        if let Some(ability) = entry.ability_increase {
+            let ability = ABILITY_NAMES.get(ability as usize).copied().unwrap_or("?");
+            title += &format!(", +1 {ability}");
+        }
+        let pending = feat_choices.map(|x| x.pending()).unwrap_or(0);
+        if pending > 0 {
+            title += &format!(" ({pending} feats to choose)");
+        }
+
+        let hit_die = iced_aw::number_input(entry.hit_die.get(), ..=u8::MAX, move |roll| {
+            Message::HitDieChanged { level, roll }
+        })
+        .ignore_buttons(true)
+        .width(64);
+
+        let skill_points = entry.skills.skill_points.as_ref().map(|points| {
+            iced_aw::number_input(points.get(), ..=u16::MAX, move |points| {
+                Message::SkillPointsChanged { level, points }
+            })
+            .ignore_buttons(true)
+            .width(64)
+        });
+
+        let numbers = row![text("Hit die roll"), hit_die]
+            .push_maybe(skill_points.as_ref().map(|_| text("Unspent skill points")))
+            .push_maybe(skill_points)
+            .spacing(16);
+
+        let skills = skill_record
+            .skills
+            .iter()
+            .filter_map(|(id, skill)| {
+                let rank = entry.skills.ranks.get(*id);
+                (rank > 0).then(|| format!("{} +{rank}", skill.name.data))
+            })
+            .sorted()
+            .join(", ");
+
+        let feats = entry.feat_ids().enumerate().map(|(index, id)| {
+            let name = feat_record
+                .feats
+                .get(&(id as usize))
+                .map(|x| x.name.data.clone())
+                .unwrap_or_else(|| format!("Feat {id}"));
+
+            row![
+                text(name).width(240),
+                small_button("Remove").on_press(Message::RemoveFeatPressed { level, index }),
+            ]
+            .spacing(8)
+            .into()
+        });
+
+        let feats = column![text("Feats")]
+            .extend(feats)
+            .push_maybe(
+                entry
+                    .feats
+                    .as_ref()
+                    .map(|_| small_button("Add feat").on_press(Message::AddFeatPressed(level))),
+            )
+            .spacing(4);
+
+        // Spell levels learned here or known by the level's class
+        let class_known = player
+            .classes
+            .iter()
+            .find(|x| *x.class.get() == entry.class)
+            .map(|x| &x.spell_known_list);
+
+        let spells = (0..entry.spells.len())
+            .filter(|i| entry.spells[*i].is_some() || class_known.is_some_and(|x| x[*i].is_some()))
+            .map(|spell_level| {
+                let learned = entry.spells[spell_level]
+                    .iter()
+                    .flat_map(|x| x.spells.iter())
+                    .enumerate()
+                    .map(|(index, spell)| {
+                        let name = spell_record
+                            .spells
+                            .get(&(spell.0 as usize))
+                            .map(|x| x.name.data.clone())
+                            .unwrap_or_else(|| spell.to_string());
+
+                        row![
+                            text(name).width(240),
+                            small_button("Remove").on_press(Message::RemoveSpellPressed {
+                                level,
+                                spell_level,
+                                index,
+                            }),
+                        ]
+                        .spacing(8)
+                        .into()
+                    });
+
+                column![text(format!("Level {spell_level} spells"))]
+                    .extend(learned)
+                    .push(
+                        small_button("Add spell")
+                            .on_press(Message::AddSpellPressed { level, spell_level }),
+                    )
+                    .spacing(4)
+                    .into()
+            });
+
+        let content = column![text(title).size(18), numbers]
+            .push_maybe((!skills.is_empty()).then(|| text(format!("Skills: {skills}"))))
+            .push(feats)
+            .extend(spells)
+            .spacing(12)
+            .width(Length::Fill);
+
+        bordered(content).padding(12).width(Length::Fill).into()
+    }
+
+    fn view_level_up<'a>(&self, player: &'a Player, class_record: &'a ClassRecord) -> Element<'a> {
+        let classes = class_record
+            .classes
+            .iter()
+            .filter(|(_, info)| info.player_class)
+            .map(|(class, _)| *class)
+            .sorted_by_key(|x| x.to_string())
+            .collect::<Vec<_>>();
+
+        let next_level = total_level(&player.classes) + 1;
+        let ability = raises_ability(next_level).then(|| {
+            let options = (0..ABILITY_NAMES.len() as u8)
+                .map(AbilityOption)
+                .collect::<Vec<_>>();
+            row![
+                text("raising"),
+                pick_list(options, self.new_ability, Message::NewAbilitySelected)
+                    .placeholder("Ability"),
+            ]
+            .spacing(8)
+        });
+
+        row![
+            text(format!("Level {next_level}:")),
+            pick_list(classes, self.new_class, Message::NewClassSelected).placeholder("Class"),
+        ]
+        .push_maybe(ability)
+        .push(button("Add level").on_press_maybe(self.new_class.map(|_| Message::AddLevelPressed)))
+        .push(button("Remove latest level").on_press_maybe(
+            (!player.history.levels.is_empty()).then_some(Message::RemoveLevelPressed),
+        ))
+        .spacing(16)
+        .padding([0, 16])
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        player: &'a Player,
+        rules: Rules<'a>,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
+        let Rules {
+            feats: feat_record,
+            spells: spell_record,
+            skills: skill_record,
+            classes: class_record,
+            ..
+        } = rules;
+
+        if self.search_window.is_active() {
+            let kind = match self.add_target {
+                Some(AddTarget::Spell { level, spell_level }) => {
+                    search_window::SearchKind::Spells {
+                        spell_record,
+                        class: player.history.levels[level].class,
+                        level: spell_level as u8,
+                    }
+                }
+                _ => search_window::SearchKind::Feats {
+                    feat_record,
+                    check: Some(search_window::FeatCheck {
+                        player,
+                        class_record,
+                        skill_record,
+                    }),
+                },
+            };
+
+            return self
+                .search_window
+                .view(kind, icons)
+                .map(Message::SearchWindow);
+        }
+
+        let level_up = self.view_level_up(player, class_record);
+
+        if player.history.levels.is_empty() {
+            return column![level_up, text("This character has no level history")]
+                .spacing(16)
+                .padding([16, 0])
+                .into();
+        }
+
+        let feat_choices = player.feat_choices(rules);
+        let levels = player
+            .history
+            .levels
+            .iter()
+            .zip(feat_choices)
+            .enumerate()
+            .map(|(i, (entry, choices))| self.view_level(player, i, entry, choices, rules));
+
+        let levels =
+            scrollable(Column::from_iter(levels).spacing(16).padding(16)).height(Length::Fill);
+
+        column![level_up, levels].padding([16, 0]).into()
+    }
+}
@@ -0,0 +1,455 @@
+use crate::icons::IconCache;
+use iced::{
+    Alignment, Length,
+    widget::{
+        Column, Image, button, checkbox, column, container, horizontal_space, pick_list, row,
+        scrollable, text,
+    },
+};
+use nwn_model::{
+    error::Error,
+    item::BaseItemRecord,
+    item_property::{Choice, ItemProperty, ItemPropertyRecord},
+    player::{
+        Player,
+        inventory::{Item, ItemFlag, ItemSlot, equip_slot_name},
+        legality::Rules,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    ItemSelected(ItemSlot),
+    StackSizeChanged(u16),
+    ChargesChanged(u8),
+    FlagToggled { flag: ItemFlag, value: bool },
+    DuplicatePressed,
+    DeletePressed,
+    PropertyAddPressed,
+    PropertyEditPressed(usize),
+    PropertyRemovePressed(usize),
+    DraftPropertyChanged(u16),
+    DraftSubtypeChanged(u16),
+    DraftCostChanged(u16),
+    DraftParamChanged(u8),
+    DraftSaved,
+    DraftCancelled,
+}
+
+pub type Element<'a> = iced::Element<'a, Message>;
+
+const FLAGS: [(ItemFlag, &str); 3] = [
+    (ItemFlag::Identified, "Identified"),
+    (ItemFlag::Plot, "Plot"),
+    (ItemFlag::Cursed, "Cursed"),
+];
+
+/// Property being added, `index` is `None`, or edited before it's saved to the item
+#[derive(Debug, Clone, Copy)]
+struct Draft {
+    index: Option<usize>,
+    property: ItemProperty,
+}
+
+fn field<'a>(label: &'a str, content: impl Into<Element<'a>>) -> Element<'a> {
+    row![text(label).width(100), content.into()]
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .into()
+}
+
+fn find(choices: &[Choice], value: u16) -> Option<Choice> {
+    choices.iter().find(|x| x.value == value).cloned()
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    selected: Option<ItemSlot>,
+    draft: Option<Draft>,
+    /// Property choices sorted by name, read from the record when a draft is first opened
+    choices: Vec<Choice>,
+}
+impl State {
+    pub fn update(
+        &mut self,
+        player: &mut Player,
+        rules: Rules<'_>,
+        next_object_id: Result<u32, Error>,
+        msg: Message,
+    ) {
+        let properties = rules.item_properties;
+        if self.choices.is_empty() {
+            self.choices = properties.choices();
+        }
+        if let Message::ItemSelected(slot) = msg {
+            self.selected = Some(slot);
+            self.draft = None;
+            return;
+        }
+        let Some(slot) = self.selected else {
+            return;
+        };
+
+        let result = match msg {
+            Message::ItemSelected(_) => Ok(()),
+            Message::StackSizeChanged(stack_size) => {
+                player.set_stack_size(slot, stack_size, rules.base_items)
+            }
+            Message::ChargesChanged(charges) => match player.inventory.get_mut(slot) {
+                Some(item) => item.set_charges(charges),
+                None => Ok(()),
+            },
+            Message::FlagToggled { flag, value } => match player.inventory.get_mut(slot) {
+                Some(item) => item.set_flag(flag, value),
+                None => Ok(()),
+            },
+            Message::DuplicatePressed => next_object_id
+                .and_then(|id| player.inventory.duplicate(slot, id))
+                .map(|copy| self.selected = Some(copy)),
+            Message::DeletePressed => {
+                // Indices after the item shift down
+                self.selected = None;
+                self.draft = None;
+                player.inventory.remove(slot).map(|_| ())
+            }
+            Message::PropertyAddPressed => match self.choices.first() {
+                Some(first) => properties.default_property(first.value).map(|property| {
+                    self.draft = Some(Draft {
+                        index: None,
+                        property,
+                    })
+                }),
+                None => Ok(()),
+            },
+            Message::PropertyEditPressed(index) => {
+                let property = player
+                    .inventory
+                    .get(slot)
+                    .and_then(|x| x.properties.as_ref())
+                    .and_then(|x| x.properties.get(index));
+                self.draft = property.map(|&property| Draft {
+                    index: Some(index),
+                    property,
+                });
+                Ok(())
+            }
+            Message::PropertyRemovePressed(index) => {
+                // Indices after the property shift down
+                self.draft = None;
+                match player.inventory.get_mut(slot) {
+                    Some(item) => item
+                        .properties_mut()
+                        .and_then(|x| x.remove(index))
+                        .map(|_| ()),
+                    None => Ok(()),
+                }
+            }
+            Message::DraftPropertyChanged(id) => match &mut self.draft {
+                Some(draft) => properties
+                    .default_property(id)
+                    .map(|property| draft.property = property),
+                None => Ok(()),
+            },
+            Message::DraftSubtypeChanged(subtype) => {
+                if let Some(draft) = &mut self.draft {
+                    properties.set_subtype(&mut draft.property, subtype);
+                }
+                Ok(())
+            }
+            Message::DraftCostChanged(cost) => {
+                if let Some(draft) = &mut self.draft {
+                    draft.property.cost_value = cost;
+                }
+                Ok(())
+            }
+            Message::DraftParamChanged(param) => {
+                if let Some(draft) = &mut self.draft {
+                    draft.property.param1_value = param;
+                }
+                Ok(())
+            }
+            Message::DraftSaved => match (self.draft, player.inventory.get_mut(slot)) {
+                (Some(draft), Some(item)) => item
+                    .properties_mut()
+                    .and_then(|list| match draft.index {
+                        Some(index) => list.set(index, draft.property, properties),
+                        None => list.add(draft.property, properties).map(|_| ()),
+                    })
+                    .map(|()| self.draft = None),
+                _ => Ok(()),
+            },
+            Message::DraftCancelled => {
+                self.draft = None;
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            crate::show_error_popup(format!("Can't change item: {e}"));
+        }
+    }
+
+    fn view_item<'a>(
+        &self,
+        slot: ItemSlot,
+        item: &Item,
+        label: String,
+        base_items: &BaseItemRecord,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
+        let icon: Element = match icons.base_items.get(&item.base_item) {
+            Some(icon) => Image::new(icon).width(32).height(32).into(),
+            None => horizontal_space().width(32).into(),
+        };
+
+        let stack = item
+            .stack_size
+            .as_ref()
+            .map(|x| *x.get())
+            .filter(|x| *x > 1)
+            .map(|x| format!(" x{x}"))
+            .unwrap_or_default();
+
+        let indent = match slot {
+            ItemSlot::InContainer { .. } => 32,
+            _ => 0,
+        };
+
+        let content = row![
+            horizontal_space().width(indent),
+            icon,
+            text(label).width(120),
+            text(format!("{}{stack}", item.display_name(base_items))),
+        ]
+        .spacing(16)
+        .align_y(Alignment::Center);
+
+        let style = match self.selected == Some(slot) {
+            true => button::primary,
+            false => button::text,
+        };
+
+        button(content)
+            .style(style)
+            .width(Length::Fill)
+            .on_press(Message::ItemSelected(slot))
+            .into()
+    }
+
+    fn view_items<'a>(
+        &self,
+        player: &Player,
+        base_items: &BaseItemRecord,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
+        let inventory = &player.inventory;
+        let mut rows = vec![text("Equipped").size(20).into()];
+
+        rows.extend(
+            inventory
+                .equipped
+                .items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let label = equip_slot_name(item.struct_id);
+                    self.view_item(ItemSlot::Equipped(i), item, label, base_items, icons)
+                }),
+        );
+
+        rows.push(text("Carried").size(20).into());
+        for (container, item) in inventory.carried.items.iter().enumerate() {
+            let slot = ItemSlot::Carried(container);
+            let label = match &item.contents {
+                Some(contents) => format!("Holds {}", contents.items.len()),
+                None => String::new(),
+            };
+            rows.push(self.view_item(slot, item, label, base_items, icons));
+
+            let contents = item.contents.iter().flat_map(|x| &x.items).enumerate();
+            for (index, item) in contents {
+                let slot = ItemSlot::InContainer { container, index };
+                rows.push(self.view_item(slot, item, String::new(), base_items, icons));
+            }
+        }
+
+        scrollable(Column::from_iter(rows).spacing(4).padding(16))
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Pick lists of a property limited to the values its tables allow
+    fn view_draft<'a>(&'a self, draft: Draft, record: &'a ItemPropertyRecord) -> Element<'a> {
+        let property = draft.property;
+        let selected = find(&self.choices, property.property);
+        let mut rows = vec![field(
+            "Property",
+            pick_list(self.choices.as_slice(), selected, |x| {
+                Message::DraftPropertyChanged(x.value)
+            }),
+        )];
+
+        if let Some(def) = record.properties.get(&property.property)
+            && !def.subtypes.is_empty()
+        {
+            let selected = find(&def.subtypes, property.subtype);
+            rows.push(field(
+                "Subtype",
+                pick_list(def.subtypes.as_slice(), selected, |x| {
+                    Message::DraftSubtypeChanged(x.value)
+                }),
+            ));
+        }
+
+        let costs = record.costs(property.property);
+        if !costs.is_empty() {
+            let selected = find(costs, property.cost_value);
+            rows.push(field(
+                "Value",
+                pick_list(costs, selected, |x| Message::DraftCostChanged(x.value)),
+            ));
+        }
+
+        let params = record.params(property.property, property.subtype);
+        if !params.is_empty() {
+            let name = record
+                .param_table(property.property, property.subtype)
+                .and_then(|x| record.param_tables.get(&x))
+                .map_or("Parameter", |x| x.name.as_str());
+            let selected = find(params, property.param1_value.into());
+            rows.push(field(
+                name,
+                pick_list(params, selected, |x| {
+                    Message::DraftParamChanged(x.value as u8)
+                }),
+            ));
+        }
+
+        let save = match draft.index {
+            Some(_) => "Save property",
+            None => "Add property",
+        };
+        rows.push(
+            row![
+                button(save).on_press(Message::DraftSaved),
+                button("Cancel")
+                    .style(button::secondary)
+                    .on_press(Message::DraftCancelled),
+            ]
+            .spacing(8)
+            .into(),
+        );
+
+        Column::from_iter(rows).spacing(8).into()
+    }
+
+    fn view_properties<'a>(&'a self, item: &Item, record: &'a ItemPropertyRecord) -> Element<'a> {
+        let Some(list) = &item.properties else {
+            return text("The item has no property list").into();
+        };
+
+        let properties = list.properties.iter().enumerate().map(|(i, property)| {
+            row![
+                text(record.describe(property)).width(Length::Fill),
+                button("Edit")
+                    .style(button::secondary)
+                    .on_press(Message::PropertyEditPressed(i)),
+                button("Remove")
+                    .style(button::danger)
+                    .on_press(Message::PropertyRemovePressed(i)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .into()
+        });
+
+        let editor: Element = match self.draft {
+            Some(draft) => self.view_draft(draft, record),
+            None => button("New property")
+                .on_press(Message::PropertyAddPressed)
+                .into(),
+        };
+
+        column![
+            text("Properties").size(20),
+            Column::from_iter(properties).spacing(4),
+            editor,
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    fn view_details<'a>(&'a self, item: &Item, rules: Rules<'a>) -> Element<'a> {
+        let base_items = rules.base_items;
+        let base = base_items.items.get(&item.base_item);
+
+        let stack: Element = match &item.stack_size {
+            Some(stack_size) => {
+                let max = base.map_or(u16::MAX, |x| x.max_stack.max(1));
+                iced_aw::number_input(stack_size.get(), 1..=max, Message::StackSizeChanged)
+                    .ignore_buttons(true)
+                    .width(80)
+                    .into()
+            }
+            None => text("-").into(),
+        };
+        let charges: Element = match &item.charges {
+            Some(charges) => {
+                iced_aw::number_input(charges.get(), ..=u8::MAX, Message::ChargesChanged)
+                    .ignore_buttons(true)
+                    .width(80)
+                    .into()
+            }
+            None => text("-").into(),
+        };
+
+        let flags = FLAGS.into_iter().filter_map(|(flag, label)| {
+            let value = item.flag(flag)?;
+            Some(
+                checkbox(label, value)
+                    .on_toggle(move |value| Message::FlagToggled { flag, value })
+                    .into(),
+            )
+        });
+
+        let details = column![
+            text(item.display_name(base_items)).size(20),
+            text(format!("Base item: {}", base_items.name(item.base_item))),
+            text(format!("Tag: {}", item.tag)),
+            field("Stack size", stack),
+            field("Charges", charges),
+            Column::from_iter(flags).spacing(8),
+            row![
+                button("Duplicate").on_press(Message::DuplicatePressed),
+                button("Delete")
+                    .style(button::danger)
+                    .on_press(Message::DeletePressed),
+            ]
+            .spacing(8),
+            self.view_properties(item, rules.item_properties),
+        ]
+        .spacing(12)
+        .padding(16);
+
+        scrollable(details).width(400).into()
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        player: &'a Player,
+        rules: Rules<'a>,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
+        let items = self.view_items(player, rules.base_items, icons);
+
+        let details: Element = match self.selected.and_then(|x| player.inventory.get(x)) {
+            Some(item) => self.view_details(item, rules),
+            None => container(text("Select an item"))
+                .padding(16)
+                .width(400)
+                .into(),
+        };
+
+        row![container(items).width(Length::Fill), details].into()
+    }
+}
@@ -0,0 +1,138 @@
+#![allow(unstable_name_collisions)]
+
+use crate::icons::IconCache;
+use iced::{
+    Length,
+    widget::{
+        Column, Image, column, container, horizontal_rule, horizontal_space, row, scrollable, text,
+    },
+};
+use itertools::Itertools;
+use nwn_model::{
+    player::{Player, total_level},
+    skill::{Skill, SkillId, SkillRecord},
+};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    RankChanged { skill: SkillId, rank: u8 },
+    UnspentPointsChanged(u16),
+}
+
+pub type Element<'a> = iced::Element<'a, Message>;
+
+#[derive(Debug, Default)]
+pub struct State;
+impl State {
+    pub fn update(&mut self, player: &mut Player, skill_record: &SkillRecord, msg: Message) {
+        match msg {
+            Message::RankChanged { skill, rank } => {
+                if let Err(e) = player.set_skill_rank(skill_record, skill, rank) {
+                    crate::show_error_popup(format!("Can't change skill: {e}"));
+                }
+            }
+            Message::UnspentPointsChanged(points) => player.set_unspent_skill_points(points),
+        }
+    }
+
+    fn view_skill<'a>(
+        &self,
+        player: &'a Player,
+        skill_record: &'a SkillRecord,
+        icons: &'a IconCache,
+        id: SkillId,
+        skill: &'a Skill,
+        total: i16,
+    ) -> Element<'a> {
+        let icon: Element = match icons.skills.get(&id) {
+            Some(icon) => Image::new(icon).width(32).height(32).into(),
+            None => horizontal_space().width(32).into(),
+        };
+
+        let classes = player
+            .classes
+            .iter()
+            .map(|x| *x.class.get())
+            .collect::<Vec<_>>();
+        let max = skill_record.max_ranks(&classes, total_level(&player.classes), id);
+
+        let rank = iced_aw::number_input(&player.skills.ranks.get(id), ..=max, move |rank| {
+            Message::RankChanged { skill: id, rank }
+        })
+        .ignore_buttons(true)
+        .width(64);
+
+        let status = classes
+            .iter()
+            .map(|class| format!("{class}: {}", skill_record.status(*class, id)))
+            .join(", ");
+
+        let by_level = player.skill_ranks_by_level(id).join(" / ");
+
+        row![
+            icon,
+            text(&skill.name.data).width(160),
+            text(&skill.key_ability).width(40),
+            rank,
+            text(format!("max {max}")).width(60),
+            text(format!("{total:+}")).width(40),
+            text(status).width(200),
+            text(by_level),
+        ]
+        .spacing(16)
+        .padding([4, 16])
+        .into()
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        player: &'a Player,
+        skill_record: &'a SkillRecord,
+        totals: &HashMap<SkillId, i16>,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
+        let unspent: Element = match player.unspent_skill_points() {
+            Some(points) => row![
+                text("Unspent skill points"),
+                iced_aw::number_input(&points, ..=u16::MAX, Message::UnspentPointsChanged)
+                    .ignore_buttons(true)
+                    .width(80),
+            ]
+            .spacing(16)
+            .into(),
+            None => text("Skill points aren't tracked for this character").into(),
+        };
+
+        let header = row![
+            horizontal_space().width(32),
+            text("Skill").width(160),
+            text("Ability").width(40),
+            text("Ranks").width(64),
+            horizontal_space().width(60),
+            text("Total").width(40),
+            text("Class skill").width(200),
+            text("Ranks by level"),
+        ]
+        .spacing(16)
+        .padding([4, 16]);
+
+        let skills = skill_record
+            .skills
+            .iter()
+            .sorted_by(|(_, a), (_, b)| a.name.data.cmp(&b.name.data))
+            .map(|(id, skill)| {
+                let total = totals.get(id).copied().unwrap_or_default();
+                self.view_skill(player, skill_record, icons, *id, skill, total)
+            })
+            .intersperse_with(|| horizontal_rule(1).into());
+
+        let skills =
+            scrollable(container(Column::from_iter(skills)).padding(16)).height(Length::Fill);
+
+        column![unspent, header, skills]
+            .spacing(8)
+            .padding(16)
+            .into()
+    }
+}
@@ -1,26 +1,39 @@
 #![allow(unstable_name_collisions)]
 
 use crate::{
-    ids::spell::Spell as SpellId,
-    player::{Player, PlayerClass, player_class::SpellKnownList},
-    spell::{Spell, SpellRecord},
+    icons::IconCache,
     ui::{HoverableEvent, HoverableState, hoverable, search_window},
 };
 use iced::{
     Length,
     widget::{
-        Column, Image, button, column, combo_box, container, horizontal_rule, image::Handle, row,
-        scrollable, text, vertical_space,
+        Column, Image, button, checkbox, column, combo_box, container, horizontal_rule,
+        image::Handle, pick_list, row, scrollable, text, vertical_space,
     },
 };
 use itertools::Itertools;
+use nwn_model::{
+    class::ClassRecord,
+    ids::{class::Class, spell::Spell as SpellId},
+    player::{
+        Player, PlayerClass,
+        legality::Rules,
+        memorized::{MemorizedSpell, SpellSlots, metamagic_names},
+        player_class::{SpellKnownList, SpellLevelUsage},
+    },
+    spell::{Spell, SpellRecord},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Message {
     HoverableEvent(HoverableEvent),
-    ClassSelected(usize),
+    ClassSelected(Class),
     SpellTabSelected(usize),
+    BlockOverLimitToggled(bool),
+    ModeSelected(SpellMode),
+    DomainToggled(bool),
     AddPressed,
+    ReadyToggled(usize),
     SwapPressed(usize),
     RemovePressed(usize),
     SearchWindow(search_window::Message),
@@ -28,9 +41,30 @@ pub enum Message {
 
 pub type Element<'a> = iced::Element<'a, Message>;
 
+/// Which spell lists of the class are shown
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SpellMode {
+    #[default]
+    Known,
+    Memorized,
+}
+impl SpellMode {
+    const ALL: [Self; 2] = [Self::Known, Self::Memorized];
+}
+impl std::fmt::Display for SpellMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Known => f.write_str("Known spells"),
+            Self::Memorized => f.write_str("Memorized spells"),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 struct ClassOption {
+    /// Index in the player's classes
     index: usize,
+    class: Class,
     name: String,
 }
 impl ClassOption {
@@ -54,6 +88,11 @@ pub struct State {
     selected_class: ClassOption,
     hoverable_state: HoverableState,
     spell_tab: usize,
+    mode: SpellMode,
+    /// Whether adding spells past the class's spells known or slots is refused instead of marked
+    block_over_limit: bool,
+    /// Whether spells are prepared in the domain slot
+    prepare_as_domain: bool,
     search_window: search_window::State,
 }
 impl State {
@@ -65,8 +104,12 @@ impl State {
                 .enumerate()
                 .filter(|(_, class)| class.is_caster)
                 .map(|(i, class)| {
-                    let name = class.class.get().to_string();
-                    ClassOption { index: i, name }
+                    let class = *class.class.get();
+                    ClassOption {
+                        index: i,
+                        class,
+                        name: class.to_string(),
+                    }
                 })
                 .collect(),
         );
@@ -82,6 +125,9 @@ impl State {
             selected_class,
             hoverable_state: Default::default(),
             spell_tab: 0,
+            mode: SpellMode::default(),
+            block_over_limit: false,
+            prepare_as_domain: false,
             search_window: Default::default(),
         }
     }
@@ -97,16 +143,43 @@ impl State {
         )
     }
 
-    pub fn update(&mut self, player: &mut Player, msg: Message) {
+    pub fn update(&mut self, player: &mut Player, rules: Rules<'_>, msg: Message) {
+        let class_record = rules.classes;
         match msg {
             Message::HoverableEvent(e) => e.update(&mut self.hoverable_state),
-            Message::ClassSelected(i) => {
-                self.selected_class = self.class_options.options()[i].clone();
+            Message::ClassSelected(class) => {
+                if let Some(option) = self
+                    .class_options
+                    .options()
+                    .iter()
+                    .find(|x| x.class == class)
+                {
+                    self.selected_class = option.clone();
+                }
             }
             Message::SpellTabSelected(i) => {
                 self.spell_tab = i;
                 self.hoverable_state.reset();
             }
+            Message::BlockOverLimitToggled(block) => self.block_over_limit = block,
+            Message::ModeSelected(mode) => {
+                self.mode = mode;
+                self.hoverable_state.reset();
+            }
+            Message::DomainToggled(domain) => self.prepare_as_domain = domain,
+            Message::ReadyToggled(i) => {
+                let list = player
+                    .classes
+                    .get_mut(self.selected_class.index)
+                    .and_then(|x| x.memorized_list.get_mut(self.spell_tab))
+                    .and_then(|x| x.as_mut());
+                if let Some(list) = list
+                    && let Some(ready) = list.spells.get(i).map(|x| x.ready)
+                    && let Err(e) = list.set_ready(i, !ready)
+                {
+                    crate::show_error_popup(format!("Can't change spell: {e}"));
+                }
+            }
             Message::AddPressed => {
                 self.search_window.open(search_window::SearchMode::Add);
             }
@@ -115,20 +188,78 @@ impl State {
             }
             Message::RemovePressed(i) => {
                 self.hoverable_state.reset();
-                if let Some(lst) = self.get_current_spell_list(player) {
-                    lst.remove_spell(i);
+                match self.mode {
+                    SpellMode::Known => {
+                        if let Some(lst) = self.get_current_spell_list(player)
+                            && let Err(e) = lst.remove_spell(i)
+                        {
+                            crate::show_error_popup(format!("Can't remove spell: {e}"));
+                        }
+                    }
+                    SpellMode::Memorized => {
+                        let list = player
+                            .classes
+                            .get_mut(self.selected_class.index)
+                            .and_then(|x| x.memorized_list.get_mut(self.spell_tab))
+                            .and_then(|x| x.as_mut());
+                        if let Some(list) = list
+                            && let Err(e) = list.remove_spell(i)
+                        {
+                            crate::show_error_popup(format!("Can't remove spell: {e}"));
+                        }
+                    }
                 }
             }
+            Message::SearchWindow(msg @ search_window::Message::Confirm)
+                if self.mode == SpellMode::Memorized =>
+            {
+                if let search_window::SearchMode::Add = self.search_window.mode
+                    && let Some(new_id) = self.search_window.selected_id
+                {
+                    let slots =
+                        player.spell_slots(class_record, self.selected_class.index, self.spell_tab);
+
+                    if self.block_over_limit && slots.is_none_or(|x| x.is_full()) {
+                        crate::show_error_popup(format!(
+                            "Can't prepare spell: no level {} slots left",
+                            self.spell_tab
+                        ));
+                    } else {
+                        let mut spell = MemorizedSpell::new(SpellId(new_id.try_into().unwrap()));
+                        spell.domain = self.prepare_as_domain;
+
+                        if let Err(e) = player.memorize_spell(
+                            self.selected_class.index,
+                            self.spell_tab,
+                            spell,
+                            rules.domains,
+                        ) {
+                            crate::show_error_popup(format!("Can't prepare spell: {e}"));
+                        }
+                    }
+                }
+
+                self.search_window.update(msg);
+            }
             Message::SearchWindow(msg @ search_window::Message::Confirm) => {
-                let spell_list = self.get_current_spell_list(player);
+                let limit = self
+                    .selected_class
+                    .get(player)
+                    .map(|x| x.check_spell_limit(class_record, self.spell_tab));
 
                 match self.search_window.mode {
                     search_window::SearchMode::None => {}
                     search_window::SearchMode::Add => {
-                        if let Some(new_id) = self.search_window.selected_id
-                            && let Some(spell_list) = spell_list
+                        if self.block_over_limit
+                            && let Some(Err(e)) = limit
                         {
-                            spell_list.add_spell(SpellId(new_id.try_into().unwrap()));
+                            crate::show_error_popup(format!("Can't add spell: {e}"));
+                        } else if let Some(new_id) = self.search_window.selected_id
+                            && let Some(spell_list) = self.get_current_spell_list(player)
+                            && let Err(e) =
+                                spell_list.add_spell(SpellId(new_id.try_into().unwrap()))
+                        {
+                            crate::show_error_popup(format!("Can't add spell: {e}"));
                         }
                     }
                     search_window::SearchMode::Swap(index) => {
@@ -136,7 +267,9 @@ impl State {
                             && let Some(spell_list) = self.get_current_spell_list(player)
                         {
                             let spell = SpellId(new_id.try_into().unwrap());
-                            spell_list.swap_spell(index, spell);
+                            if let Err(e) = spell_list.swap_spell(index, spell) {
+                                crate::show_error_popup(format!("Can't swap spell: {e}"));
+                            }
                         }
                     }
                 }
@@ -149,8 +282,8 @@ impl State {
         }
     }
 
-    fn view_spell<'a>(&self, spell: &'a Spell) -> Option<Element<'a>> {
-        let icon: Element<'_> = match &spell.icon {
+    fn view_spell<'a>(&self, spell: &'a Spell, icon: Option<&'a Handle>) -> Option<Element<'a>> {
+        let icon: Element<'_> = match icon {
             Some(handle) => Image::<Handle>::new(handle).width(40).height(40).into(),
             None => vertical_space().width(40).into(),
         };
@@ -175,8 +308,17 @@ impl State {
         &self,
         class: &'a PlayerClass,
         spell_record: &'a SpellRecord,
+        class_record: &'a ClassRecord,
+        icons: &'a IconCache,
     ) -> Element<'a> {
         let spells = &class.spell_known_list;
+        let label = |spell_level: usize| {
+            let usage = class.spell_usage(class_record, spell_level);
+            match usage.allowed {
+                Some(allowed) => format!("{spell_level} ({}/{allowed})", usage.known),
+                None => spell_level.to_string(),
+            }
+        };
 
         let tabs = spells.iter().map_while(|x| x.as_ref()).enumerate().fold(
             iced_aw::Tabs::new(Message::SpellTabSelected),
@@ -185,8 +327,9 @@ impl State {
                     .spells
                     .iter()
                     .filter_map(|x| {
-                        let spell = spell_record.spells.get(&(x.0 as usize))?;
-                        self.view_spell(spell)
+                        let id = x.0 as usize;
+                        let spell = spell_record.spells.get(&id)?;
+                        self.view_spell(spell, icons.spells.get(&id))
                     })
                     .enumerate()
                     .map(|(i, x)| {
@@ -199,36 +342,193 @@ impl State {
                     .width(Length::Fill);
                 let col = scrollable(col).height(Length::Fill);
 
-                tabs.push(i, iced_aw::TabLabel::Text(i.to_string()), col)
+                tabs.push(i, iced_aw::TabLabel::Text(label(i)), col)
+            },
+        );
+
+        tabs.set_active_tab(&self.spell_tab).into()
+    }
+
+    fn view_memorized_spell<'a>(
+        &self,
+        entry: &MemorizedSpell,
+        spell_record: &'a SpellRecord,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
+        let id = entry.spell.0 as usize;
+        let icon: Element<'_> = match icons.spells.get(&id) {
+            Some(handle) => Image::<Handle>::new(handle).width(40).height(40).into(),
+            None => vertical_space().width(40).into(),
+        };
+
+        let name = match spell_record.spells.get(&id) {
+            Some(spell) => spell.name.data.trim().to_string(),
+            None => format!("Unknown spell {id}"),
+        };
+
+        let metamagic = metamagic_names(entry.metamagic).join(", ");
+        let ready = match entry.ready {
+            true => text("Ready"),
+            false => text("Cast").style(text::secondary),
+        };
+
+        row![
+            icon,
+            text(name).width(200),
+            text(metamagic).width(Length::Fill)
+        ]
+        .push_maybe(entry.domain.then(|| text("Domain")))
+        .push(ready)
+        .width(Length::Fill)
+        .spacing(16)
+        .padding(16)
+        .into()
+    }
+
+    fn view_memorized<'a>(
+        &self,
+        player: &'a Player,
+        spell_record: &'a SpellRecord,
+        class_record: &'a ClassRecord,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
+        let index = self.selected_class.index;
+        let Some(class) = player.classes.get(index) else {
+            return text("").into();
+        };
+
+        // Levels the class has slots of, or spells prepared in anyway
+        let levels = (0..10).filter_map(|spell_level| {
+            let slots = player.spell_slots(class_record, index, spell_level);
+            let list = class.memorized_list[spell_level].as_ref();
+            (slots.is_some_and(|x| x.slots > 0) || list.is_some()).then_some((
+                spell_level,
+                slots.unwrap_or_default(),
+                list,
+            ))
+        });
+
+        let tabs = levels.fold(
+            iced_aw::Tabs::new(Message::SpellTabSelected),
+            |tabs, (spell_level, slots, list)| {
+                let spells = list
+                    .map(|x| x.spells.as_slice())
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|x| self.view_memorized_spell(x, spell_record, icons))
+                    .enumerate()
+                    .map(|(i, x)| {
+                        hoverable(x, i, self.hoverable_state, Message::HoverableEvent).into()
+                    })
+                    .intersperse_with(|| horizontal_rule(1).into());
+
+                let col = Column::from_iter(spells).width(Length::Fill);
+                let col = scrollable(col).height(Length::Fill);
+
+                let label = format!("{spell_level} ({}/{})", slots.prepared, slots.slots);
+                tabs.push(spell_level, iced_aw::TabLabel::Text(label), col)
             },
         );
 
         tabs.set_active_tab(&self.spell_tab).into()
     }
 
+    /// Prepared spells of the selected level against the class's slots
+    fn view_slots<'a>(
+        &self,
+        slots: SpellSlots,
+        class_record: &ClassRecord,
+        class: &PlayerClass,
+    ) -> Element<'a> {
+        let prepared = format!("{} of {} slots prepared", slots.prepared, slots.slots);
+        let prepared = match slots.over_limit() {
+            true => text(format!("{prepared}, over the limit")).style(text::danger),
+            false => text(prepared),
+        };
+
+        let has_domains = class_record
+            .classes
+            .get(class.class.get())
+            .is_some_and(|x| x.has_domains);
+        let domain = has_domains.then(|| {
+            checkbox("Prepare in domain slot", self.prepare_as_domain)
+                .on_toggle(Message::DomainToggled)
+        });
+
+        row![prepared]
+            .push_maybe(
+                (slots.domain_slots > 0).then(|| text(format!("{} domain", slots.domain_slots))),
+            )
+            .push_maybe(domain)
+            .spacing(16)
+            .into()
+    }
+
+    /// Spells known of the selected level against the class's tables
+    fn view_usage<'a>(&self, usage: SpellLevelUsage) -> Element<'a> {
+        let known = match usage.allowed {
+            Some(allowed) => format!("{} of {allowed} spells known", usage.known),
+            None => format!("{} spells known", usage.known),
+        };
+        let known = match usage.over_limit() {
+            true => text(format!("{known}, over the limit")).style(text::danger),
+            false => text(known),
+        };
+
+        row![known]
+            .push_maybe(usage.per_day.map(|x| text(format!("{x} per day"))))
+            .spacing(16)
+            .into()
+    }
+
     fn view_class<'a>(
         &'a self,
+        player: &'a Player,
         class: &'a PlayerClass,
         spell_record: &'a SpellRecord,
+        class_record: &'a ClassRecord,
+        icons: &'a IconCache,
     ) -> Element<'a> {
-        let spells = self.view_spells(class, spell_record);
+        let (usage, spells) = match self.mode {
+            SpellMode::Known => (
+                self.view_usage(class.spell_usage(class_record, self.spell_tab)),
+                self.view_spells(class, spell_record, class_record, icons),
+            ),
+            SpellMode::Memorized => {
+                let slots = player
+                    .spell_slots(class_record, self.selected_class.index, self.spell_tab)
+                    .unwrap_or_default();
+                (
+                    self.view_slots(slots, class_record, class),
+                    self.view_memorized(player, spell_record, class_record, icons),
+                )
+            }
+        };
 
-        bordered_container(spells)
-            .height(Length::Fill)
-            .width(Length::Fill)
-            .into()
+        column![
+            usage,
+            bordered_container(spells)
+                .height(Length::Fill)
+                .width(Length::Fill),
+        ]
+        .spacing(8)
+        .into()
     }
 
     fn button_bar(&self) -> Element<'_> {
         let btn = |content| button(text(content).center()).width(Length::Fill);
+        let selected = self.hoverable_state.selected_entry;
+
+        let middle = match self.mode {
+            SpellMode::Known => btn("Swap").on_press_maybe(selected.map(Message::SwapPressed)),
+            SpellMode::Memorized => {
+                btn("Toggle ready").on_press_maybe(selected.map(Message::ReadyToggled))
+            }
+        };
 
         row![
             btn("Add").on_press(Message::AddPressed),
-            btn("Swap").on_press_maybe(
-                self.hoverable_state
-                    .selected_entry
-                    .map(Message::SwapPressed)
-            ),
+            middle,
             btn("Remove").on_press_maybe(
                 self.hoverable_state
                     .selected_entry
@@ -241,35 +541,51 @@ impl State {
         .into()
     }
 
-    pub fn view<'a>(&'a self, player: &'a Player, spell_record: &'a SpellRecord) -> Element<'a> {
+    pub fn view<'a>(
+        &'a self,
+        player: &'a Player,
+        spell_record: &'a SpellRecord,
+        class_record: &'a ClassRecord,
+        icons: &'a IconCache,
+    ) -> Element<'a> {
         if self.search_window.is_active() {
             let selected_class = &player.classes[self.selected_class.index];
             let class = *selected_class.class.get();
             let level = self.spell_tab;
 
             self.search_window
-                .view(search_window::SearchKind::Spells {
-                    spell_record,
-                    class,
-                    level: level as u8,
-                })
+                .view(
+                    search_window::SearchKind::Spells {
+                        spell_record,
+                        class,
+                        level: level as u8,
+                    },
+                    icons,
+                )
                 .map(Message::SearchWindow)
         } else {
-            let mut caster_classes = player.classes.iter().filter(|c| c.is_caster);
-
             let combo = iced::widget::combo_box(
                 &self.class_options,
                 "Select class",
                 Some(&self.selected_class),
-                |item| Message::ClassSelected(item.index),
+                |item| Message::ClassSelected(item.class),
             );
 
-            let class = caster_classes
-                .nth(self.selected_class.index)
-                .map(|c| self.view_class(c, spell_record))
+            let class = self
+                .selected_class
+                .get(player)
+                .map(|c| self.view_class(player, c, spell_record, class_record, icons))
                 .map(|elem| container(elem).padding(16).height(Length::Fill));
 
-            let items = column![combo,]
+            let mode = pick_list(SpellMode::ALL, Some(self.mode), Message::ModeSelected);
+
+            let block = checkbox(
+                "Block spells past the class's limits",
+                self.block_over_limit,
+            )
+            .on_toggle(Message::BlockOverLimitToggled);
+
+            let items = column![row![combo, mode, block].spacing(16)]
                 .push_maybe(class)
                 .push(self.button_bar())
                 .padding(8.0);
@@ -0,0 +1,267 @@
+use iced::{
+    Length,
+    widget::{
+        Column, button, checkbox, column, horizontal_rule, horizontal_space, row, scrollable, text,
+        text_input,
+    },
+};
+use nwn_lib::globals::Globals;
+use nwn_model::roster::Roster;
+
+use crate::{SaveFile, show_error_popup};
+
+/// Influence variables of the original campaign's companions by roster tag. The
+/// campaign scripts (`ginc_companion`) store them as `00_nInfluence` and the tag.
+const COMPANION_INFLUENCE: [(&str, &str, &str); 11] = [
+    ("ammon_jerro", "Ammon Jerro", "00_nInfluenceammon_jerro"),
+    ("bishop", "Bishop", "00_nInfluencebishop"),
+    ("casavir", "Casavir", "00_nInfluencecasavir"),
+    ("elanee", "Elanee", "00_nInfluenceelanee"),
+    ("grobnar", "Grobnar", "00_nInfluencegrobnar"),
+    ("khelgar", "Khelgar", "00_nInfluencekhelgar"),
+    ("neeshka", "Neeshka", "00_nInfluenceneeshka"),
+    ("qara", "Qara", "00_nInfluenceqara"),
+    ("sand", "Sand", "00_nInfluencesand"),
+    ("shandra", "Shandra", "00_nInfluenceshandra"),
+    ("zhjaeve", "Zhjaeve", "00_nInfluencezhjaeve"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    FilterChanged(String),
+    IntegerChanged(usize, i32),
+    BooleanChanged(usize, bool),
+    FloatChanged(usize, String),
+    StringChanged(usize, String),
+    Close,
+}
+
+type Element<'a> = iced::Element<'a, Message>;
+
+#[derive(Debug, Clone)]
+struct Companion {
+    name: String,
+    /// Index into [`Globals::integers`]
+    influence: usize,
+}
+
+/// Companions of [`COMPANION_INFLUENCE`] whose variable the save has, named like
+/// in the roster when they're in it
+fn find_companions(globals: &Globals, roster: Option<&Roster>) -> Vec<Companion> {
+    COMPANION_INFLUENCE
+        .iter()
+        .filter_map(|(tag, name, variable)| {
+            let influence = globals
+                .integers
+                .iter()
+                .position(|e| e.name.eq_ignore_ascii_case(variable))?;
+
+            let name = roster
+                .and_then(|x| x.members.iter().find(|x| x.name.eq_ignore_ascii_case(tag)))
+                .map(|x| x.char_name.clone())
+                .filter(|x| !x.is_empty())
+                .unwrap_or_else(|| name.to_string());
+
+            Some(Companion { name, influence })
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub active: bool,
+    globals: Globals,
+    /// Text of each float input, kept separately so partial input like `1.` can be typed
+    float_inputs: Vec<String>,
+    filter: String,
+    companions: Vec<Companion>,
+}
+impl State {
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    pub fn open(&mut self, save_file: &SaveFile, roster: Option<&Roster>) {
+        let globals = match save_file.save.globals() {
+            Some(Ok(globals)) => globals,
+            Some(Err(e)) => return show_error_popup(format!("Failed to read globals.xml: {e}")),
+            None => return show_error_popup("Save has no globals.xml"),
+        };
+
+        self.active = true;
+        self.float_inputs = globals.floats.iter().map(|e| e.value.to_string()).collect();
+        self.companions = find_companions(&globals, roster);
+        self.globals = globals;
+    }
+
+    pub fn update(&mut self, msg: Message, save_file: &mut SaveFile) {
+        match msg {
+            Message::FilterChanged(filter) => {
+                self.filter = filter;
+                return;
+            }
+            Message::Close => {
+                self.close();
+                return;
+            }
+            Message::IntegerChanged(i, value) => {
+                if let Some(e) = self.globals.integers.get_mut(i) {
+                    e.value = value;
+                }
+            }
+            Message::BooleanChanged(i, value) => {
+                if let Some(e) = self.globals.booleans.get_mut(i) {
+                    e.value = value;
+                }
+            }
+            Message::FloatChanged(i, input) => {
+                if let Some(e) = self.globals.floats.get_mut(i)
+                    && let Ok(value) = input.trim().parse()
+                {
+                    e.value = value;
+                }
+
+                if let Some(x) = self.float_inputs.get_mut(i) {
+                    *x = input;
+                }
+            }
+            Message::StringChanged(i, value) => {
+                if let Some(e) = self.globals.strings.get_mut(i) {
+                    e.value = value;
+                }
+            }
+        }
+
+        save_file.save.set_globals(&self.globals);
+    }
+
+    fn matches_filter(&self, name: &str) -> bool {
+        self.filter.is_empty() || name.to_lowercase().contains(&self.filter.to_lowercase())
+    }
+
+    fn integer_input(&self, i: usize) -> Element<'_> {
+        let value = &self.globals.integers[i].value;
+
+        iced_aw::number_input(value, i32::MIN..=i32::MAX, move |x| {
+            Message::IntegerChanged(i, x)
+        })
+        .ignore_buttons(true)
+        .width(Length::Fixed(128.0))
+        .into()
+    }
+
+    fn view_companions(&self) -> Element<'_> {
+        let rows = self.companions.iter().map(|companion| {
+            let i = companion.influence;
+            let entry = &self.globals.integers[i];
+
+            row![
+                text(&companion.name).width(Length::FillPortion(1)),
+                text(&entry.name).width(Length::FillPortion(1)),
+                self.integer_input(i),
+            ]
+            .spacing(8)
+            .into()
+        });
+
+        column![
+            text("Companion Influence").size(20),
+            Column::with_children(rows).spacing(4)
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    fn view_globals(&self) -> Element<'_> {
+        let name_text = |name| text(name).width(Length::Fill);
+
+        let integers = self
+            .globals
+            .integers
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.matches_filter(&e.name))
+            .map(|(i, e)| row![name_text(e.name.clone()), self.integer_input(i)].into());
+
+        let booleans = self
+            .globals
+            .booleans
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.matches_filter(&e.name))
+            .map(|(i, e)| {
+                row![
+                    name_text(e.name.clone()),
+                    checkbox("", e.value).on_toggle(move |x| Message::BooleanChanged(i, x)),
+                ]
+                .into()
+            });
+
+        let floats = self
+            .globals
+            .floats
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.matches_filter(&e.name))
+            .map(|(i, e)| {
+                row![
+                    name_text(e.name.clone()),
+                    text_input("0.0", &self.float_inputs[i])
+                        .on_input(move |x| Message::FloatChanged(i, x))
+                        .width(Length::Fixed(128.0)),
+                ]
+                .into()
+            });
+
+        let strings = self
+            .globals
+            .strings
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.matches_filter(&e.name))
+            .map(|(i, e)| {
+                row![
+                    name_text(e.name.clone()),
+                    text_input("", &e.value)
+                        .on_input(move |x| Message::StringChanged(i, x))
+                        .width(Length::FillPortion(1)),
+                ]
+                .into()
+            });
+
+        column![
+            text("Integers").size(20),
+            Column::with_children(integers).spacing(4),
+            text("Booleans").size(20),
+            Column::with_children(booleans).spacing(4),
+            text("Floats").size(20),
+            Column::with_children(floats).spacing(4),
+            text("Strings").size(20),
+            Column::with_children(strings).spacing(4),
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<'_> {
+        let header = row![
+            text_input("Filter", &self.filter).on_input(Message::FilterChanged),
+            horizontal_space().width(Length::Fixed(16.0)),
+            button("Close").on_press(Message::Close),
+        ]
+        .spacing(8);
+
+        let mut body = column![].spacing(16).padding(iced::Padding {
+            right: 16.0,
+            ..Default::default()
+        });
+
+        if !self.companions.is_empty() {
+            body = body.push(self.view_companions()).push(horizontal_rule(2));
+        }
+
+        body = body.push(self.view_globals());
+
+        super::bordered_padded(column![header, scrollable(body)].spacing(16)).into()
+    }
+}
@@ -1,4 +1,6 @@
 pub mod character;
+pub mod globals;
+pub mod portrait;
 pub mod save_file;
 pub mod search_window;
 pub mod select_file;
@@ -10,9 +12,12 @@ use std::{
 };
 
 use crate::error::Error;
+use nwn_model::error::Error as ModelError;
 
 pub use self::{
     character::{Message as CharacterMessage, State as CharacterState},
+    globals::{Message as GlobalsMessage, State as GlobalsState},
+    portrait::{Message as PortraitMessage, State as PortraitState},
     save_file::{Message as SaveMessage, State as SaveState},
     select_file::{Message as SelectFileMessage, State as SelectFileState},
     settings::{Message as SettingsMessage, State as SettingsState},
@@ -71,7 +76,8 @@ impl Date {
         hour: &str,
         minute: &str,
     ) -> Result<Self, Error> {
-        let to_parse_error = |e: std::num::ParseIntError| Error::ParseError(e.to_string());
+        let to_parse_error =
+            |e: std::num::ParseIntError| Error::from(ModelError::ParseError(e.to_string()));
 
         Ok(Self {
             day: day.parse().map_err(to_parse_error)?,
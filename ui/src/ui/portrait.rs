@@ -0,0 +1,150 @@
+use iced::{
+    Length, Task,
+    widget::{Image, button, column, image::Handle, row, scrollable, text, text_input},
+};
+use rayon::prelude::*;
+use std::path::Path;
+
+use crate::{
+    portrait::{PortraitRecord, import_portrait, load_portrait},
+    show_error_popup,
+};
+
+const THUMBNAIL_SIZE: u32 = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    FilterChanged(String),
+    Selected(String),
+    Import,
+    Close,
+    /// Thumbnails decoded off the UI thread after opening
+    Loaded(Vec<(String, Handle)>),
+}
+
+type Element<'a> = iced::Element<'a, Message>;
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub active: bool,
+    filter: String,
+    /// ResRef and thumbnail of each portrait
+    portraits: Vec<(String, Handle)>,
+    loading: bool,
+}
+impl State {
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    pub fn open(
+        &mut self,
+        record: &PortraitRecord,
+        user_dir: Option<&Path>,
+    ) -> Task<crate::Message> {
+        self.active = true;
+        self.loading = true;
+        self.portraits.clear();
+
+        let paths = record.all(user_dir);
+        let thumbnails = async move {
+            paths
+                .into_par_iter()
+                .filter_map(|(name, path)| {
+                    Some((name, load_portrait(&path, Some(THUMBNAIL_SIZE)).ok()?))
+                })
+                .collect()
+        };
+        Task::perform(thumbnails, |x| {
+            crate::Message::Portraits(Message::Loaded(x))
+        })
+    }
+
+    /// Returns the ResRef of the chosen portrait
+    pub fn update(&mut self, msg: Message, user_dir: Option<&Path>) -> Option<String> {
+        match msg {
+            Message::FilterChanged(filter) => {
+                self.filter = filter;
+                None
+            }
+            Message::Selected(name) => {
+                self.close();
+                Some(name)
+            }
+            Message::Import => {
+                let Some(user_dir) = user_dir else {
+                    show_error_popup("Couldn't find the user portraits folder");
+                    return None;
+                };
+
+                let path = rfd::FileDialog::new()
+                    .add_filter("Image", &["png", "jpg", "jpeg", "tga", "bmp"])
+                    .pick_file()?;
+
+                match import_portrait(&path, user_dir) {
+                    Ok(name) => {
+                        self.close();
+                        Some(name)
+                    }
+                    Err(e) => {
+                        show_error_popup(format!("Failed to import {}: {e}", path.display()));
+                        None
+                    }
+                }
+            }
+            Message::Close => {
+                self.close();
+                None
+            }
+            Message::Loaded(portraits) => {
+                // Thumbnails of a gallery that was closed meanwhile
+                if self.active {
+                    self.portraits = portraits;
+                    self.loading = false;
+                }
+                None
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_> {
+        let header = row![
+            text_input("Filter", &self.filter).on_input(Message::FilterChanged),
+            button("Import Image").on_press(Message::Import),
+            button("Close").on_press(Message::Close),
+        ]
+        .spacing(8);
+
+        let filter = self.filter.to_lowercase();
+        let portraits = self
+            .portraits
+            .iter()
+            .filter(|(name, _)| name.contains(&filter))
+            .map(|(name, handle)| {
+                let content = column![
+                    Image::<Handle>::new(handle.clone())
+                        .width(THUMBNAIL_SIZE as f32)
+                        .height(THUMBNAIL_SIZE as f32),
+                    text(name).size(12),
+                ]
+                .align_x(iced::Alignment::Center)
+                .width(Length::Fixed(96.0));
+
+                button(content)
+                    .style(button::text)
+                    .on_press(Message::Selected(name.clone()))
+                    .into()
+            });
+
+        let grid = iced::widget::Row::with_children(portraits)
+            .spacing(8)
+            .wrap();
+
+        let body: Element = match self.loading {
+            true => text("Loading portraits...").into(),
+            false => scrollable(grid).width(Length::Fill).into(),
+        };
+
+        super::bordered_padded(column![header, body].spacing(16)).into()
+    }
+}
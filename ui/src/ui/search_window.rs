@@ -1,17 +1,23 @@
 use crate::{
-    feat::{Feat, FeatId, FeatRecord},
-    ids::class::Class,
-    spell::{Spell, SpellId, SpellRecord},
+    icons::IconCache,
     ui::{HoverableEvent, HoverableState, hoverable},
 };
 use iced::{
     Length,
     widget::{
-        Column, Image, button, column, container, horizontal_rule, horizontal_space, row,
-        scrollable, text, text_input,
+        Column, Image, button, column, container, horizontal_rule, horizontal_space, image::Handle,
+        row, scrollable, text, text_input,
     },
 };
 use itertools::Itertools;
+use nwn_model::{
+    class::ClassRecord,
+    feat::{Feat, FeatId, FeatRecord},
+    ids::class::Class,
+    player::Player,
+    skill::SkillRecord,
+    spell::{Spell, SpellId, SpellRecord},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Message {
@@ -23,9 +29,20 @@ pub enum Message {
 
 type Element<'a> = iced::Element<'a, Message>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Character a feat search checks `feat.2da` requirements against
+#[derive(Debug, Clone, Copy)]
+pub struct FeatCheck<'a> {
+    pub player: &'a Player,
+    pub class_record: &'a ClassRecord,
+    pub skill_record: &'a SkillRecord,
+}
+
+#[derive(Debug, Clone)]
 pub enum SearchKind<'a> {
-    Feats(&'a FeatRecord),
+    Feats {
+        feat_record: &'a FeatRecord,
+        check: Option<FeatCheck<'a>>,
+    },
     Spells {
         spell_record: &'a SpellRecord,
         class: Class,
@@ -85,10 +102,25 @@ impl State {
     fn view_feats<'a>(
         &self,
         feats: impl Iterator<Item = (FeatId, &'a Feat)>,
+        feat_record: &FeatRecord,
+        check: Option<FeatCheck<'_>>,
+        icons: &IconCache,
     ) -> Column<'a, Message> {
         let elements = feats
             .enumerate()
-            .map(|(index, (feat_id, feat))| view_feat(feat_id, feat, index, self.hoverable_state))
+            .map(|(index, (feat_id, feat))| {
+                let icon = icons.feats.get(&feat_id);
+                let unmet = check
+                    .map(|x| {
+                        x.player
+                            .unmet_prereqs(x.class_record, feat_id, &feat.prereqs)
+                            .iter()
+                            .map(|prereq| prereq.describe(feat_record, x.skill_record))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                view_feat(feat_id, feat, icon, unmet, index, self.hoverable_state)
+            })
             .intersperse_with(|| horizontal_rule(2).into());
 
         Column::from_iter(elements).width(Length::Fill)
@@ -97,11 +129,15 @@ impl State {
     fn view_spells<'a>(
         &self,
         spells: impl Iterator<Item = (SpellId, &'a Spell)>,
+        icons: &IconCache,
     ) -> Column<'a, Message> {
         let elements = spells
             .into_iter()
             .enumerate()
-            .map(|(index, (id, spell))| view_spell(id, spell, index, self.hoverable_state))
+            .map(|(index, (id, spell))| {
+                let icon = icons.spells.get(&id);
+                view_spell(id, spell, icon, index, self.hoverable_state)
+            })
             .intersperse_with(|| horizontal_rule(2).into())
             .collect();
 
@@ -110,20 +146,22 @@ impl State {
         elements.width(Length::Fill)
     }
 
-    pub fn view<'a>(&self, kind: SearchKind<'a>) -> Element<'a> {
+    pub fn view<'a>(&self, kind: SearchKind<'a>, icons: &IconCache) -> Element<'a> {
         let search_bar = text_input("Search...", &self.search_text).on_input(Message::TextChanged);
 
         let body: Element<'a> = match kind {
-            SearchKind::Feats(record) => {
-                let feats = record.feats.iter().map(|(id, feat)| (*id, feat));
+            SearchKind::Feats { feat_record, check } => {
+                let feats = feat_record.feats.iter().map(|(id, feat)| (*id, feat));
 
                 if self.search_text.len() < 3 {
                     Column::new()
                 } else {
                     let search = self.search_text.to_ascii_lowercase();
-                    self.view_feats(feats.filter(|(_id, feat)| {
+                    let feats = feats.filter(|(_id, feat)| {
                         feat.name.data.to_ascii_lowercase().contains(&search)
-                    }))
+                    });
+
+                    self.view_feats(feats, feat_record, check, icons)
                 }
                 .into()
             }
@@ -141,14 +179,14 @@ impl State {
                     .map(|(id, spell)| (*id, *spell));
 
                 if self.search_text.is_empty() {
-                    self.view_spells(spells).into()
+                    self.view_spells(spells, icons).into()
                 } else {
                     let search = self.search_text.to_ascii_lowercase();
                     let spells = spells.filter(|(_id, spell)| {
                         spell.name.data.to_ascii_lowercase().contains(&search)
                     });
 
-                    self.view_spells(spells).into()
+                    self.view_spells(spells, icons).into()
                 }
             }
         };
@@ -170,13 +208,16 @@ impl State {
     }
 }
 
+/// `unmet` requirements are listed under the description
 fn view_feat(
     feat_id: FeatId,
     feat: &Feat,
+    icon: Option<&Handle>,
+    unmet: Vec<String>,
     index: usize,
     hoverable_state: HoverableState,
 ) -> Element<'static> {
-    let icon: Element<'_> = match &feat.icon {
+    let icon: Element<'_> = match icon {
         Some(icon) => Image::new(icon).width(40).height(40).into(),
         None => horizontal_space().width(40).into(),
     };
@@ -190,10 +231,17 @@ fn view_feat(
         .unwrap_or_default()
         .to_string();
 
-    let item = row![icon, text(name).width(120), text(desc),]
-        .width(Length::Fill)
-        .padding(16)
-        .spacing(16);
+    let unmet = (!unmet.is_empty())
+        .then(|| text(format!("Requires {}", unmet.join(", "))).style(text::danger));
+
+    let item = row![
+        icon,
+        text(name).width(120),
+        column![text(desc)].push_maybe(unmet).spacing(4),
+    ]
+    .width(Length::Fill)
+    .padding(16)
+    .spacing(16);
 
     hoverable(item, index, hoverable_state, |evt| {
         Message::HoverableEvent((feat_id, evt))
@@ -205,10 +253,11 @@ fn view_feat(
 fn view_spell(
     spell_id: SpellId,
     spell: &Spell,
+    icon: Option<&Handle>,
     index: usize,
     hoverable_state: HoverableState,
 ) -> Element<'static> {
-    let icon: Element<'_> = match &spell.icon {
+    let icon: Element<'_> = match icon {
         Some(handle) => Image::new(handle).width(40).height(40).into(),
         None => horizontal_space().width(40).into(),
     };
@@ -41,12 +41,8 @@ impl State {
             Message::HoverableEvent(e) => e.update(&mut self.hoverable_state),
             Message::Open(idx) => {
                 if let Some(entry) = self.save_entries.get(idx) {
-                    let mut file_path = entry.path.join("resgff.zip");
-                    if !file_path.exists() {
-                        file_path = entry.path.join("playerlist.ifo");
-                    }
-
-                    let selected_task = Task::done(crate::Message::FileSelected(file_path));
+                    let selected_task =
+                        Task::done(crate::Message::FileSelected(entry.path.clone()));
                     let close_task = Task::done(crate::Message::FileSelector(Message::Close));
                     return selected_task.chain(close_task);
                 }
@@ -1,19 +1,30 @@
 use crate::{
-    Tlk, error::Error, feat::FeatRecord, popup_opt, popup_panic, show_error_popup,
-    spell::SpellRecord, two_d_array::FileReader2DA,
+    error::Error, icons::IconCache, popup_opt, popup_panic, portrait::PortraitRecord,
+    show_error_popup,
 };
 use cfg_if::cfg_if;
 use iced::{
     Length,
     widget::{button, column, horizontal_space, row, text, text_input, vertical_space},
 };
-use serde::{Deserialize, Serialize};
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::BufReader,
-    path::{Path, PathBuf},
+use nwn_model::{
+    Tlk,
+    class::ClassRecord,
+    domain::DomainRecord,
+    error::Error as ModelError,
+    feat::FeatRecord,
+    item::BaseItemRecord,
+    item_property::ItemPropertyRecord,
+    player::legality::Rules,
+    race::RaceRecord,
+    resources::{get_icon_paths, get_tlk_file},
+    school::SchoolRecord,
+    skill::SkillRecord,
+    spell::SpellRecord,
+    two_d_array::FileReader2DA,
 };
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PickDirMode {
@@ -109,70 +120,6 @@ fn path_to_string(path: Option<&Path>) -> String {
         .unwrap_or_default()
 }
 
-pub type IconName = String;
-pub type IconPath = PathBuf;
-
-pub(crate) fn read_dir_recursive(path: &std::path::Path) -> impl Iterator<Item = PathBuf> {
-    use std::collections::VecDeque;
-    use std::fs::DirEntry;
-    use std::path::Path;
-
-    fn read_dir(dir: impl AsRef<Path>) -> impl Iterator<Item = DirEntry> {
-        dir.as_ref().read_dir().unwrap().filter_map(Result::ok)
-    }
-
-    let mut stack = VecDeque::from_iter(read_dir(path));
-
-    std::iter::from_fn(move || {
-        while let Some(x) = stack.pop_front() {
-            let metadata = x.metadata().unwrap();
-            if metadata.is_file() {
-                return Some(x.path());
-            } else if metadata.is_dir() {
-                read_dir(x.path()).for_each(|x| stack.push_front(x));
-            }
-        }
-
-        None
-    })
-}
-
-fn get_icon_paths(game_dir: &Path) -> HashMap<IconName, IconPath> {
-    read_dir_recursive(game_dir)
-        .filter_map(|x| {
-            let name = x
-                .file_stem()
-                .and_then(|stem| stem.to_str())
-                .map(|x| x.to_string())?;
-            Some((name, x))
-        })
-        .collect()
-}
-
-fn get_tlk_file(game_dir: &Path) -> Result<Tlk, Error> {
-    let mut read_dir = game_dir.read_dir()?;
-
-    let file_path = read_dir.find_map(|x| {
-        if let Ok(dir) = x
-            && let Ok(m) = dir.metadata()
-            && m.is_file()
-            && dir.file_name().eq_ignore_ascii_case("dialog.tlk")
-        {
-            return Some(dir.path());
-        }
-
-        None
-    });
-
-    match file_path {
-        Some(p) => {
-            let f = File::open(p)?;
-            Tlk::read(BufReader::new(f)).map_err(Error::LibError)
-        }
-        None => Err(Error::MissingDialogFile(game_dir.into())),
-    }
-}
-
 #[derive(Debug)]
 pub struct GameResources {
     pub game_dir: PathBuf,
@@ -180,14 +127,23 @@ pub struct GameResources {
     // pub icon_paths: HashMap<IconName, IconPath>,
     pub feat_record: FeatRecord,
     pub spell_record: SpellRecord,
+    pub skill_record: SkillRecord,
+    pub class_record: ClassRecord,
+    pub race_record: RaceRecord,
+    pub domain_record: DomainRecord,
+    pub school_record: SchoolRecord,
+    pub base_item_record: BaseItemRecord,
+    pub item_property_record: ItemPropertyRecord,
+    pub portrait_record: PortraitRecord,
+    pub icons: IconCache,
     pub file_reader: FileReader2DA,
 }
 impl GameResources {
     fn load(game_dir: &Path) -> Result<Self, Error> {
         let tlk = get_tlk_file(game_dir)?;
-        let icon_paths = get_icon_paths(game_dir);
+        let icon_paths = get_icon_paths(game_dir)?;
 
-        let reader = FileReader2DA::new(game_dir)?;
+        let mut reader = FileReader2DA::new(game_dir)?;
 
         let (feat_record, spell_record) = std::thread::scope(|s| {
             let a = s.spawn(|| FeatRecord::new(&tlk, game_dir, &icon_paths));
@@ -198,21 +154,59 @@ impl GameResources {
 
             match (a, b) {
                 (Ok(a), Ok(b)) => Ok((a, b)),
-                (Err(a), Err(b)) => Err(Error::Aggregate(vec![a, b])),
+                (Err(a), Err(b)) => Err(ModelError::Aggregate(vec![a, b])),
                 (Err(a), _) => Err(a),
                 (_, Err(b)) => Err(b),
             }
         })?;
 
+        let skill_record = SkillRecord::new(&tlk, &mut reader, &icon_paths)?;
+        let class_record = ClassRecord::new(&mut reader)?;
+        let race_record = RaceRecord::new(&tlk, &mut reader)?;
+        let domain_record = DomainRecord::new(&tlk, &mut reader)?;
+        let school_record = SchoolRecord::new(&tlk, &mut reader)?;
+        let base_item_record = BaseItemRecord::new(&tlk, &mut reader, &icon_paths)?;
+        let item_property_record = ItemPropertyRecord::new(&tlk, &mut reader)?;
+        let icons = IconCache::new(
+            &feat_record,
+            &spell_record,
+            &skill_record,
+            &base_item_record,
+        );
+
         Ok(Self {
             game_dir: game_dir.into(),
             tlk,
             // icon_paths,
             feat_record,
             spell_record,
+            skill_record,
+            class_record,
+            race_record,
+            domain_record,
+            school_record,
+            base_item_record,
+            item_property_record,
+            portrait_record: PortraitRecord::new(&icon_paths),
+            icons,
             file_reader: reader,
         })
     }
+
+    /// Tables the character rules checks need
+    pub fn rules(&self) -> Rules<'_> {
+        Rules {
+            classes: &self.class_record,
+            skills: &self.skill_record,
+            feats: &self.feat_record,
+            races: &self.race_record,
+            domains: &self.domain_record,
+            schools: &self.school_record,
+            spells: &self.spell_record,
+            base_items: &self.base_item_record,
+            item_properties: &self.item_property_record,
+        }
+    }
 }
 
 #[derive(Debug, Default)]